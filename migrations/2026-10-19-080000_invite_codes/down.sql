@@ -0,0 +1,2 @@
+DROP    TABLE   IF  EXISTS invite_code_redemptions          CASCADE;
+DROP    TABLE   IF  EXISTS invite_codes                     CASCADE;
\ No newline at end of file
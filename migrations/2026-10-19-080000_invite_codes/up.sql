@@ -0,0 +1,58 @@
+CREATE  TABLE   IF  NOT EXISTS  "invite_codes"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   code                                                            varchar (   32 )                NOT NULL
+,   group_name                                                      varchar (  255 )                NOT NULL
+,   usage_limit                                                     integer                         NOT NULL
+,   valid_from                                                      timestamp   with    time zone       NULL
+,   valid_until                                                     timestamp   with    time zone       NULL
+,   created_by                                                      varchar (  255 )                NOT NULL
+,   created_at                                                      timestamp   with    time zone   NOT NULL    DEFAULT now()
+
+,   CONSTRAINT  "PK_invite_codes"                                   PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "UK_invite_codes#code"                              UNIQUE
+    (
+        code
+    )
+);
+
+CREATE  TABLE   IF  NOT EXISTS  "invite_code_redemptions"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   invite_code_id                                                  integer                         NOT NULL
+,   token_id                                                        integer                             NULL
+,   redeemed_at                                                     timestamp   with    time zone   NOT NULL    DEFAULT now()
+
+,   CONSTRAINT  "PK_invite_code_redemptions"                        PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "UK_invite_code_redemptions#token_id"               UNIQUE
+    (
+        token_id
+    )
+
+,   CONSTRAINT  "FK_invite_code_redemptions_invite_codes"           FOREIGN KEY
+    (
+        invite_code_id
+    )
+    REFERENCES  "invite_codes"
+    (
+        id
+    )
+
+,   CONSTRAINT  "FK_invite_code_redemptions_tokens"                 FOREIGN KEY
+    (
+        token_id
+    )
+    REFERENCES  "tokens"
+    (
+        id
+    )
+    ON DELETE SET NULL
+);
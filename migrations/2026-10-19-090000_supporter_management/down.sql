@@ -0,0 +1,9 @@
+ALTER   TABLE   "supporters"
+    DROP COLUMN IF  EXISTS  end_date
+,   DROP COLUMN IF  EXISTS  start_date
+,   DROP COLUMN IF  EXISTS  sort_priority
+;
+
+ALTER   TABLE   "supporter_types"
+    DROP COLUMN IF  EXISTS  sort_priority
+;
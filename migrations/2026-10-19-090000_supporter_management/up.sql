@@ -0,0 +1,9 @@
+ALTER   TABLE   "supporter_types"
+    ADD COLUMN  IF  NOT EXISTS  sort_priority                       integer                         NOT NULL    DEFAULT 0
+;
+
+ALTER   TABLE   "supporters"
+    ADD COLUMN  IF  NOT EXISTS  sort_priority                       integer                         NOT NULL    DEFAULT 0
+,   ADD COLUMN  IF  NOT EXISTS  start_date                          date                                NULL
+,   ADD COLUMN  IF  NOT EXISTS  end_date                            date                                NULL
+;
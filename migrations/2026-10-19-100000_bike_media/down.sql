@@ -0,0 +1,2 @@
+DROP    TABLE   IF  EXISTS bike_media_translatables         CASCADE;
+DROP    TABLE   IF  EXISTS bike_media                       CASCADE;
\ No newline at end of file
@@ -0,0 +1,57 @@
+CREATE  TABLE   IF  NOT EXISTS  "bike_media"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   bike_id                                                         integer                         NOT NULL
+,   position                                                        integer                         NOT NULL    DEFAULT 0
+,   url                                                             varchar ( 1024 )                NOT NULL
+,   width                                                           integer                         NOT NULL
+,   height                                                          integer                         NOT NULL
+,   thumbnail_url                                                   varchar ( 1024 )                NOT NULL
+,   thumbnail_width                                                 integer                         NOT NULL
+,   thumbnail_height                                                integer                         NOT NULL
+,   created_at                                                      timestamp   with    time zone   NOT NULL    DEFAULT now()
+
+,   CONSTRAINT  "PK_bike_media"                                     PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "FK_bike_media_bikes"                               FOREIGN KEY
+    (
+        bike_id
+    )
+    REFERENCES  "bikes"
+    (
+        id
+    )
+    ON DELETE CASCADE
+);
+
+CREATE  TABLE   IF  NOT EXISTS  "bike_media_translatables"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   bike_media_id                                                   integer                         NOT NULL
+,   locale                                                          varchar (    5 )                NOT NULL
+,   alt_text                                                        varchar ( 1024 )                NOT NULL
+
+,   CONSTRAINT  "PK_bike_media_translatables"                       PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "UK_bike_media_translatables#bike_media_id#locale"  UNIQUE
+    (
+        bike_media_id
+    ,   locale
+    )
+
+,   CONSTRAINT  "FK_bike_media_translatables_bike_media"            FOREIGN KEY
+    (
+        bike_media_id
+    )
+    REFERENCES  "bike_media"
+    (
+        id
+    )
+    ON DELETE CASCADE
+);
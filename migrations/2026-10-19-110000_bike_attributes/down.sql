@@ -0,0 +1,5 @@
+DROP    TABLE   IF  EXISTS bike_attribute_values            CASCADE;
+DROP    TABLE   IF  EXISTS bike_attribute_option_translatables CASCADE;
+DROP    TABLE   IF  EXISTS bike_attribute_options           CASCADE;
+DROP    TABLE   IF  EXISTS bike_attribute_translatables     CASCADE;
+DROP    TABLE   IF  EXISTS bike_attributes                  CASCADE;
\ No newline at end of file
@@ -0,0 +1,160 @@
+CREATE  TABLE   IF  NOT EXISTS  "bike_attributes"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   key                                                             varchar (   64 )                NOT NULL
+,   value_type                                                      varchar (   16 )                NOT NULL
+,   unit                                                            varchar (   16 )                    NULL
+,   sort_priority                                                   integer                         NOT NULL    DEFAULT 0
+
+,   CONSTRAINT  "PK_bike_attributes"                                PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "UK_bike_attributes#key"                            UNIQUE
+    (
+        key
+    )
+
+,   CONSTRAINT  "CK_bike_attributes#value_type"                     CHECK
+    (
+        value_type IN ( 'number', 'boolean', 'enum' )
+    )
+);
+
+CREATE  TABLE   IF  NOT EXISTS  "bike_attribute_translatables"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   bike_attribute_id                                               integer                         NOT NULL
+,   locale                                                          varchar (    5 )                NOT NULL
+,   label                                                           varchar (  255 )                NOT NULL
+
+,   CONSTRAINT  "PK_bike_attribute_translatables"                   PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "UK_bike_attribute_translatables#bike_attribute_id#locale" UNIQUE
+    (
+        bike_attribute_id
+    ,   locale
+    )
+
+,   CONSTRAINT  "FK_bike_attribute_translatables_bike_attributes"   FOREIGN KEY
+    (
+        bike_attribute_id
+    )
+    REFERENCES  "bike_attributes"
+    (
+        id
+    )
+    ON DELETE CASCADE
+);
+
+CREATE  TABLE   IF  NOT EXISTS  "bike_attribute_options"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   bike_attribute_id                                               integer                         NOT NULL
+,   key                                                             varchar (   64 )                NOT NULL
+
+,   CONSTRAINT  "PK_bike_attribute_options"                         PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "UK_bike_attribute_options#bike_attribute_id#key"   UNIQUE
+    (
+        bike_attribute_id
+    ,   key
+    )
+
+,   CONSTRAINT  "FK_bike_attribute_options_bike_attributes"         FOREIGN KEY
+    (
+        bike_attribute_id
+    )
+    REFERENCES  "bike_attributes"
+    (
+        id
+    )
+    ON DELETE CASCADE
+);
+
+CREATE  TABLE   IF  NOT EXISTS  "bike_attribute_option_translatables"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   bike_attribute_option_id                                        integer                         NOT NULL
+,   locale                                                          varchar (    5 )                NOT NULL
+,   label                                                           varchar (  255 )                NOT NULL
+
+,   CONSTRAINT  "PK_bike_attribute_option_translatables"            PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "UK_bike_attribute_option_translatables#option#locale" UNIQUE
+    (
+        bike_attribute_option_id
+    ,   locale
+    )
+
+,   CONSTRAINT  "FK_bike_attribute_option_translatables_bike_attribute_options" FOREIGN KEY
+    (
+        bike_attribute_option_id
+    )
+    REFERENCES  "bike_attribute_options"
+    (
+        id
+    )
+    ON DELETE CASCADE
+);
+
+CREATE  TABLE   IF  NOT EXISTS  "bike_attribute_values"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   bike_id                                                         integer                         NOT NULL
+,   bike_attribute_id                                               integer                         NOT NULL
+,   number_value                                                    double  precision                   NULL
+,   boolean_value                                                   boolean                             NULL
+,   bike_attribute_option_id                                        integer                             NULL
+
+,   CONSTRAINT  "PK_bike_attribute_values"                          PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "UK_bike_attribute_values#bike_id#bike_attribute_id" UNIQUE
+    (
+        bike_id
+    ,   bike_attribute_id
+    )
+
+,   CONSTRAINT  "FK_bike_attribute_values_bikes"                    FOREIGN KEY
+    (
+        bike_id
+    )
+    REFERENCES  "bikes"
+    (
+        id
+    )
+    ON DELETE CASCADE
+
+,   CONSTRAINT  "FK_bike_attribute_values_bike_attributes"          FOREIGN KEY
+    (
+        bike_attribute_id
+    )
+    REFERENCES  "bike_attributes"
+    (
+        id
+    )
+    ON DELETE CASCADE
+
+,   CONSTRAINT  "FK_bike_attribute_values_bike_attribute_options"   FOREIGN KEY
+    (
+        bike_attribute_option_id
+    )
+    REFERENCES  "bike_attribute_options"
+    (
+        id
+    )
+    ON DELETE CASCADE
+);
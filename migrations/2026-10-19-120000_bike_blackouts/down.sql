@@ -0,0 +1 @@
+DROP    TABLE   IF  EXISTS bike_blackouts                   CASCADE;
\ No newline at end of file
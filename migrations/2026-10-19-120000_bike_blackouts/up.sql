@@ -0,0 +1,30 @@
+CREATE  TABLE   IF  NOT EXISTS  "bike_blackouts"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   bike_id                                                         integer                             NULL
+,   start_timestamp                                                 timestamp   with    time zone   NOT NULL
+,   end_timestamp                                                   timestamp   with    time zone   NOT NULL
+,   reason                                                          varchar (  255 )                    NULL
+,   created_by                                                      varchar (  255 )                NOT NULL
+,   created_at                                                      timestamp   with    time zone   NOT NULL    DEFAULT now()
+
+,   CONSTRAINT  "PK_bike_blackouts"                                 PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "CK_bike_blackouts#period"                          CHECK
+    (
+        start_timestamp < end_timestamp
+    )
+
+,   CONSTRAINT  "FK_bike_blackouts_bikes"                           FOREIGN KEY
+    (
+        bike_id
+    )
+    REFERENCES  "bikes"
+    (
+        id
+    )
+    ON DELETE CASCADE
+);
@@ -0,0 +1,11 @@
+DROP    TABLE   IF  EXISTS bike_status_changes              CASCADE;
+
+ALTER   TABLE   "rents"
+    DROP COLUMN IF  EXISTS  flag_reason
+,   DROP COLUMN IF  EXISTS  flagged_at
+;
+
+ALTER   TABLE   "bikes"
+    DROP CONSTRAINT IF  EXISTS  "CK_bikes#status"
+,   DROP COLUMN IF  EXISTS  status
+;
\ No newline at end of file
@@ -0,0 +1,37 @@
+ALTER   TABLE   "bikes"
+    ADD COLUMN  IF  NOT EXISTS  status                              varchar (   16 )                NOT NULL    DEFAULT 'active'
+,   ADD CONSTRAINT  "CK_bikes#status"                               CHECK
+    (
+        status IN ('active', 'maintenance', 'retired', 'hidden')
+    )
+;
+
+ALTER   TABLE   "rents"
+    ADD COLUMN  IF  NOT EXISTS  flagged_at                          timestamp   with    time zone       NULL
+,   ADD COLUMN  IF  NOT EXISTS  flag_reason                         varchar (  255 )                    NULL
+;
+
+CREATE  TABLE   IF  NOT EXISTS  "bike_status_changes"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   bike_id                                                         integer                         NOT NULL
+,   status                                                          varchar (   16 )                NOT NULL
+,   comment                                                         varchar (  255 )                    NULL
+,   changed_by                                                      varchar (  255 )                NOT NULL
+,   changed_at                                                      timestamp   with    time zone   NOT NULL    DEFAULT now()
+
+,   CONSTRAINT  "PK_bike_status_changes"                            PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "FK_bike_status_changes_bikes"                      FOREIGN KEY
+    (
+        bike_id
+    )
+    REFERENCES  "bikes"
+    (
+        id
+    )
+    ON DELETE CASCADE
+);
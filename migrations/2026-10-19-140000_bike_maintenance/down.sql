@@ -0,0 +1,2 @@
+DROP    TABLE   IF  EXISTS bike_work_orders                 CASCADE;
+DROP    TABLE   IF  EXISTS bike_service_intervals           CASCADE;
\ No newline at end of file
@@ -0,0 +1,76 @@
+CREATE  TABLE   IF  NOT EXISTS  "bike_service_intervals"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   bike_id                                                         integer                         NOT NULL
+,   title                                                           varchar (  255 )                NOT NULL
+,   interval_days                                                   integer                             NULL
+,   interval_rents                                                  integer                             NULL
+,   blocks_bookings                                                 boolean                         NOT NULL    DEFAULT false
+,   created_at                                                      timestamp   with    time zone   NOT NULL    DEFAULT now()
+,   notified_at                                                     timestamp   with    time zone       NULL
+
+,   CONSTRAINT  "PK_bike_service_intervals"                         PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "CK_bike_service_intervals#interval"                CHECK
+    (
+        (interval_days IS NOT NULL OR interval_rents IS NOT NULL)
+    AND COALESCE(interval_days, 1) > 0
+    AND COALESCE(interval_rents, 1) > 0
+    )
+
+,   CONSTRAINT  "FK_bike_service_intervals_bikes"                   FOREIGN KEY
+    (
+        bike_id
+    )
+    REFERENCES  "bikes"
+    (
+        id
+    )
+    ON DELETE CASCADE
+);
+
+CREATE  TABLE   IF  NOT EXISTS  "bike_work_orders"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   bike_id                                                         integer                         NOT NULL
+,   bike_service_interval_id                                        integer                             NULL
+,   "description"                                                   text                            NOT NULL
+,   parts                                                           text                                NULL
+,   cost_cents                                                      integer                             NULL
+,   performed_by                                                    varchar (  255 )                NOT NULL
+,   performed_at                                                    timestamp   with    time zone   NOT NULL    DEFAULT now()
+,   created_at                                                      timestamp   with    time zone   NOT NULL    DEFAULT now()
+
+,   CONSTRAINT  "PK_bike_work_orders"                               PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "CK_bike_work_orders#cost_cents"                    CHECK
+    (
+        cost_cents >= 0
+    )
+
+,   CONSTRAINT  "FK_bike_work_orders_bikes"                         FOREIGN KEY
+    (
+        bike_id
+    )
+    REFERENCES  "bikes"
+    (
+        id
+    )
+    ON DELETE CASCADE
+
+,   CONSTRAINT  "FK_bike_work_orders_bike_service_intervals"        FOREIGN KEY
+    (
+        bike_service_interval_id
+    )
+    REFERENCES  "bike_service_intervals"
+    (
+        id
+    )
+    ON DELETE SET NULL
+);
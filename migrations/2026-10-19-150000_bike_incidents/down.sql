@@ -0,0 +1,2 @@
+DROP    TABLE   IF  EXISTS bike_incident_photos             CASCADE;
+DROP    TABLE   IF  EXISTS bike_incidents                   CASCADE;
\ No newline at end of file
@@ -0,0 +1,68 @@
+CREATE  TABLE   IF  NOT EXISTS  "bike_incidents"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   rent_id                                                         integer                         NOT NULL
+,   bike_id                                                         integer                         NOT NULL
+,   category                                                        varchar (   32 )                NOT NULL
+,   "description"                                                   text                            NOT NULL
+,   reported_by                                                     varchar (  255 )                    NULL
+,   created_at                                                      timestamp   with    time zone   NOT NULL    DEFAULT now()
+
+,   CONSTRAINT  "PK_bike_incidents"                                 PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "CK_bike_incidents#category"                        CHECK
+    (
+        category IN ('flat_tyre', 'damage', 'crash', 'theft', 'other')
+    )
+
+,   CONSTRAINT  "FK_bike_incidents_rents"                           FOREIGN KEY
+    (
+        rent_id
+    )
+    REFERENCES  "rents"
+    (
+        id
+    )
+    ON DELETE CASCADE
+
+,   CONSTRAINT  "FK_bike_incidents_bikes"                           FOREIGN KEY
+    (
+        bike_id
+    )
+    REFERENCES  "bikes"
+    (
+        id
+    )
+    ON DELETE CASCADE
+);
+
+CREATE  TABLE   IF  NOT EXISTS  "bike_incident_photos"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   bike_incident_id                                                integer                         NOT NULL
+,   url                                                             varchar ( 1024 )                NOT NULL
+,   width                                                           integer                         NOT NULL
+,   height                                                          integer                         NOT NULL
+,   thumbnail_url                                                   varchar ( 1024 )                NOT NULL
+,   thumbnail_width                                                 integer                         NOT NULL
+,   thumbnail_height                                                integer                         NOT NULL
+,   created_at                                                      timestamp   with    time zone   NOT NULL    DEFAULT now()
+
+,   CONSTRAINT  "PK_bike_incident_photos"                           PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "FK_bike_incident_photos_bike_incidents"            FOREIGN KEY
+    (
+        bike_incident_id
+    )
+    REFERENCES  "bike_incidents"
+    (
+        id
+    )
+    ON DELETE CASCADE
+);
@@ -0,0 +1 @@
+DROP    TABLE   IF  EXISTS rent_feedbacks                   CASCADE;
\ No newline at end of file
@@ -0,0 +1,39 @@
+CREATE  TABLE   IF  NOT EXISTS  "rent_feedbacks"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   rent_id                                                         integer                         NOT NULL
+,   rating                                                          smallint                        NOT NULL
+,   "comment"                                                       text                                NULL
+,   distance_km                                                     double precision                    NULL
+,   created_at                                                      timestamp   with    time zone   NOT NULL    DEFAULT now()
+
+,   CONSTRAINT  "PK_rent_feedbacks"                                 PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "UK_rent_feedbacks#rent_id"                         UNIQUE
+    (
+        rent_id
+    )
+
+,   CONSTRAINT  "CK_rent_feedbacks#rating"                          CHECK
+    (
+        rating BETWEEN 1 AND 5
+    )
+
+,   CONSTRAINT  "CK_rent_feedbacks#distance_km"                     CHECK
+    (
+        distance_km >= 0
+    )
+
+,   CONSTRAINT  "FK_rent_feedbacks_rents"                           FOREIGN KEY
+    (
+        rent_id
+    )
+    REFERENCES  "rents"
+    (
+        id
+    )
+    ON DELETE CASCADE
+);
@@ -0,0 +1,4 @@
+DROP    TABLE   IF  EXISTS rent_accessories                 CASCADE;
+DROP    TABLE   IF  EXISTS accessory_bikes                  CASCADE;
+DROP    TABLE   IF  EXISTS accessory_translatables          CASCADE;
+DROP    TABLE   IF  EXISTS accessories                      CASCADE;
\ No newline at end of file
@@ -0,0 +1,127 @@
+CREATE  TABLE   IF  NOT EXISTS  "accessories"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   quantity                                                        integer                         NOT NULL    DEFAULT 1
+,   sort_priority                                                   integer                         NOT NULL    DEFAULT 0
+
+,   CONSTRAINT  "PK_accessories"                                    PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "CK_accessories#quantity"                           CHECK
+    (
+        quantity >= 0
+    )
+);
+
+CREATE  TABLE   IF  NOT EXISTS  "accessory_translatables"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   accessory_id                                                    integer                         NOT NULL
+,   locale                                                          varchar (    5 )                NOT NULL
+,   title                                                           varchar (  255 )                NOT NULL
+,   "description"                                                   text                                NULL
+
+,   CONSTRAINT  "PK_accessory_translatables"                        PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "UK_accessory_translatables#accessory_id#locale"    UNIQUE
+    (
+        accessory_id
+    ,   locale
+    )
+
+,   CONSTRAINT  "FK_accessory_translatables_accessories"            FOREIGN KEY
+    (
+        accessory_id
+    )
+    REFERENCES  "accessories"
+    (
+        id
+    )
+    ON DELETE CASCADE
+);
+
+CREATE  TABLE   IF  NOT EXISTS  "accessory_bikes"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   accessory_id                                                    integer                         NOT NULL
+,   bike_id                                                         integer                         NOT NULL
+
+,   CONSTRAINT  "PK_accessory_bikes"                                PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "UK_accessory_bikes#accessory_id#bike_id"           UNIQUE
+    (
+        accessory_id
+    ,   bike_id
+    )
+
+,   CONSTRAINT  "FK_accessory_bikes_accessories"                    FOREIGN KEY
+    (
+        accessory_id
+    )
+    REFERENCES  "accessories"
+    (
+        id
+    )
+    ON DELETE CASCADE
+
+,   CONSTRAINT  "FK_accessory_bikes_bikes"                          FOREIGN KEY
+    (
+        bike_id
+    )
+    REFERENCES  "bikes"
+    (
+        id
+    )
+    ON DELETE CASCADE
+);
+
+CREATE  TABLE   IF  NOT EXISTS  "rent_accessories"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   rent_id                                                         integer                         NOT NULL
+,   accessory_id                                                    integer                         NOT NULL
+,   quantity                                                        integer                         NOT NULL    DEFAULT 1
+
+,   CONSTRAINT  "PK_rent_accessories"                               PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "UK_rent_accessories#rent_id#accessory_id"          UNIQUE
+    (
+        rent_id
+    ,   accessory_id
+    )
+
+,   CONSTRAINT  "CK_rent_accessories#quantity"                      CHECK
+    (
+        quantity > 0
+    )
+
+,   CONSTRAINT  "FK_rent_accessories_rents"                         FOREIGN KEY
+    (
+        rent_id
+    )
+    REFERENCES  "rents"
+    (
+        id
+    )
+    ON DELETE CASCADE
+
+,   CONSTRAINT  "FK_rent_accessories_accessories"                   FOREIGN KEY
+    (
+        accessory_id
+    )
+    REFERENCES  "accessories"
+    (
+        id
+    )
+);
@@ -0,0 +1,7 @@
+ALTER   TABLE   "rents"
+    DROP CONSTRAINT IF  EXISTS  "UK_rents#token_id#bike_id"
+,   ADD CONSTRAINT  "UK_rents#token_id"                             UNIQUE
+    (
+        token_id
+    )
+;
\ No newline at end of file
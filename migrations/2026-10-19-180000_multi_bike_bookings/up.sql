@@ -0,0 +1,8 @@
+ALTER   TABLE   "rents"
+    DROP CONSTRAINT IF  EXISTS  "UK_rents#token_id"
+,   ADD CONSTRAINT  "UK_rents#token_id#bike_id"                     UNIQUE
+    (
+        token_id
+    ,   bike_id
+    )
+;
@@ -0,0 +1,10 @@
+ALTER   TABLE   "rents"
+    DROP CONSTRAINT IF  EXISTS  "UK_rents#token_id#bike_id#start_timestamp"
+,   ADD CONSTRAINT  "UK_rents#token_id#bike_id"                     UNIQUE
+    (
+        token_id
+    ,   bike_id
+    )
+;
+
+DROP    TABLE   IF  EXISTS rent_series                      CASCADE;
\ No newline at end of file
@@ -0,0 +1,67 @@
+CREATE  TABLE   IF  NOT EXISTS  "rent_series"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   token_id                                                        integer                         NOT NULL
+,   bike_id                                                         integer                         NOT NULL
+,   frequency                                                       varchar (   32 )                NOT NULL
+,   start_timestamp                                                 timestamp   with    time zone   NOT NULL
+,   end_timestamp                                                   timestamp   with    time zone   NOT NULL
+,   until_date                                                      date                                NULL
+,   occurrence_count                                                integer                             NULL
+,   created_at                                                      timestamp   with    time zone   NOT NULL    DEFAULT now()
+
+,   CONSTRAINT  "PK_rent_series"                                    PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "UK_rent_series#token_id"                           UNIQUE
+    (
+        token_id
+    )
+
+,   CONSTRAINT  "CK_rent_series#frequency"                          CHECK
+    (
+        frequency IN ('weekly', 'biweekly')
+    )
+
+,   CONSTRAINT  "CK_rent_series#end"                                CHECK
+    (
+        (until_date IS NULL) <> (occurrence_count IS NULL)
+    )
+
+,   CONSTRAINT  "CK_rent_series#occurrence_count"                   CHECK
+    (
+        occurrence_count > 0
+    )
+
+,   CONSTRAINT  "FK_rent_series_tokens"                             FOREIGN KEY
+    (
+        token_id
+    )
+    REFERENCES  "tokens"
+    (
+        id
+    )
+    ON DELETE CASCADE
+
+,   CONSTRAINT  "FK_rent_series_bikes"                              FOREIGN KEY
+    (
+        bike_id
+    )
+    REFERENCES  "bikes"
+    (
+        id
+    )
+    ON DELETE CASCADE
+);
+
+ALTER   TABLE   "rents"
+    DROP CONSTRAINT IF  EXISTS  "UK_rents#token_id#bike_id"
+,   ADD CONSTRAINT  "UK_rents#token_id#bike_id#start_timestamp"     UNIQUE
+    (
+        token_id
+    ,   bike_id
+    ,   start_timestamp
+    )
+;
@@ -0,0 +1,3 @@
+DROP    TABLE   IF  EXISTS rent_hold_accessories            CASCADE;
+DROP    TABLE   IF  EXISTS rent_holds                       CASCADE;
+DROP    TABLE   IF  EXISTS waitlist_entries                 CASCADE;
\ No newline at end of file
@@ -0,0 +1,153 @@
+CREATE  TABLE   IF  NOT EXISTS  "waitlist_entries"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   uuid                                                            uuid                            NOT NULL    DEFAULT gen_random_uuid ()
+,   bike_id                                                         integer                         NOT NULL
+,   start_timestamp                                                 timestamp   with    time zone   NOT NULL
+,   end_timestamp                                                   timestamp   with    time zone   NOT NULL
+,   token_id                                                        integer                         NOT NULL
+,   created_at                                                      timestamp   with    time zone   NOT NULL    DEFAULT now()
+,   notified_at                                                     timestamp   with    time zone       NULL
+
+,   CONSTRAINT  "PK_waitlist_entries"                               PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "UK_waitlist_entries#uuid"                          UNIQUE
+    (
+        uuid
+    )
+
+,   CONSTRAINT  "UK_waitlist_entries#token_id"                      UNIQUE
+    (
+        token_id
+    )
+
+,   CONSTRAINT  "CK_waitlist_entries#period"                        CHECK
+    (
+        start_timestamp < end_timestamp
+    )
+
+,   CONSTRAINT  "FK_waitlist_entries_bikes"                         FOREIGN KEY
+    (
+        bike_id
+    )
+    REFERENCES  "bikes"
+    (
+        id
+    )
+    ON DELETE CASCADE
+
+,   CONSTRAINT  "FK_waitlist_entries_tokens"                        FOREIGN KEY
+    (
+        token_id
+    )
+    REFERENCES  "tokens"
+    (
+        id
+    )
+    ON DELETE CASCADE
+);
+
+CREATE  TABLE   IF  NOT EXISTS  "rent_holds"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   uuid                                                            uuid                            NOT NULL    DEFAULT gen_random_uuid ()
+,   bike_id                                                         integer                         NOT NULL
+,   start_timestamp                                                 timestamp   with    time zone   NOT NULL
+,   end_timestamp                                                   timestamp   with    time zone   NOT NULL
+,   expires_at                                                      timestamp   with    time zone   NOT NULL
+,   waitlist_entry_id                                               integer                             NULL
+,   token_id                                                        integer                             NULL
+,   created_at                                                      timestamp   with    time zone   NOT NULL    DEFAULT now()
+
+,   CONSTRAINT  "PK_rent_holds"                                     PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "UK_rent_holds#uuid"                                UNIQUE
+    (
+        uuid
+    )
+
+,   CONSTRAINT  "CK_rent_holds#period"                              CHECK
+    (
+        start_timestamp < end_timestamp
+    )
+
+,   CONSTRAINT  "FK_rent_holds_bikes"                               FOREIGN KEY
+    (
+        bike_id
+    )
+    REFERENCES  "bikes"
+    (
+        id
+    )
+    ON DELETE CASCADE
+
+,   CONSTRAINT  "FK_rent_holds_waitlist_entries"                    FOREIGN KEY
+    (
+        waitlist_entry_id
+    )
+    REFERENCES  "waitlist_entries"
+    (
+        id
+    )
+    ON DELETE CASCADE
+
+,   CONSTRAINT  "FK_rent_holds_tokens"                              FOREIGN KEY
+    (
+        token_id
+    )
+    REFERENCES  "tokens"
+    (
+        id
+    )
+    ON DELETE CASCADE
+);
+
+CREATE  TABLE   IF  NOT EXISTS  "rent_hold_accessories"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   rent_hold_id                                                    integer                         NOT NULL
+,   accessory_id                                                    integer                         NOT NULL
+,   quantity                                                        integer                         NOT NULL    DEFAULT 1
+
+,   CONSTRAINT  "PK_rent_hold_accessories"                          PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "UK_rent_hold_accessories#rent_hold_id#accessory_id" UNIQUE
+    (
+        rent_hold_id
+    ,   accessory_id
+    )
+
+,   CONSTRAINT  "CK_rent_hold_accessories#quantity"                 CHECK
+    (
+        quantity > 0
+    )
+
+,   CONSTRAINT  "FK_rent_hold_accessories_rent_holds"               FOREIGN KEY
+    (
+        rent_hold_id
+    )
+    REFERENCES  "rent_holds"
+    (
+        id
+    )
+    ON DELETE CASCADE
+
+,   CONSTRAINT  "FK_rent_hold_accessories_accessories"              FOREIGN KEY
+    (
+        accessory_id
+    )
+    REFERENCES  "accessories"
+    (
+        id
+    )
+    ON DELETE CASCADE
+);
@@ -0,0 +1,6 @@
+ALTER   TABLE   "bikes"
+    DROP CONSTRAINT IF  EXISTS  "CK_bikes#buffer_after_minutes"
+,   DROP CONSTRAINT IF  EXISTS  "CK_bikes#buffer_before_minutes"
+,   DROP COLUMN IF  EXISTS  buffer_after_minutes
+,   DROP COLUMN IF  EXISTS  buffer_before_minutes
+;
\ No newline at end of file
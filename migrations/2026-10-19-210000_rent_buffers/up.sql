@@ -0,0 +1,12 @@
+ALTER   TABLE   "bikes"
+    ADD COLUMN  IF  NOT EXISTS  buffer_before_minutes               integer                             NULL
+,   ADD COLUMN  IF  NOT EXISTS  buffer_after_minutes                integer                             NULL
+,   ADD CONSTRAINT  "CK_bikes#buffer_before_minutes"                CHECK
+    (
+        buffer_before_minutes >= 0
+    )
+,   ADD CONSTRAINT  "CK_bikes#buffer_after_minutes"                 CHECK
+    (
+        buffer_after_minutes >= 0
+    )
+;
@@ -0,0 +1,4 @@
+ALTER   TABLE   "bikes"
+    DROP CONSTRAINT IF  EXISTS  "CK_bikes#booking_granularity"
+,   DROP COLUMN IF  EXISTS  booking_granularity
+;
\ No newline at end of file
@@ -0,0 +1,7 @@
+ALTER   TABLE   "bikes"
+    ADD COLUMN  IF  NOT EXISTS  booking_granularity                 varchar (   16 )                NOT NULL    DEFAULT 'free'
+,   ADD CONSTRAINT  "CK_bikes#booking_granularity"                  CHECK
+    (
+        booking_granularity IN ('free', 'hourly', 'half_day', 'day')
+    )
+;
@@ -0,0 +1,4 @@
+DROP    TABLE   IF  EXISTS rent_approvals                   CASCADE;
+ALTER   TABLE   "bikes"
+    DROP COLUMN IF  EXISTS  requires_approval
+;
\ No newline at end of file
@@ -0,0 +1,39 @@
+ALTER   TABLE   "bikes"
+    ADD COLUMN  IF  NOT EXISTS  requires_approval                   boolean                         NOT NULL    DEFAULT false
+;
+
+CREATE  TABLE   IF  NOT EXISTS  "rent_approvals"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   token_id                                                        integer                         NOT NULL
+,   status                                                          varchar (   16 )                NOT NULL    DEFAULT 'pending'
+,   expires_at                                                      timestamp   with    time zone   NOT NULL
+,   decided_by                                                      varchar (  255 )                    NULL
+,   decided_at                                                      timestamp   with    time zone       NULL
+,   created_at                                                      timestamp   with    time zone   NOT NULL    DEFAULT now()
+
+,   CONSTRAINT  "PK_rent_approvals"                                 PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "UK_rent_approvals#token_id"                        UNIQUE
+    (
+        token_id
+    )
+
+,   CONSTRAINT  "CK_rent_approvals#status"                          CHECK
+    (
+        status IN ('pending', 'approved', 'declined', 'expired')
+    )
+
+,   CONSTRAINT  "FK_rent_approvals_tokens"                          FOREIGN KEY
+    (
+        token_id
+    )
+    REFERENCES  "tokens"
+    (
+        id
+    )
+    ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+ALTER   TABLE   "tokens"
+    DROP COLUMN IF  EXISTS  rider_hash
+;
\ No newline at end of file
@@ -0,0 +1,3 @@
+ALTER   TABLE   "tokens"
+    ADD COLUMN  IF  NOT EXISTS  rider_hash                          varchar (   64 )                    NULL
+;
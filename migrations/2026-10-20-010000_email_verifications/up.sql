@@ -0,0 +1,42 @@
+CREATE  TABLE   IF  NOT EXISTS  "email_verifications"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   token_id                                                        integer                         NOT NULL
+,   uuid                                                            uuid                            NOT NULL    DEFAULT gen_random_uuid ()
+,   encrypted_email                                                 text                            NOT NULL
+,   short_token                                                     varchar (  255 )                    NULL
+,   status                                                          varchar (   16 )                NOT NULL    DEFAULT 'pending'
+,   expires_at                                                      timestamp   with    time zone   NOT NULL
+,   verified_at                                                     timestamp   with    time zone       NULL
+,   created_at                                                      timestamp   with    time zone   NOT NULL    DEFAULT now()
+
+,   CONSTRAINT  "PK_email_verifications"                            PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "UK_email_verifications#token_id"                   UNIQUE
+    (
+        token_id
+    )
+
+,   CONSTRAINT  "UK_email_verifications#uuid"                       UNIQUE
+    (
+        uuid
+    )
+
+,   CONSTRAINT  "CK_email_verifications#status"                     CHECK
+    (
+        status IN ('pending', 'verified', 'lapsed')
+    )
+
+,   CONSTRAINT  "FK_email_verifications_tokens"                     FOREIGN KEY
+    (
+        token_id
+    )
+    REFERENCES  "tokens"
+    (
+        id
+    )
+    ON DELETE CASCADE
+);
@@ -0,0 +1 @@
+DROP    TABLE   IF  EXISTS proof_of_work_nonces             CASCADE;
\ No newline at end of file
@@ -0,0 +1,16 @@
+CREATE  TABLE   IF  NOT EXISTS  "proof_of_work_nonces"
+(
+    id                                                              integer                                     GENERATED BY DEFAULT AS IDENTITY
+,   nonce                                                           varchar (   64 )                NOT NULL
+,   expires_at                                                      timestamp   with    time zone   NOT NULL
+
+,   CONSTRAINT  "PK_proof_of_work_nonces"                           PRIMARY KEY
+    (
+        id
+    )
+
+,   CONSTRAINT  "UK_proof_of_work_nonces#nonce"                     UNIQUE
+    (
+        nonce
+    )
+);
@@ -0,0 +1,282 @@
+#[cfg(test)]
+use mocktopus::macros::mockable;
+
+use std::env;
+use std::str::FromStr;
+
+use chrono_tz::Tz;
+
+use serde::Serialize;
+
+#[derive(Serialize,PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug,Clone,Copy)]
+pub enum ChallengeType {
+    Question,
+    ProofOfWork,
+}
+
+impl FromStr for ChallengeType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<ChallengeType, String> {
+        match s.trim() {
+            "question" => Ok(ChallengeType::Question),
+            "proof_of_work" => Ok(ChallengeType::ProofOfWork),
+            other => Err(format!("Unknown challenge type '{}'.", other)),
+        }
+    }
+}
+
+fn parse_env<T: FromStr>(key: &str, default: T) -> T {
+    env::var(key).ok()
+        .and_then(|value| value.parse::<T>().ok())
+        .unwrap_or(default)
+}
+
+/// Challenge types a rider may solve to obtain a token, configured as a
+/// comma separated list in `CHALLENGE_TYPES` (defaults to `question`).
+pub fn enabled_challenge_types() -> Vec<ChallengeType> {
+    let challenge_types = env::var("CHALLENGE_TYPES").unwrap_or("question".to_string());
+
+    challenge_types.split(',')
+        .filter(|s| !s.trim().is_empty())
+        .filter_map(|s| s.parse::<ChallengeType>().ok())
+        .collect()
+}
+
+pub fn is_challenge_type_enabled(challenge_type: ChallengeType) -> bool {
+    enabled_challenge_types().contains(&challenge_type)
+}
+
+pub fn proof_of_work_secret() -> Option<String> {
+    env::var("PROOF_OF_WORK_SECRET").ok()
+}
+
+/// Number of leading zero bits the solution hash must have.
+pub fn proof_of_work_difficulty() -> u8 {
+    parse_env("PROOF_OF_WORK_DIFFICULTY", 20)
+}
+
+pub fn proof_of_work_ttl_seconds() -> i64 {
+    parse_env("PROOF_OF_WORK_TTL_SECONDS", 300)
+}
+
+/// Hours after which a token that has not been used for a booking expires.
+pub fn token_ttl_hours() -> i64 {
+    parse_env("TOKEN_TTL_HOURS", 24)
+}
+
+pub fn job_interval_seconds() -> u64 {
+    parse_env("JOB_INTERVAL_SECONDS", 3600)
+}
+
+/// API keys for the admin endpoints, configured in `ADMIN_API_KEYS` as a comma
+/// separated list of `<name>:<key>` pairs. The name is recorded as the author
+/// of administrative changes.
+pub fn admin_api_keys() -> Vec<(String, String)> {
+    let api_keys = env::var("ADMIN_API_KEYS").unwrap_or_default();
+
+    api_keys.split(',')
+        .filter_map(|pair| {
+            let mut parts = pair.trim().splitn(2, ':');
+
+            match (parts.next(), parts.next()) {
+                (Some(name), Some(key)) if !name.is_empty() && !key.is_empty() => Some((name.to_string(), key.to_string())),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Locales tried after the ones requested by the client, configured in
+/// `LOCALE_FALLBACK_CHAIN` as a comma separated list (defaults to `de-DE`).
+pub fn locale_fallback_chain() -> Vec<String> {
+    let chain = env::var("LOCALE_FALLBACK_CHAIN").unwrap_or("de-DE".to_string());
+
+    chain.split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Locales every translatable resource should be available in, configured in
+/// `SUPPORTED_LOCALES` (defaults to the locale fallback chain).
+pub fn supported_locales() -> Vec<String> {
+    match env::var("SUPPORTED_LOCALES") {
+        Ok(locales) => locales.split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+        Err(_) => locale_fallback_chain(),
+    }
+}
+
+/// Directory uploaded files are stored in (`UPLOAD_DIRECTORY`, defaults to `uploads`).
+pub fn upload_directory() -> String {
+    env::var("UPLOAD_DIRECTORY").unwrap_or("uploads".to_string())
+}
+
+/// URL prefix under which uploaded files are served (`UPLOAD_BASE_URL`,
+/// defaults to `/uploads`). Only needs to be changed when the files are
+/// served by a proxy or CDN.
+pub fn upload_base_url() -> String {
+    env::var("UPLOAD_BASE_URL").unwrap_or("/uploads".to_string())
+}
+
+pub fn upload_limit_bytes() -> u64 {
+    parse_env("UPLOAD_LIMIT_BYTES", 5 * 1024 * 1024)
+}
+
+/// Number of pixels an uploaded image may have (`UPLOAD_MAX_PIXELS`). Checked
+/// before the image is decoded, as small files can decode to huge images.
+#[cfg_attr(test, mockable)]
+pub fn upload_max_pixels() -> u64 {
+    parse_env("UPLOAD_MAX_PIXELS", 40_000_000)
+}
+
+/// Bounding box of the web variant generated for uploaded logos.
+pub fn logo_max_width() -> u32 {
+    parse_env("LOGO_MAX_WIDTH", 400)
+}
+
+pub fn logo_max_height() -> u32 {
+    parse_env("LOGO_MAX_HEIGHT", 200)
+}
+
+/// Bounding box of the variant shown for uploaded bike images.
+pub fn bike_image_max_width() -> u32 {
+    parse_env("BIKE_IMAGE_MAX_WIDTH", 1600)
+}
+
+pub fn bike_image_max_height() -> u32 {
+    parse_env("BIKE_IMAGE_MAX_HEIGHT", 1200)
+}
+
+pub fn thumbnail_max_width() -> u32 {
+    parse_env("THUMBNAIL_MAX_WIDTH", 320)
+}
+
+pub fn thumbnail_max_height() -> u32 {
+    parse_env("THUMBNAIL_MAX_HEIGHT", 240)
+}
+
+/// Number of photos which can be attached to a single incident.
+pub fn incident_photo_limit() -> i64 {
+    parse_env("INCIDENT_PHOTO_LIMIT", 5)
+}
+
+/// Days after the end of a rent in which its rider can still report
+/// incidents (`INCIDENT_REPORT_DAYS`).
+pub fn incident_report_days() -> i64 {
+    parse_env("INCIDENT_REPORT_DAYS", 7)
+}
+
+/// Number of bikes a single booking may reserve (`MAX_BIKES_PER_BOOKING`).
+pub fn max_bikes_per_booking() -> usize {
+    parse_env("MAX_BIKES_PER_BOOKING", 3)
+}
+
+/// Number of occurrences a recurring booking may expand to (`MAX_SERIES_OCCURRENCES`).
+pub fn max_series_occurrences() -> usize {
+    parse_env("MAX_SERIES_OCCURRENCES", 52)
+}
+
+/// Minutes a waitlisted rider may hold a freed slot (`WAITLIST_HOLD_MINUTES`).
+pub fn waitlist_hold_minutes() -> i64 {
+    parse_env("WAITLIST_HOLD_MINUTES", 120)
+}
+
+/// Minutes a slot stays held while the booking form is filled in (`HOLD_MINUTES`).
+pub fn hold_minutes() -> i64 {
+    parse_env("HOLD_MINUTES", 15)
+}
+
+/// Holds a token may have at the same time (`MAX_HOLDS_PER_TOKEN`).
+#[cfg_attr(test, mockable)]
+pub fn max_holds_per_token() -> i64 {
+    parse_env("MAX_HOLDS_PER_TOKEN", 1)
+}
+
+/// Hours a held window may span at most (`MAX_HOLD_WINDOW_HOURS`).
+pub fn max_hold_window_hours() -> i64 {
+    parse_env("MAX_HOLD_WINDOW_HOURS", 168)
+}
+
+/// Hours the admins have to approve a booking before it expires, bookings
+/// starting earlier expire at their start (`APPROVAL_TIMEOUT_HOURS`).
+pub fn approval_timeout_hours() -> i64 {
+    parse_env("APPROVAL_TIMEOUT_HOURS", 48)
+}
+
+/// Minutes kept free before each rent for the handover check, unless the bike
+/// has its own buffer (`RENT_BUFFER_BEFORE_MINUTES`).
+pub fn rent_buffer_before_minutes() -> i32 {
+    parse_env("RENT_BUFFER_BEFORE_MINUTES", 0)
+}
+
+/// Minutes kept free after each rent for cleaning and charging, unless the
+/// bike has its own buffer (`RENT_BUFFER_AFTER_MINUTES`).
+pub fn rent_buffer_after_minutes() -> i32 {
+    parse_env("RENT_BUFFER_AFTER_MINUTES", 0)
+}
+
+/// Hour at which the afternoon slot of half-day bookings starts (`HALF_DAY_SPLIT_HOUR`).
+pub fn half_day_split_hour() -> u32 {
+    parse_env("HALF_DAY_SPLIT_HOUR", 12).clamp(1, 23)
+}
+
+/// Whether bookings off the slots of a bike are widened to whole slots or
+/// rejected (`SNAP_BOOKINGS`).
+pub fn snap_bookings() -> bool {
+    parse_env("SNAP_BOOKINGS", true)
+}
+
+/// Timezone of the deployment as IANA name, e.g. `Europe/Berlin` (`TIMEZONE`,
+/// defaults to UTC). Days and slots are laid out in it and timestamps without
+/// an offset are read in it.
+#[cfg_attr(test, mockable)]
+pub fn timezone() -> Tz {
+    parse_env("TIMEZONE", Tz::UTC)
+}
+
+/// Key of the hash which identifies riders by their email address for the
+/// fair-use limits, also seals the stored addresses (`RIDER_HASH_SECRET`).
+/// Without it bookings cannot carry an address.
+#[cfg_attr(test, mockable)]
+pub fn rider_hash_secret() -> Option<String> {
+    env::var("RIDER_HASH_SECRET").ok()
+}
+
+/// Bookings a rider may have which have not ended yet, 0 for no limit
+/// (`MAX_ACTIVE_BOOKINGS`).
+#[cfg_attr(test, mockable)]
+pub fn max_active_bookings() -> i64 {
+    parse_env("MAX_ACTIVE_BOOKINGS", 0)
+}
+
+/// Hours a rider may book within 30 days, 0 for no limit
+/// (`MAX_BOOKED_HOURS_PER_MONTH`).
+#[cfg_attr(test, mockable)]
+pub fn max_booked_hours_per_month() -> i64 {
+    parse_env("MAX_BOOKED_HOURS_PER_MONTH", 0)
+}
+
+/// Minutes a rider has to confirm the email address left with a booking, the
+/// booking lapses otherwise (`VERIFICATION_TIMEOUT_MINUTES`).
+pub fn verification_timeout_minutes() -> i64 {
+    parse_env("VERIFICATION_TIMEOUT_MINUTES", 60)
+}
+
+/// Minutes a rider has at least to confirm the address, even if the rent
+/// starts earlier (`VERIFICATION_GRACE_MINUTES`).
+#[cfg_attr(test, mockable)]
+pub fn verification_grace_minutes() -> i64 {
+    parse_env("VERIFICATION_GRACE_MINUTES", 15)
+}
+
+/// Public URL of the backend, the links in verification and waitlist mails
+/// start with it (`VERIFICATION_BASE_URL`).
+pub fn verification_base_url() -> String {
+    env::var("VERIFICATION_BASE_URL").unwrap_or("http://localhost:8000".to_string())
+}
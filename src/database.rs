@@ -2,12 +2,21 @@ pub mod models;
 
 pub mod daos;
 
+use rocket::Rocket;
 use rocket_contrib::database;
-use rocket_contrib::databases::diesel;
+use rocket_contrib::databases::{diesel,r2d2,Poolable};
 
 #[database("cbs")]
 pub struct DbConn(diesel::PgConnection);
 
+pub type DbPool = r2d2::Pool<<diesel::PgConnection as Poolable>::Manager>;
+
+/// The pool behind the `DbConn` guard, for work outside of requests which
+/// checks out a connection whenever it needs one.
+pub fn pool(rocket: &Rocket) -> Option<DbPool> {
+    rocket.state::<DbConnPool>().map(|pool| pool.0.clone())
+}
+
 #[cfg(test)]
 pub mod test {
     use dotenv::dotenv;
@@ -1,7 +1,37 @@
+pub mod accessory;
+
+pub mod approval;
+
+pub mod availability;
+
 pub mod bike;
 
+pub mod bike_attribute;
+
 pub mod challenge;
 
+pub mod feedback;
+
+pub mod hold;
+
+pub mod incident;
+
+pub mod invite;
+
+pub mod maintenance;
+
+pub mod quota;
+
 pub mod rent;
 
-pub mod supporter;
\ No newline at end of file
+pub mod rent_series;
+
+pub mod supporter;
+
+pub mod token;
+
+pub mod translatable;
+
+pub mod verification;
+
+pub mod waitlist;
\ No newline at end of file
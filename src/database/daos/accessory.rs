@@ -0,0 +1,107 @@
+#[cfg(test)]
+use mocktopus::macros::mockable;
+
+use diesel::{Connection,RunQueryDsl,QueryDsl,ExpressionMethods};
+use diesel::{delete,insert_into,update};
+
+use crate::database::DbConn;
+use crate::database::daos::translatable;
+use crate::database::daos::translatable::{AccessoryTranslations,TranslatableResource};
+use crate::database::models::{Accessory,AccessoryTranslatable,AccessoryWithDetails,InsertAccessory,InsertAccessoryBike,InsertAccessoryTranslatable};
+use crate::locale::LocalePreferences;
+use crate::schema::accessories;
+use crate::schema::accessory_bikes;
+
+/// Accessories with their best matching translation, ordered by sort
+/// priority.
+#[cfg_attr(test, mockable)]
+pub fn get_accessories(db: &DbConn, preferences: &LocalePreferences) -> Result<Vec<AccessoryWithDetails>, diesel::result::Error> {
+    let inventory = get_accessory_inventory(db)?;
+    let translations = translatable::load_by_locale::<AccessoryTranslations>(db, preferences)?;
+
+    Ok(inventory.into_iter()
+        .filter_map(|accessory| translations.iter()
+            .find(|t| t.accessory_id == accessory.id)
+            .map(|translation| AccessoryWithDetails {
+                accessory: translation.clone(),
+                quantity: accessory.quantity,
+                sort_priority: accessory.sort_priority,
+                available: None,
+            }))
+        .collect())
+}
+
+#[cfg_attr(test, mockable)]
+pub fn get_accessory_inventory(db: &DbConn) -> Result<Vec<Accessory>, diesel::result::Error> {
+    accessories::table
+        .order_by((accessories::sort_priority.desc(), accessories::id))
+        .load::<Accessory>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn insert_accessory(db: &DbConn, accessory: &InsertAccessory) -> Result<Accessory, diesel::result::Error> {
+    insert_into(accessories::table)
+        .values(accessory)
+        .get_result::<Accessory>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn update_accessory(db: &DbConn, p_accessory_id: i32, accessory: &InsertAccessory) -> Result<Accessory, diesel::result::Error> {
+    update(accessories::table.find(p_accessory_id))
+        .set(accessory)
+        .get_result::<Accessory>(&**db)
+}
+
+/// Fails with a foreign key violation while rents reference the accessory.
+#[cfg_attr(test, mockable)]
+pub fn delete_accessory(db: &DbConn, p_accessory_id: i32) -> Result<usize, diesel::result::Error> {
+    delete(accessories::table.find(p_accessory_id))
+        .execute(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn get_accessory_translations(db: &DbConn, p_accessory_id: i32) -> Result<Vec<AccessoryTranslatable>, diesel::result::Error> {
+    AccessoryTranslations::load_translations_of(db, p_accessory_id)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn upsert_accessory_translation(db: &DbConn, translation: &InsertAccessoryTranslatable) -> Result<AccessoryTranslatable, diesel::result::Error> {
+    translatable::upsert_translation::<AccessoryTranslations>(db, translation)
+}
+
+/// Bikes the accessory is made for, none if it goes with every bike.
+#[cfg_attr(test, mockable)]
+pub fn get_compatible_bike_ids(db: &DbConn, p_accessory_id: i32) -> Result<Vec<i32>, diesel::result::Error> {
+    accessory_bikes::table
+        .select(accessory_bikes::bike_id)
+        .filter(accessory_bikes::accessory_id.eq(p_accessory_id))
+        .order_by(accessory_bikes::bike_id)
+        .load::<i32>(&**db)
+}
+
+/// Replaces the bikes the accessory is made for. Existing rents are not
+/// touched, it only limits new bookings.
+#[cfg_attr(test, mockable)]
+pub fn set_compatible_bike_ids(db: &DbConn, p_accessory_id: i32, bike_ids: &[i32]) -> Result<Vec<i32>, diesel::result::Error> {
+    (*db).transaction(|| {
+        accessories::table
+            .find(p_accessory_id)
+            .get_result::<Accessory>(&**db)?;
+
+        delete(accessory_bikes::table.filter(accessory_bikes::accessory_id.eq(p_accessory_id)))
+            .execute(&**db)?;
+
+        let compatible_bikes = bike_ids.iter()
+            .map(|bike_id| InsertAccessoryBike { accessory_id: p_accessory_id, bike_id: *bike_id })
+            .collect::<Vec<_>>();
+
+        if !compatible_bikes.is_empty() {
+            insert_into(accessory_bikes::table)
+                .values(&compatible_bikes)
+                .on_conflict_do_nothing()
+                .execute(&**db)?;
+        }
+
+        get_compatible_bike_ids(db, p_accessory_id)
+    })
+}
@@ -0,0 +1,121 @@
+#[cfg(test)]
+use mocktopus::macros::mockable;
+
+use ::uuid::Uuid;
+use chrono::{Duration,NaiveDateTime,Utc};
+
+use diesel::{Connection,RunQueryDsl,QueryDsl,ExpressionMethods};
+use diesel::{insert_into,update};
+
+use crate::config;
+use crate::database::DbConn;
+use crate::database::models::{ApprovalStatus,InsertRentApproval,Rent,RentApproval,RentApprovalWithRents};
+use crate::schema::rent_approvals;
+use crate::schema::rents;
+use crate::schema::tokens;
+
+use crate::routes::errors::RentError;
+
+/// Marks the booking of the token as pending. Nobody can act on it after
+/// the rent started, so it expires then at the latest.
+pub fn insert_pending_approval(db: &DbConn, p_token_id: i32, rent_start: NaiveDateTime) -> Result<RentApproval, diesel::result::Error> {
+    let timeout = Utc::now().naive_utc() + Duration::hours(config::approval_timeout_hours());
+
+    insert_into(rent_approvals::table)
+        .values(&InsertRentApproval {
+            token_id: p_token_id,
+            expires_at: timeout.min(rent_start),
+        })
+        .get_result::<RentApproval>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn get_approval_by_token(db: &DbConn, p_uuid: &Uuid) -> Result<RentApproval, diesel::result::Error> {
+    rent_approvals::table
+        .inner_join(tokens::table)
+        .filter(tokens::uuid.eq(p_uuid))
+        .select(rent_approvals::all_columns)
+        .get_result::<RentApproval>(&**db)
+}
+
+fn with_rents(db: &DbConn, approval: RentApproval) -> Result<RentApprovalWithRents, diesel::result::Error> {
+    let rents = rents::table
+        .filter(rents::token_id.eq(approval.token_id))
+        .order_by(rents::id)
+        .load::<Rent>(&**db)?;
+
+    Ok(RentApprovalWithRents { approval, rents })
+}
+
+/// Bookings waiting for a decision, those expiring first come first.
+#[cfg_attr(test, mockable)]
+pub fn get_pending_approvals(db: &DbConn) -> Result<Vec<RentApprovalWithRents>, diesel::result::Error> {
+    rent_approvals::table
+        .filter(rent_approvals::status.eq(ApprovalStatus::Pending.as_str()))
+        .order_by((rent_approvals::expires_at, rent_approvals::id))
+        .load::<RentApproval>(&**db)?
+        .into_iter()
+        .map(|approval| with_rents(db, approval))
+        .collect()
+}
+
+/// Moves the approval out of pending. Unless it is approved its rents are
+/// revoked, which frees the slot again.
+fn close_approval(db: &DbConn, approval: &RentApproval, status: ApprovalStatus, p_decided_by: Option<&str>, now: NaiveDateTime) -> Result<RentApprovalWithRents, diesel::result::Error> {
+    let approval = update(rent_approvals::table.find(approval.id))
+        .set((
+            rent_approvals::status.eq(status.as_str()),
+            rent_approvals::decided_by.eq(p_decided_by),
+            rent_approvals::decided_at.eq(now),
+        ))
+        .get_result::<RentApproval>(&**db)?;
+
+    if status != ApprovalStatus::Approved {
+        update(rents::table
+                .filter(rents::token_id.eq(approval.token_id))
+                .filter(rents::revocation_timestamp.is_null()))
+            .set(rents::revocation_timestamp.eq(now))
+            .execute(&**db)?;
+    }
+
+    with_rents(db, approval)
+}
+
+/// Approves or declines a pending booking on behalf of the admin.
+#[cfg_attr(test, mockable)]
+pub fn decide_approval(db: &DbConn, p_approval_id: i32, approve: bool, admin_name: &str) -> Result<RentApprovalWithRents, RentError> {
+    (*db).transaction(|| {
+        let now = Utc::now().naive_utc();
+        let approval = rent_approvals::table
+            .find(p_approval_id)
+            .for_update()
+            .get_result::<RentApproval>(&**db)?;
+
+        if approval.status != ApprovalStatus::Pending.as_str() {
+            return Err(RentError::Validation(format!("The booking is already {}.", approval.status)));
+        }
+
+        if approval.expires_at <= now {
+            return Err(RentError::Validation(String::from("The booking has expired.")));
+        }
+
+        let status = if approve { ApprovalStatus::Approved } else { ApprovalStatus::Declined };
+
+        Ok(close_approval(db, &approval, status, Some(admin_name), now)?)
+    })
+}
+
+/// Expires the bookings nobody decided on in time and frees their slots.
+#[cfg_attr(test, mockable)]
+pub fn expire_pending_approvals(db: &DbConn, as_of: NaiveDateTime) -> Result<Vec<RentApprovalWithRents>, diesel::result::Error> {
+    (*db).transaction(|| {
+        rent_approvals::table
+            .filter(rent_approvals::status.eq(ApprovalStatus::Pending.as_str()))
+            .filter(rent_approvals::expires_at.le(as_of))
+            .for_update()
+            .load::<RentApproval>(&**db)?
+            .iter()
+            .map(|approval| close_approval(db, approval, ApprovalStatus::Expired, None, as_of))
+            .collect()
+    })
+}
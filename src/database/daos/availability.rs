@@ -0,0 +1,325 @@
+#[cfg(test)]
+use mocktopus::macros::mockable;
+
+use chrono::{Duration,NaiveDateTime,Utc};
+
+use diesel::{RunQueryDsl,QueryDsl,BoolExpressionMethods,ExpressionMethods};
+use diesel::{delete,insert_into};
+use diesel::dsl::max;
+
+use crate::config;
+use crate::database::DbConn;
+use crate::database::daos::{accessory,maintenance};
+use crate::database::models::{Accessory,Bike,BikeBlackout,BikeStatus,BookingAccessory,BookingGranularity,InsertBikeBlackout};
+use crate::schema::accessories;
+use crate::schema::bike_blackouts;
+use crate::schema::bikes;
+use crate::schema::rent_accessories;
+use crate::schema::rent_hold_accessories;
+use crate::schema::rent_holds;
+use crate::schema::rents;
+
+use crate::routes::errors::RentError;
+
+// Periods are half-open, without buffers a rent ending at 12:00 does not
+// overlap with one starting at 12:00. Two periods overlap if each starts
+// before the other ends.
+
+/// Bikes of the blackouts overlapping the window. Blackouts without a bike
+/// block every bike and show up as `None`.
+fn blocking_blackouts(db: &DbConn, start: NaiveDateTime, end: NaiveDateTime) -> Result<Vec<Option<i32>>, diesel::result::Error> {
+    bike_blackouts::table
+        .select(bike_blackouts::bike_id)
+        .filter(bike_blackouts::start_timestamp.lt(end).and(bike_blackouts::end_timestamp.gt(start)))
+        .load::<Option<i32>>(&**db)
+}
+
+/// Buffers of the bike in minutes, before and after each rent. Bikes without
+/// their own buffers use the global ones.
+pub fn buffer_minutes(bike: &Bike) -> (i32, i32) {
+    (
+        bike.buffer_before_minutes.unwrap_or_else(config::rent_buffer_before_minutes),
+        bike.buffer_after_minutes.unwrap_or_else(config::rent_buffer_after_minutes),
+    )
+}
+
+/// Aligns the window to the slots of all the bikes. Windows off the slots are
+/// widened to whole slots, or rejected if snapping is turned off.
+pub fn align_window(db: &DbConn, bike_ids: &[i32], start: NaiveDateTime, end: NaiveDateTime) -> Result<(NaiveDateTime, NaiveDateTime), RentError> {
+    let granularities = bikes::table
+        .select(bikes::booking_granularity)
+        .filter(bikes::id.eq_any(bike_ids))
+        .load::<String>(&**db)?;
+
+    // Day boundaries are half-day boundaries, which are hour boundaries, so
+    // snapping to every granularity in turn aligns the window to all of them.
+    let mut aligned = (start, end);
+    for granularity in granularities {
+        let granularity = granularity.parse::<BookingGranularity>().map_err(RentError::Validation)?;
+        aligned = granularity.snap(aligned.0, aligned.1, config::half_day_split_hour());
+    }
+
+    if aligned != (start, end) && !config::snap_bookings() {
+        return Err(RentError::Validation(String::from("The rent has to start and end at the slots of the bike.")));
+    }
+
+    Ok(aligned)
+}
+
+/// Upper bound of the turnaround of any bike, reservations further away from
+/// a window cannot touch it.
+fn widest_turnaround(db: &DbConn) -> Result<Duration, diesel::result::Error> {
+    let before = bikes::table
+        .select(max(bikes::buffer_before_minutes))
+        .get_result::<Option<i32>>(&**db)?;
+    let after = bikes::table
+        .select(max(bikes::buffer_after_minutes))
+        .get_result::<Option<i32>>(&**db)?;
+
+    Ok(Duration::minutes(
+        i64::from(before.unwrap_or(0).max(config::rent_buffer_before_minutes()))
+            + i64::from(after.unwrap_or(0).max(config::rent_buffer_after_minutes()))))
+}
+
+type BikeReservation = (i32, NaiveDateTime, NaiveDateTime, Option<i32>, Option<i32>);
+
+/// Bikes taken by rents or by holds which have not expired yet. Consecutive
+/// rents of a bike have to be apart by the buffer after the first plus the
+/// buffer before the second one, so reservations count if they overlap the
+/// window widened by both buffers on either side.
+fn reserved_bike_ids(db: &DbConn, start: NaiveDateTime, end: NaiveDateTime) -> Result<Vec<i32>, diesel::result::Error> {
+    let widest = widest_turnaround(db)?;
+    let (from, to) = (start - widest, end + widest);
+
+    let mut reserved = rents::table
+        .inner_join(bikes::table)
+        .select((rents::bike_id, rents::start_timestamp, rents::end_timestamp, bikes::buffer_before_minutes, bikes::buffer_after_minutes))
+        .filter(rents::revocation_timestamp.is_null())
+        .filter(rents::start_timestamp.lt(to).and(rents::end_timestamp.gt(from)))
+        .load::<BikeReservation>(&**db)?;
+
+    reserved.extend(rent_holds::table
+        .inner_join(bikes::table)
+        .select((rent_holds::bike_id, rent_holds::start_timestamp, rent_holds::end_timestamp, bikes::buffer_before_minutes, bikes::buffer_after_minutes))
+        .filter(rent_holds::expires_at.gt(Utc::now().naive_utc()))
+        .filter(rent_holds::start_timestamp.lt(to).and(rent_holds::end_timestamp.gt(from)))
+        .load::<BikeReservation>(&**db)?);
+
+    Ok(reserved.into_iter()
+        .filter(|(_, reserved_start, reserved_end, before, after)| {
+            let turnaround = Duration::minutes(
+                i64::from(before.unwrap_or_else(config::rent_buffer_before_minutes))
+                    + i64::from(after.unwrap_or_else(config::rent_buffer_after_minutes)));
+
+            *reserved_start < end + turnaround && *reserved_end + turnaround > start
+        })
+        .map(|(bike_id, _, _, _, _)| bike_id)
+        .collect())
+}
+
+/// Those of the bikes which are active and can go out at `start`, i.e. no
+/// service which blocks bookings is overdue by then.
+fn bookable_bike_ids(db: &DbConn, bike_ids: &[i32], start: NaiveDateTime) -> Result<Vec<i32>, diesel::result::Error> {
+    let blocked = maintenance::get_blocked_bike_ids(db, bike_ids, start)?;
+
+    Ok(bikes::table
+        .select(bikes::id)
+        .filter(bikes::id.eq_any(bike_ids))
+        .filter(bikes::status.eq(BikeStatus::Active.as_str()))
+        .load::<i32>(&**db)?
+        .into_iter()
+        .filter(|id| !blocked.contains(id))
+        .collect())
+}
+
+/// Whether the bike is active, not blocked by maintenance and can be booked
+/// for the whole window.
+#[cfg_attr(test, mockable)]
+pub fn is_bike_available(db: &DbConn, bike_id: i32, start: NaiveDateTime, end: NaiveDateTime) -> Result<bool, diesel::result::Error> {
+    let status = bikes::table
+        .find(bike_id)
+        .select(bikes::status)
+        .get_result::<String>(&**db)?;
+
+    if status != BikeStatus::Active.as_str() || !maintenance::get_blocked_bike_ids(db, &[bike_id], start)?.is_empty() {
+        return Ok(false);
+    }
+
+    let blocked = blocking_blackouts(db, start, end)?.iter()
+        .any(|blackout| blackout.map_or(true, |id| id == bike_id));
+
+    Ok(!blocked && !reserved_bike_ids(db, start, end)?.contains(&bike_id))
+}
+
+/// Filters the given bikes down to the bookable ones which are free for the
+/// whole window.
+#[cfg_attr(test, mockable)]
+pub fn get_available_bike_ids(db: &DbConn, bike_ids: &[i32], start: NaiveDateTime, end: NaiveDateTime) -> Result<Vec<i32>, diesel::result::Error> {
+    let blackouts = blocking_blackouts(db, start, end)?;
+
+    if blackouts.contains(&None) {
+        return Ok(vec![]);
+    }
+
+    let bookable = bookable_bike_ids(db, bike_ids, start)?;
+    let reserved = reserved_bike_ids(db, start, end)?;
+
+    Ok(bike_ids.iter()
+        .filter(|id| bookable.contains(id) && !reserved.contains(id) && !blackouts.contains(&Some(**id)))
+        .cloned()
+        .collect())
+}
+
+type AccessoryReservation = (i32, i32, NaiveDateTime, NaiveDateTime, Option<i32>, Option<i32>);
+
+/// Items taken by rents and by holds which have not expired yet, as
+/// `(accessory_id, quantity, start, end)`. They go out and come back with the
+/// bike, so each reservation is widened by the buffers of its bike.
+fn accessory_reservations(db: &DbConn, start: NaiveDateTime, end: NaiveDateTime) -> Result<Vec<(i32, i32, NaiveDateTime, NaiveDateTime)>, diesel::result::Error> {
+    let widest = widest_turnaround(db)?;
+    let (from, to) = (start - widest, end + widest);
+
+    let mut reserved = rent_accessories::table
+        .inner_join(rents::table.inner_join(bikes::table))
+        .select((rent_accessories::accessory_id, rent_accessories::quantity, rents::start_timestamp, rents::end_timestamp, bikes::buffer_before_minutes, bikes::buffer_after_minutes))
+        .filter(rents::revocation_timestamp.is_null())
+        .filter(rents::start_timestamp.lt(to).and(rents::end_timestamp.gt(from)))
+        .load::<AccessoryReservation>(&**db)?;
+
+    reserved.extend(rent_hold_accessories::table
+        .inner_join(rent_holds::table.inner_join(bikes::table))
+        .select((rent_hold_accessories::accessory_id, rent_hold_accessories::quantity, rent_holds::start_timestamp, rent_holds::end_timestamp, bikes::buffer_before_minutes, bikes::buffer_after_minutes))
+        .filter(rent_holds::expires_at.gt(Utc::now().naive_utc()))
+        .filter(rent_holds::start_timestamp.lt(to).and(rent_holds::end_timestamp.gt(from)))
+        .load::<AccessoryReservation>(&**db)?);
+
+    Ok(reserved.into_iter()
+        .map(|(accessory_id, quantity, reserved_start, reserved_end, before, after)| (
+            accessory_id,
+            quantity,
+            reserved_start - Duration::minutes(i64::from(before.unwrap_or_else(config::rent_buffer_before_minutes))),
+            reserved_end + Duration::minutes(i64::from(after.unwrap_or_else(config::rent_buffer_after_minutes))),
+        ))
+        .filter(|(_, _, reserved_start, reserved_end)| *reserved_start < end && *reserved_end > start)
+        .collect())
+}
+
+/// Most items in use at the same time within the window. Items come back at
+/// the end of a period and can go out again at the same instant.
+fn peak_usage(reservations: &[(i32, NaiveDateTime, NaiveDateTime)], start: NaiveDateTime, end: NaiveDateTime) -> i32 {
+    let mut changes = reservations.iter()
+        .flat_map(|(quantity, reserved_start, reserved_end)| vec![
+            (*reserved_start.max(&start), *quantity),
+            (*reserved_end.min(&end), -*quantity),
+        ])
+        .collect::<Vec<_>>();
+    changes.sort_unstable();
+
+    changes.iter()
+        .scan(0, |used, (_, change)| {
+            *used += change;
+            Some(*used)
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Number of items of each accessory which are free for the whole window.
+/// Accessories share the global blackouts with the bikes, as they are handed
+/// out together with them.
+#[cfg_attr(test, mockable)]
+pub fn get_available_accessory_quantities(db: &DbConn, start: NaiveDateTime, end: NaiveDateTime) -> Result<Vec<(i32, i32)>, diesel::result::Error> {
+    let closed = blocking_blackouts(db, start, end)?.contains(&None);
+    let reserved = accessory_reservations(db, start, end)?;
+
+    Ok(accessories::table
+        .select((accessories::id, accessories::quantity))
+        .order_by(accessories::id)
+        .load::<(i32, i32)>(&**db)?
+        .into_iter()
+        .map(|(accessory_id, quantity)| {
+            let reservations = reserved.iter()
+                .filter(|(id, _, _, _)| *id == accessory_id)
+                .map(|(_, quantity, reserved_start, reserved_end)| (*quantity, *reserved_start, *reserved_end))
+                .collect::<Vec<_>>();
+
+            (accessory_id, if closed { 0 } else { (quantity - peak_usage(&reservations, start, end)).max(0) })
+        })
+        .collect())
+}
+
+#[cfg_attr(test, mockable)]
+pub fn is_accessory_available(db: &DbConn, accessory_id: i32, quantity: i32, start: NaiveDateTime, end: NaiveDateTime) -> Result<bool, diesel::result::Error> {
+    Ok(get_available_accessory_quantities(db, start, end)?.iter()
+        .any(|(id, available)| *id == accessory_id && *available >= quantity))
+}
+
+/// Checks the accessories requested for the bikes within the window. Each
+/// one goes with a bike it is made for, the first bike by default, and is
+/// returned with it. Has to run in the transaction which reserves them, the
+/// accessories stay locked until then.
+pub fn check_accessories(db: &DbConn, requested: &[BookingAccessory], bike_ids: &[i32], start: NaiveDateTime, end: NaiveDateTime) -> Result<Vec<BookingAccessory>, RentError> {
+    let mut requested_accessories = requested.iter()
+        .map(|accessory| BookingAccessory { bike_id: accessory.bike_id.or_else(|| bike_ids.first().cloned()), ..accessory.clone() })
+        .collect::<Vec<_>>();
+    requested_accessories.sort_by_key(|accessory| (accessory.accessory_id, accessory.bike_id));
+
+    for (index, requested) in requested_accessories.iter().enumerate() {
+        if requested.quantity <= 0 || requested_accessories[..index].iter().any(|a| a.accessory_id == requested.accessory_id && a.bike_id == requested.bike_id) {
+            return Err(RentError::Validation(String::from("Each accessory has to be requested once per bike with a positive quantity.")));
+        }
+
+        if !requested.bike_id.map_or(false, |bike_id| bike_ids.contains(&bike_id)) {
+            return Err(RentError::Validation(String::from("Accessories can only go with a bike of the booking.")));
+        }
+
+        let compatible_bike_ids = accessory::get_compatible_bike_ids(db, requested.accessory_id)?;
+
+        if !compatible_bike_ids.is_empty() && !requested.bike_id.map_or(false, |bike_id| compatible_bike_ids.contains(&bike_id)) {
+            return Err(RentError::Validation(format!("The accessory {} does not fit the bike.", requested.accessory_id)));
+        }
+
+        // The same accessory for several bikes is locked and checked once
+        // for all of them.
+        if index > 0 && requested_accessories[index - 1].accessory_id == requested.accessory_id {
+            continue;
+        }
+
+        let quantity = requested_accessories[index..].iter()
+            .take_while(|a| a.accessory_id == requested.accessory_id)
+            .map(|a| a.quantity)
+            .sum();
+
+        accessories::table
+            .find(requested.accessory_id)
+            .for_update()
+            .get_result::<Accessory>(&**db)?;
+
+        if !is_accessory_available(db, requested.accessory_id, quantity, start, end)? {
+            return Err(RentError::Validation(format!("The accessory {} is not available at the same period.", requested.accessory_id)));
+        }
+    }
+
+    Ok(requested_accessories)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn get_blackouts(db: &DbConn, as_of: NaiveDateTime) -> Result<Vec<BikeBlackout>, diesel::result::Error> {
+    bike_blackouts::table
+        .filter(bike_blackouts::end_timestamp.gt(as_of))
+        .order_by((bike_blackouts::start_timestamp, bike_blackouts::id))
+        .load::<BikeBlackout>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn insert_blackout(db: &DbConn, blackout: &InsertBikeBlackout) -> Result<BikeBlackout, diesel::result::Error> {
+    insert_into(bike_blackouts::table)
+        .values(blackout)
+        .get_result::<BikeBlackout>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn delete_blackout(db: &DbConn, p_blackout_id: i32) -> Result<usize, diesel::result::Error> {
+    delete(bike_blackouts::table.find(p_blackout_id))
+        .execute(&**db)
+}
@@ -1,13 +1,184 @@
 #[cfg(test)]
 use mocktopus::macros::mockable;
 
-use diesel::RunQueryDsl;
+use chrono::Utc;
+
+use diesel::{Connection,RunQueryDsl,QueryDsl,BoolExpressionMethods,ExpressionMethods};
+use diesel::{delete,insert_into,update};
 
 use crate::database::DbConn;
-use crate::database::models::BikeTranslatable;
-use crate::schema::bike_translatables::dsl::{bike_translatables};
+use crate::database::daos::availability;
+use crate::database::daos::bike_attribute;
+use crate::database::daos::translatable;
+use crate::database::daos::translatable::{BikeMediaTranslations,BikeTranslations,TranslatableResource};
+use crate::database::models::{Bike,BikeBufferData,BikeMedia,BikeMediaTranslatable,BikeMediaWithAltText,BikeStatus,BikeStatusChange,BikeStatusUpdate,BikeWithDetails,BookingGranularity,InsertBikeMedia,InsertBikeMediaTranslatable,InsertBikeStatusChange,Rent};
+use crate::locale::LocalePreferences;
+use crate::schema::bike_media::dsl::*;
+use crate::schema::bike_status_changes;
+use crate::schema::bikes;
+use crate::schema::rents;
+
+/// Bikes with their best matching translation, their attributes and their
+/// media in gallery order. Labels and alt texts are negotiated the same way
+/// as the bike translation. Retired and hidden bikes are left out.
+#[cfg_attr(test, mockable)]
+pub fn get_bikes(db: &DbConn, preferences: &LocalePreferences) -> Result<Vec<BikeWithDetails>, diesel::result::Error> {
+    let statuses = bikes::table.load::<Bike>(&**db)?;
+    let listed = translatable::load_by_locale::<BikeTranslations>(db, preferences)?;
+    let alt_texts = translatable::load_by_locale::<BikeMediaTranslations>(db, preferences)?;
+    let mut attributes = bike_attribute::get_attributes_by_bike(db, preferences)?;
+    let mut media = bike_media
+        .order_by((bike_id, position, id))
+        .load::<BikeMedia>(&**db)?;
+
+    Ok(listed.into_iter()
+        .filter_map(|bike| {
+            let listed_bike = statuses.iter()
+                .find(|b| b.id == bike.bike_id)
+                .filter(|b| b.status.parse::<BikeStatus>().map_or(false, BikeStatus::is_listed))?;
+            let (buffer_before_minutes, buffer_after_minutes) = availability::buffer_minutes(listed_bike);
+
+            let (own, other): (Vec<BikeMedia>, Vec<BikeMedia>) = media.drain(..).partition(|m| m.bike_id == bike.bike_id);
+            media = other;
+
+            let (own_attributes, other_attributes): (Vec<_>, Vec<_>) = attributes.drain(..).partition(|(attribute_bike_id, _)| *attribute_bike_id == bike.bike_id);
+            attributes = other_attributes;
+
+            Some(BikeWithDetails {
+                attributes: own_attributes.into_iter().map(|(_, attribute)| attribute).collect(),
+                bike,
+                status: listed_bike.status.clone(),
+                buffer_before_minutes,
+                buffer_after_minutes,
+                booking_granularity: listed_bike.booking_granularity.clone(),
+                requires_approval: listed_bike.requires_approval,
+                media: own.into_iter()
+                    .map(|m| BikeMediaWithAltText {
+                        alt_text: alt_texts.iter()
+                            .find(|t| t.bike_media_id == m.id)
+                            .map(|t| t.alt_text.clone()),
+                        id: m.id,
+                        position: m.position,
+                        url: m.url,
+                        width: m.width,
+                        height: m.height,
+                        thumbnail_url: m.thumbnail_url,
+                        thumbnail_width: m.thumbnail_width,
+                        thumbnail_height: m.thumbnail_height,
+                    })
+                    .collect(),
+            })
+        })
+        .collect())
+}
+
+#[cfg_attr(test, mockable)]
+pub fn get_bike_media(db: &DbConn, p_bike_id: i32) -> Result<Vec<BikeMedia>, diesel::result::Error> {
+    bike_media
+        .filter(bike_id.eq(p_bike_id))
+        .order_by((position, id))
+        .load::<BikeMedia>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn insert_bike_media(db: &DbConn, media: &InsertBikeMedia) -> Result<BikeMedia, diesel::result::Error> {
+    insert_into(bike_media)
+        .values(media)
+        .get_result::<BikeMedia>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn update_bike_media_position(db: &DbConn, p_bike_id: i32, p_bike_media_id: i32, p_position: i32) -> Result<BikeMedia, diesel::result::Error> {
+    update(bike_media.filter(bike_id.eq(p_bike_id).and(id.eq(p_bike_media_id))))
+        .set(position.eq(p_position))
+        .get_result::<BikeMedia>(&**db)
+}
+
+/// Deletes the media entry and its alt texts. The stored files are kept as
+/// identical uploads share them.
+#[cfg_attr(test, mockable)]
+pub fn delete_bike_media(db: &DbConn, p_bike_id: i32, p_bike_media_id: i32) -> Result<usize, diesel::result::Error> {
+    delete(bike_media.filter(bike_id.eq(p_bike_id).and(id.eq(p_bike_media_id))))
+        .execute(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn get_bike_media_translations(db: &DbConn, p_bike_media_id: i32) -> Result<Vec<BikeMediaTranslatable>, diesel::result::Error> {
+    BikeMediaTranslations::load_translations_of(db, p_bike_media_id)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn upsert_bike_media_translation(db: &DbConn, translation: &InsertBikeMediaTranslatable) -> Result<BikeMediaTranslatable, diesel::result::Error> {
+    translatable::upsert_translation::<BikeMediaTranslations>(db, translation)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn get_bike_status_history(db: &DbConn, p_bike_id: i32) -> Result<Vec<BikeStatusChange>, diesel::result::Error> {
+    bike_status_changes::table
+        .filter(bike_status_changes::bike_id.eq(p_bike_id))
+        .order_by((bike_status_changes::changed_at.desc(), bike_status_changes::id.desc()))
+        .load::<BikeStatusChange>(&**db)
+}
+
+/// Sets the status of the bike and records the change. Retiring a bike flags
+/// every upcoming rent of it, so the operators can reach out to the renters.
+#[cfg_attr(test, mockable)]
+pub fn update_bike_status(db: &DbConn, change: &InsertBikeStatusChange) -> Result<BikeStatusUpdate, diesel::result::Error> {
+    (*db).transaction(|| {
+        update(bikes::table.find(change.bike_id))
+            .set(bikes::status.eq(&change.status))
+            .get_result::<Bike>(&**db)?;
+
+        let recorded = insert_into(bike_status_changes::table)
+            .values(change)
+            .get_result::<BikeStatusChange>(&**db)?;
+
+        let flagged_rents = if change.status == BikeStatus::Retired.as_str() {
+            update(rents::table
+                    .filter(rents::bike_id.eq(change.bike_id))
+                    .filter(rents::end_timestamp.gt(recorded.changed_at))
+                    .filter(rents::revocation_timestamp.is_null())
+                    .filter(rents::flagged_at.is_null()))
+                .set((
+                    rents::flagged_at.eq(Utc::now().naive_utc()),
+                    rents::flag_reason.eq("The bike has been retired."),
+                ))
+                .get_results::<Rent>(&**db)?
+        } else {
+            vec![]
+        };
+
+        Ok(BikeStatusUpdate {
+            change: recorded,
+            flagged_rents,
+        })
+    })
+}
+
+#[cfg_attr(test, mockable)]
+pub fn get_bike(db: &DbConn, p_bike_id: i32) -> Result<Bike, diesel::result::Error> {
+    bikes::table
+        .find(p_bike_id)
+        .get_result::<Bike>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn update_bike_buffers(db: &DbConn, p_bike_id: i32, buffers: &BikeBufferData) -> Result<Bike, diesel::result::Error> {
+    update(bikes::table.find(p_bike_id))
+        .set(buffers)
+        .get_result::<Bike>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn update_bike_granularity(db: &DbConn, p_bike_id: i32, granularity: BookingGranularity) -> Result<Bike, diesel::result::Error> {
+    update(bikes::table.find(p_bike_id))
+        .set(bikes::booking_granularity.eq(granularity.as_str()))
+        .get_result::<Bike>(&**db)
+}
 
 #[cfg_attr(test, mockable)]
-pub fn get_bikes(db: &DbConn) -> Result<Vec<BikeTranslatable>, diesel::result::Error> {
-    bike_translatables.load::<BikeTranslatable>(&**db)
-}
\ No newline at end of file
+pub fn update_bike_approval(db: &DbConn, p_bike_id: i32, p_requires_approval: bool) -> Result<Bike, diesel::result::Error> {
+    update(bikes::table.find(p_bike_id))
+        .set(bikes::requires_approval.eq(p_requires_approval))
+        .get_result::<Bike>(&**db)
+}
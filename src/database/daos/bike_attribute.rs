@@ -0,0 +1,146 @@
+#[cfg(test)]
+use mocktopus::macros::mockable;
+
+use diesel::{RunQueryDsl,QueryDsl,BoolExpressionMethods,ExpressionMethods};
+use diesel::{delete,insert_into,update};
+
+use crate::database::DbConn;
+use crate::database::daos::translatable;
+use crate::database::daos::translatable::{BikeAttributeOptionTranslations,BikeAttributeTranslations};
+use crate::database::models::{AttributeType,AttributeValue,BikeAttribute,BikeAttributeOption,BikeAttributeOptionTranslatable,BikeAttributeTranslatable,BikeAttributeValue,BikeAttributeWithValue,InsertBikeAttribute,InsertBikeAttributeOption,InsertBikeAttributeOptionTranslatable,InsertBikeAttributeTranslatable,InsertBikeAttributeValue};
+use crate::locale::LocalePreferences;
+use crate::schema::bike_attributes;
+use crate::schema::bike_attribute_options;
+use crate::schema::bike_attribute_values;
+
+/// Attribute values of all bikes with their negotiated labels, ordered by the
+/// sort priority of the attribute. Values not matching the attribute type are
+/// skipped.
+pub fn get_attributes_by_bike(db: &DbConn, preferences: &LocalePreferences) -> Result<Vec<(i32, BikeAttributeWithValue)>, diesel::result::Error> {
+    let attributes = get_bike_attributes(db)?;
+    let labels = translatable::load_by_locale::<BikeAttributeTranslations>(db, preferences)?;
+    let options = bike_attribute_options::table.load::<BikeAttributeOption>(&**db)?;
+    let option_labels = translatable::load_by_locale::<BikeAttributeOptionTranslations>(db, preferences)?;
+    let values = bike_attribute_values::table
+        .order_by((bike_attribute_values::bike_id, bike_attribute_values::id))
+        .load::<BikeAttributeValue>(&**db)?;
+
+    let mut result = vec![];
+
+    for attribute in &attributes {
+        let attribute_type = match attribute.value_type.parse::<AttributeType>() {
+            Ok(attribute_type) => attribute_type,
+            Err(_) => continue,
+        };
+
+        for value in values.iter().filter(|v| v.bike_attribute_id == attribute.id) {
+            let (typed, value_label) = match attribute_type {
+                AttributeType::Number => (value.number_value.map(AttributeValue::Number), None),
+                AttributeType::Boolean => (value.boolean_value.map(AttributeValue::Boolean), None),
+                AttributeType::Enum => match options.iter().find(|o| Some(o.id) == value.bike_attribute_option_id) {
+                    Some(option) => (
+                        Some(AttributeValue::Option(option.key.clone())),
+                        option_labels.iter().find(|l| l.bike_attribute_option_id == option.id).map(|l| l.label.clone()),
+                    ),
+                    None => (None, None),
+                },
+            };
+
+            if let Some(typed) = typed {
+                result.push((value.bike_id, BikeAttributeWithValue {
+                    key: attribute.key.clone(),
+                    label: labels.iter().find(|l| l.bike_attribute_id == attribute.id).map(|l| l.label.clone()),
+                    unit: attribute.unit.clone(),
+                    value: typed,
+                    value_label,
+                }));
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn get_bike_attributes(db: &DbConn) -> Result<Vec<BikeAttribute>, diesel::result::Error> {
+    bike_attributes::table
+        .order_by((bike_attributes::sort_priority.desc(), bike_attributes::id))
+        .load::<BikeAttribute>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn get_bike_attribute(db: &DbConn, p_bike_attribute_id: i32) -> Result<BikeAttribute, diesel::result::Error> {
+    bike_attributes::table
+        .find(p_bike_attribute_id)
+        .get_result::<BikeAttribute>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn insert_bike_attribute(db: &DbConn, attribute: &InsertBikeAttribute) -> Result<BikeAttribute, diesel::result::Error> {
+    insert_into(bike_attributes::table)
+        .values(attribute)
+        .get_result::<BikeAttribute>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn update_bike_attribute(db: &DbConn, p_bike_attribute_id: i32, attribute: &InsertBikeAttribute) -> Result<BikeAttribute, diesel::result::Error> {
+    update(bike_attributes::table.find(p_bike_attribute_id))
+        .set(attribute)
+        .get_result::<BikeAttribute>(&**db)
+}
+
+/// Deletes the attribute together with its options, labels and values.
+#[cfg_attr(test, mockable)]
+pub fn delete_bike_attribute(db: &DbConn, p_bike_attribute_id: i32) -> Result<usize, diesel::result::Error> {
+    delete(bike_attributes::table.find(p_bike_attribute_id))
+        .execute(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn upsert_bike_attribute_translation(db: &DbConn, translation: &InsertBikeAttributeTranslatable) -> Result<BikeAttributeTranslatable, diesel::result::Error> {
+    translatable::upsert_translation::<BikeAttributeTranslations>(db, translation)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn get_bike_attribute_options(db: &DbConn, p_bike_attribute_id: i32) -> Result<Vec<BikeAttributeOption>, diesel::result::Error> {
+    bike_attribute_options::table
+        .filter(bike_attribute_options::bike_attribute_id.eq(p_bike_attribute_id))
+        .order_by(bike_attribute_options::id)
+        .load::<BikeAttributeOption>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn insert_bike_attribute_option(db: &DbConn, option: &InsertBikeAttributeOption) -> Result<BikeAttributeOption, diesel::result::Error> {
+    insert_into(bike_attribute_options::table)
+        .values(option)
+        .get_result::<BikeAttributeOption>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn delete_bike_attribute_option(db: &DbConn, p_bike_attribute_id: i32, p_bike_attribute_option_id: i32) -> Result<usize, diesel::result::Error> {
+    delete(bike_attribute_options::table
+            .filter(bike_attribute_options::bike_attribute_id.eq(p_bike_attribute_id).and(bike_attribute_options::id.eq(p_bike_attribute_option_id))))
+        .execute(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn upsert_bike_attribute_option_translation(db: &DbConn, translation: &InsertBikeAttributeOptionTranslatable) -> Result<BikeAttributeOptionTranslatable, diesel::result::Error> {
+    translatable::upsert_translation::<BikeAttributeOptionTranslations>(db, translation)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn upsert_bike_attribute_value(db: &DbConn, value: &InsertBikeAttributeValue) -> Result<BikeAttributeValue, diesel::result::Error> {
+    insert_into(bike_attribute_values::table)
+        .values(value)
+        .on_conflict((bike_attribute_values::bike_id, bike_attribute_values::bike_attribute_id))
+        .do_update()
+        .set(value)
+        .get_result::<BikeAttributeValue>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn delete_bike_attribute_value(db: &DbConn, p_bike_id: i32, p_bike_attribute_id: i32) -> Result<usize, diesel::result::Error> {
+    delete(bike_attribute_values::table
+            .filter(bike_attribute_values::bike_id.eq(p_bike_id).and(bike_attribute_values::bike_attribute_id.eq(p_bike_attribute_id))))
+        .execute(&**db)
+}
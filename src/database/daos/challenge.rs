@@ -1,23 +1,41 @@
 #[cfg(test)]
 use mocktopus::macros::mockable;
 
-use diesel::{RunQueryDsl,QueryDsl,BoolExpressionMethods,ExpressionMethods};
-use diesel::{insert_into};
+use chrono::NaiveDateTime;
+
+use diesel::{Connection,RunQueryDsl,QueryDsl,BoolExpressionMethods,ExpressionMethods};
+use diesel::{delete,insert_into};
 
 use crate::database::DbConn;
+use crate::database::daos::token;
 use crate::database::models::{TokenChallengeTranslatable, ChallengeResponse, Token};
-use crate::schema::tokens::dsl::*;
+use crate::locale::LocalePreferences;
+use crate::schema::proof_of_work_nonces;
 use crate::schema::token_challenge_translatables::dsl::*;
 
+use crate::routes::errors::ChallengeError;
+
 no_arg_sql_function!(RANDOM, (), "Represents the sql RANDOM() function");
 
+/// Picks a random challenge in the locale which matches the preferences or
+/// the fallback chain best. Without any match a challenge of any locale is
+/// picked.
 #[cfg_attr(test, mockable)]
-pub fn get_random_challenge(db: &DbConn, p_locale: &String) -> Result<TokenChallengeTranslatable, diesel::result::Error> {
-    token_challenge_translatables
-        .filter(crate::schema::token_challenge_translatables::dsl::locale.eq(p_locale))
+pub fn get_random_challenge(db: &DbConn, preferences: &LocalePreferences) -> Result<TokenChallengeTranslatable, diesel::result::Error> {
+    let locales = token_challenge_translatables
+        .select(locale)
+        .distinct()
+        .load::<String>(&**db)?;
+
+    let mut query = token_challenge_translatables
         .order(RANDOM)
-        .limit(1)
-        .get_result::<TokenChallengeTranslatable>(&**db)
+        .into_boxed();
+
+    if let Some(index) = preferences.best_match(&locales) {
+        query = query.filter(locale.eq(&locales[index]));
+    }
+
+    query.first::<TokenChallengeTranslatable>(&**db)
 }
 
 #[cfg_attr(test, mockable)]
@@ -26,7 +44,35 @@ pub fn test_challenge(db: &DbConn, challenge_response: &ChallengeResponse) -> Re
         .filter(token_challenge_id.eq(challenge_response.token_challenge_id).and(answer_hash.eq(challenge_response.answer_hash.to_string())))
         .get_result::<TokenChallengeTranslatable>(&**db)?;
 
-    insert_into(tokens)
-        .default_values()
-        .get_result::<Token>(&**db)
-}
\ No newline at end of file
+    token::insert_token(db)
+}
+
+/// Mints a token for a solved proof of work challenge. The nonce of the
+/// challenge is kept until the challenge expires, so every challenge can be
+/// redeemed once only.
+#[cfg_attr(test, mockable)]
+pub fn redeem_proof_of_work(db: &DbConn, p_nonce: &str, p_expires_at: NaiveDateTime) -> Result<Token, ChallengeError> {
+    (*db).transaction(|| {
+        let inserted = insert_into(proof_of_work_nonces::table)
+            .values((
+                proof_of_work_nonces::nonce.eq(p_nonce),
+                proof_of_work_nonces::expires_at.eq(p_expires_at),
+            ))
+            .on_conflict_do_nothing()
+            .execute(&**db)?;
+
+        if inserted == 0 {
+            return Err(ChallengeError::Validation(String::from("The challenge has already been solved.")));
+        }
+
+        Ok(token::insert_token(db)?)
+    })
+}
+
+/// Nonces of expired challenges are not needed any more, the challenges are
+/// rejected for their expiry anyway.
+#[cfg_attr(test, mockable)]
+pub fn delete_expired_proof_of_work_nonces(db: &DbConn, now: NaiveDateTime) -> Result<usize, diesel::result::Error> {
+    delete(proof_of_work_nonces::table.filter(proof_of_work_nonces::expires_at.lt(now)))
+        .execute(&**db)
+}
@@ -0,0 +1,59 @@
+#[cfg(test)]
+use mocktopus::macros::mockable;
+
+use diesel::{RunQueryDsl,QueryDsl,ExpressionMethods};
+use diesel::insert_into;
+
+use crate::database::DbConn;
+use crate::database::models::{BikeFeedbackSummary,InsertRentFeedback,RentFeedback};
+use crate::schema::rent_feedbacks;
+use crate::schema::rents;
+
+/// Fails with a unique violation if the rent already has feedback.
+#[cfg_attr(test, mockable)]
+pub fn insert_feedback(db: &DbConn, feedback: &InsertRentFeedback) -> Result<RentFeedback, diesel::result::Error> {
+    insert_into(rent_feedbacks::table)
+        .values(feedback)
+        .get_result::<RentFeedback>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn get_feedback_by_bike(db: &DbConn, p_bike_id: i32) -> Result<Vec<RentFeedback>, diesel::result::Error> {
+    rent_feedbacks::table
+        .inner_join(rents::table)
+        .filter(rents::bike_id.eq(p_bike_id))
+        .select(rent_feedbacks::all_columns)
+        .order_by((rent_feedbacks::created_at.desc(), rent_feedbacks::id.desc()))
+        .load::<RentFeedback>(&**db)
+}
+
+/// Feedback aggregated per bike, bikes without feedback are left out.
+#[cfg_attr(test, mockable)]
+pub fn get_feedback_summaries(db: &DbConn) -> Result<Vec<BikeFeedbackSummary>, diesel::result::Error> {
+    let feedback = rent_feedbacks::table
+        .inner_join(rents::table)
+        .select((rents::bike_id, rent_feedbacks::rating, rent_feedbacks::distance_km))
+        .order_by(rents::bike_id)
+        .load::<(i32, i16, Option<f64>)>(&**db)?;
+
+    let mut summaries: Vec<BikeFeedbackSummary> = vec![];
+
+    for (bike_id, rating, distance_km) in feedback {
+        if summaries.last().map_or(true, |summary| summary.bike_id != bike_id) {
+            summaries.push(BikeFeedbackSummary {
+                bike_id,
+                feedback_count: 0,
+                average_rating: 0.0,
+                total_distance_km: 0.0,
+            });
+        }
+
+        if let Some(summary) = summaries.last_mut() {
+            summary.feedback_count += 1;
+            summary.average_rating += (f64::from(rating) - summary.average_rating) / summary.feedback_count as f64;
+            summary.total_distance_km += distance_km.unwrap_or(0.0);
+        }
+    }
+
+    Ok(summaries)
+}
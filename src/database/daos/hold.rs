@@ -0,0 +1,136 @@
+#[cfg(test)]
+use mocktopus::macros::mockable;
+
+use ::uuid::Uuid;
+use chrono::{Duration,NaiveDateTime,Utc};
+
+use diesel::{Connection,RunQueryDsl,QueryDsl,ExpressionMethods,OptionalExtension};
+use diesel::{delete,insert_into};
+
+use crate::config;
+use crate::database::DbConn;
+use crate::database::daos::{availability,token};
+use crate::database::models::{Bike,HoldRequest,InsertRentHold,InsertRentHoldAccessory,RentHold};
+use crate::schema::bikes;
+use crate::schema::rent_hold_accessories;
+use crate::schema::rent_holds;
+
+use crate::routes::errors::RentError;
+
+#[cfg_attr(test, mockable)]
+pub fn insert_hold(db: &DbConn, hold: &InsertRentHold) -> Result<RentHold, diesel::result::Error> {
+    insert_into(rent_holds::table)
+        .values(hold)
+        .get_result::<RentHold>(&**db)
+}
+
+/// Holds the slot for the rider while the booking is being completed. Only
+/// free slots can be held, so a hold cannot take a slot from anybody else.
+/// Holding needs a token which could still be booked with, and each token
+/// holds a few short windows at most. Accessories can be held with the bike.
+#[cfg_attr(test, mockable)]
+pub fn create_hold(db: &DbConn, request: &HoldRequest) -> Result<RentHold, RentError> {
+    (*db).transaction(|| {
+        if request.start_timestamp >= request.end_timestamp {
+            return Err(RentError::Validation(String::from("The rent has to end after it starts.")));
+        }
+
+        if request.end_timestamp - request.start_timestamp > Duration::hours(config::max_hold_window_hours()) {
+            return Err(RentError::Validation(format!("At most {} hours can be held.", config::max_hold_window_hours())));
+        }
+
+        // Also serializes concurrent holds and bookings of the token, so the
+        // holds are counted reliably.
+        let token = token::get_unused_token(db, &request.token)?;
+
+        let active_holds = rent_holds::table
+            .filter(rent_holds::token_id.eq(token.id))
+            .filter(rent_holds::expires_at.gt(Utc::now().naive_utc()))
+            .count()
+            .get_result::<i64>(&**db)?;
+
+        if active_holds >= config::max_holds_per_token() {
+            return Err(RentError::Validation(String::from("The token already holds as many slots as it may.")));
+        }
+
+        // Serializes concurrent holds and bookings of the bike.
+        bikes::table
+            .find(request.bike_id)
+            .for_update()
+            .get_result::<Bike>(&**db)?;
+
+        let (start, end) = availability::align_window(db, &[request.bike_id], request.start_timestamp, request.end_timestamp)?;
+
+        if !availability::is_bike_available(db, request.bike_id, start, end)? {
+            return Err(RentError::Validation(String::from("There is already a rent at the same period.")));
+        }
+
+        let requested_accessories = availability::check_accessories(db, &request.accessories, &[request.bike_id], start, end)?;
+
+        let hold = insert_hold(db, &InsertRentHold {
+            bike_id: request.bike_id,
+            start_timestamp: start,
+            end_timestamp: end,
+            expires_at: Utc::now().naive_utc() + Duration::minutes(config::hold_minutes()),
+            waitlist_entry_id: None,
+            token_id: Some(token.id),
+        })?;
+
+        let held_accessories = requested_accessories.iter()
+            .map(|requested| InsertRentHoldAccessory {
+                rent_hold_id: hold.id,
+                accessory_id: requested.accessory_id,
+                quantity: requested.quantity,
+            })
+            .collect::<Vec<_>>();
+
+        if !held_accessories.is_empty() {
+            insert_into(rent_hold_accessories::table)
+                .values(&held_accessories)
+                .execute(&**db)?;
+        }
+
+        Ok(hold)
+    })
+}
+
+/// Gives up a hold before it expires.
+#[cfg_attr(test, mockable)]
+pub fn delete_hold(db: &DbConn, p_uuid: &Uuid) -> Result<usize, diesel::result::Error> {
+    delete(rent_holds::table.filter(rent_holds::uuid.eq(p_uuid)))
+        .execute(&**db)
+}
+
+/// Removes the hold so that the booking of one of its bikes within its window
+/// can take the slot. Has to run in the booking's transaction.
+pub fn take_hold(db: &DbConn, p_uuid: &Uuid, p_token_id: i32, bike_ids: &[i32], start: NaiveDateTime, end: NaiveDateTime) -> Result<RentHold, RentError> {
+    let hold = rent_holds::table
+        .filter(rent_holds::uuid.eq(p_uuid))
+        .for_update()
+        .get_result::<RentHold>(&**db)
+        .optional()?
+        .ok_or_else(|| RentError::Validation(String::from("The hold is not known.")))?;
+
+    if hold.expires_at <= Utc::now().naive_utc() {
+        return Err(RentError::Validation(String::from("The hold has expired.")));
+    }
+
+    if hold.token_id.map_or(false, |holder| holder != p_token_id) {
+        return Err(RentError::Validation(String::from("The hold belongs to another token.")));
+    }
+
+    if !bike_ids.contains(&hold.bike_id) || start < hold.start_timestamp || end > hold.end_timestamp {
+        return Err(RentError::Validation(String::from("The booking does not match the hold.")));
+    }
+
+    delete(rent_holds::table.find(hold.id))
+        .execute(&**db)?;
+
+    Ok(hold)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn delete_expired_holds(db: &DbConn, as_of: NaiveDateTime) -> Result<usize, diesel::result::Error> {
+    delete(rent_holds::table.filter(rent_holds::expires_at.le(as_of)))
+        .execute(&**db)
+}
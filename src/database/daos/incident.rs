@@ -0,0 +1,98 @@
+#[cfg(test)]
+use mocktopus::macros::mockable;
+
+use diesel::{Connection,RunQueryDsl,QueryDsl,ExpressionMethods};
+use diesel::insert_into;
+
+use crate::database::DbConn;
+use crate::database::models::{BikeIncident,BikeIncidentPhoto,BikeIncidentWithPhotos,InsertBikeIncident,InsertBikeIncidentPhoto};
+use crate::schema::bike_incident_photos;
+use crate::schema::bike_incidents;
+
+use crate::routes::errors::IncidentError;
+
+fn with_photos(db: &DbConn, incidents: Vec<BikeIncident>) -> Result<Vec<BikeIncidentWithPhotos>, diesel::result::Error> {
+    let ids = incidents.iter().map(|incident| incident.id).collect::<Vec<_>>();
+    let photos = bike_incident_photos::table
+        .filter(bike_incident_photos::bike_incident_id.eq_any(ids))
+        .order_by(bike_incident_photos::id)
+        .load::<BikeIncidentPhoto>(&**db)?;
+
+    Ok(incidents.into_iter()
+        .map(|incident| BikeIncidentWithPhotos {
+            photos: photos.iter()
+                .filter(|photo| photo.bike_incident_id == incident.id)
+                .cloned()
+                .collect(),
+            incident,
+        })
+        .collect())
+}
+
+/// Incidents of all bikes, newest first.
+#[cfg_attr(test, mockable)]
+pub fn get_incidents(db: &DbConn) -> Result<Vec<BikeIncidentWithPhotos>, diesel::result::Error> {
+    let incidents = bike_incidents::table
+        .order_by((bike_incidents::created_at.desc(), bike_incidents::id.desc()))
+        .load::<BikeIncident>(&**db)?;
+
+    with_photos(db, incidents)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn get_incidents_by_bike(db: &DbConn, p_bike_id: i32) -> Result<Vec<BikeIncidentWithPhotos>, diesel::result::Error> {
+    let incidents = bike_incidents::table
+        .filter(bike_incidents::bike_id.eq(p_bike_id))
+        .order_by((bike_incidents::created_at.desc(), bike_incidents::id.desc()))
+        .load::<BikeIncident>(&**db)?;
+
+    with_photos(db, incidents)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn get_incident(db: &DbConn, p_bike_incident_id: i32) -> Result<BikeIncident, diesel::result::Error> {
+    bike_incidents::table
+        .find(p_bike_incident_id)
+        .get_result::<BikeIncident>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn insert_incident(db: &DbConn, incident: &InsertBikeIncident) -> Result<BikeIncident, diesel::result::Error> {
+    insert_into(bike_incidents::table)
+        .values(incident)
+        .get_result::<BikeIncident>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn count_incident_photos(db: &DbConn, p_bike_incident_id: i32) -> Result<i64, diesel::result::Error> {
+    bike_incident_photos::table
+        .filter(bike_incident_photos::bike_incident_id.eq(p_bike_incident_id))
+        .count()
+        .get_result::<i64>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn insert_incident_photo(db: &DbConn, photo: &InsertBikeIncidentPhoto) -> Result<BikeIncidentPhoto, diesel::result::Error> {
+    insert_into(bike_incident_photos::table)
+        .values(photo)
+        .get_result::<BikeIncidentPhoto>(&**db)
+}
+
+/// Attaches the photo unless the incident already has `limit` photos. The
+/// incident stays locked until the photo is inserted, so concurrent uploads
+/// cannot exceed the limit.
+#[cfg_attr(test, mockable)]
+pub fn add_incident_photo(db: &DbConn, photo: &InsertBikeIncidentPhoto, limit: i64) -> Result<BikeIncidentPhoto, IncidentError> {
+    (*db).transaction(|| {
+        bike_incidents::table
+            .find(photo.bike_incident_id)
+            .for_update()
+            .get_result::<BikeIncident>(&**db)?;
+
+        if count_incident_photos(db, photo.bike_incident_id)? >= limit {
+            return Err(IncidentError::Validation(String::from("No more photos can be added to this incident.")));
+        }
+
+        Ok(insert_incident_photo(db, photo)?)
+    })
+}
@@ -0,0 +1,156 @@
+#[cfg(test)]
+use mocktopus::macros::mockable;
+
+use chrono::Utc;
+
+use rand::Rng;
+
+use diesel::{Connection,RunQueryDsl,QueryDsl,ExpressionMethods,NullableExpressionMethods,OptionalExtension};
+use diesel::{insert_into,sql_query};
+use diesel::sql_types::{BigInt,Integer};
+
+use crate::database::DbConn;
+use crate::database::daos::token;
+use crate::database::models::{InsertInviteCode,InsertInviteCodeRedemption,InviteCode,InviteCodeRedemptionWithRent,InviteCodeRequest,InviteCodeWithUsage,Token};
+use crate::schema::invite_codes::dsl::*;
+use crate::schema::invite_code_redemptions::dsl::*;
+use crate::schema::rents;
+use crate::schema::tokens;
+
+use crate::routes::errors::InviteError;
+
+// Letters and digits which cannot be confused with each other when a code is
+// read out loud or typed in from a printed voucher.
+const CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const CODE_LENGTH: usize = 10;
+const MAX_CODES_PER_REQUEST: u32 = 500;
+
+fn generate_code() -> String {
+    let mut rng = rand::thread_rng();
+
+    (0..CODE_LENGTH)
+        .map(|_| CODE_ALPHABET[rng.gen_range(0..CODE_ALPHABET.len())] as char)
+        .collect()
+}
+
+#[cfg_attr(test, mockable)]
+pub fn insert_invite_codes(db: &DbConn, request: &InviteCodeRequest, author: &str) -> Result<Vec<InviteCode>, InviteError> {
+    let count = request.count.unwrap_or(1);
+
+    if count == 0 || count > MAX_CODES_PER_REQUEST {
+        return Err(InviteError::Validation(format!("Between 1 and {} codes can be generated at once.", MAX_CODES_PER_REQUEST)));
+    }
+
+    if request.usage_limit < 1 {
+        return Err(InviteError::Validation(String::from("The usage limit has to be at least 1.")));
+    }
+
+    if let (Some(from), Some(until)) = (request.valid_from, request.valid_until) {
+        if from >= until {
+            return Err(InviteError::Validation(String::from("The validity window is empty.")));
+        }
+    }
+
+    let values = (0..count)
+        .map(|_| InsertInviteCode {
+            code: generate_code(),
+            group_name: request.group_name.clone(),
+            usage_limit: request.usage_limit,
+            valid_from: request.valid_from,
+            valid_until: request.valid_until,
+            created_by: author.to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    Ok(insert_into(invite_codes)
+        .values(&values)
+        .get_results::<InviteCode>(&**db)?)
+}
+
+#[derive(QueryableByName)]
+struct UsageCount {
+    #[sql_type = "Integer"]
+    invite_code_id: i32,
+    #[sql_type = "BigInt"]
+    usage_count: i64,
+}
+
+const USAGE_COUNTS_QUERY: &str = "
+    SELECT  r.invite_code_id, COUNT (*) AS usage_count
+    FROM    invite_code_redemptions r
+    GROUP   BY r.invite_code_id";
+
+#[cfg_attr(test, mockable)]
+pub fn get_invite_codes(db: &DbConn) -> Result<Vec<InviteCodeWithUsage>, diesel::result::Error> {
+    let codes = invite_codes
+        .order_by(crate::schema::invite_codes::dsl::id)
+        .load::<InviteCode>(&**db)?;
+    let usage_counts = sql_query(USAGE_COUNTS_QUERY)
+        .load::<UsageCount>(&**db)?;
+
+    Ok(codes.into_iter()
+        .map(|invite_code| InviteCodeWithUsage {
+            usage_count: usage_counts.iter().find(|usage| usage.invite_code_id == invite_code.id).map_or(0, |usage| usage.usage_count),
+            invite_code,
+        })
+        .collect())
+}
+
+#[cfg_attr(test, mockable)]
+pub fn get_redemptions(db: &DbConn, p_invite_code_id: i32) -> Result<Vec<InviteCodeRedemptionWithRent>, diesel::result::Error> {
+    invite_code_redemptions
+        .left_join(tokens::table.left_join(rents::table))
+        .filter(invite_code_id.eq(p_invite_code_id))
+        .select((
+            crate::schema::invite_code_redemptions::dsl::id,
+            redeemed_at,
+            rents::id.nullable(),
+            rents::bike_id.nullable(),
+            rents::start_timestamp.nullable(),
+            rents::end_timestamp.nullable(),
+            rents::revocation_timestamp.nullable(),
+        ))
+        .order_by(redeemed_at)
+        .load::<InviteCodeRedemptionWithRent>(&**db)
+}
+
+/// Mints a new token if the invite code is known, within its validity window
+/// and not yet used up. Every redemption is recorded together with the token.
+#[cfg_attr(test, mockable)]
+pub fn redeem_invite_code(db: &DbConn, p_code: &str) -> Result<Token, InviteError> {
+    (*db).transaction(|| {
+        let invite_code = invite_codes
+            .filter(code.eq(p_code.trim().to_uppercase()))
+            .for_update()
+            .get_result::<InviteCode>(&**db)
+            .optional()?
+            .ok_or(InviteError::Validation(String::from("The invite code is not known.")))?;
+
+        let now = Utc::now().naive_utc();
+
+        if invite_code.valid_from.map_or(false, |from| now < from)
+            || invite_code.valid_until.map_or(false, |until| now > until) {
+            return Err(InviteError::Validation(String::from("The invite code is not valid at the moment.")));
+        }
+
+        let usage_count = invite_code_redemptions
+            .filter(invite_code_id.eq(invite_code.id))
+            .count()
+            .get_result::<i64>(&**db)?;
+
+        if usage_count >= invite_code.usage_limit as i64 {
+            return Err(InviteError::Validation(String::from("The invite code has been used up.")));
+        }
+
+        let token = token::insert_token(db)?;
+
+        insert_into(invite_code_redemptions)
+            .values(InsertInviteCodeRedemption {
+                invite_code_id: invite_code.id,
+                token_id: Some(token.id),
+            })
+            .execute(&**db)?;
+
+        Ok(token)
+    })
+}
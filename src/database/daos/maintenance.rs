@@ -0,0 +1,147 @@
+#[cfg(test)]
+use mocktopus::macros::mockable;
+
+use chrono::NaiveDateTime;
+
+use diesel::{RunQueryDsl,QueryDsl,BoolExpressionMethods,ExpressionMethods};
+use diesel::{delete,insert_into,sql_query,update};
+use diesel::sql_types::{Array,Integer,Timestamptz};
+
+use crate::database::DbConn;
+use crate::database::models::{BikeServiceInterval,BikeWorkOrder,InsertBikeServiceInterval,InsertBikeWorkOrder,ServiceDue};
+use crate::schema::bike_service_intervals;
+use crate::schema::bike_work_orders;
+
+#[cfg_attr(test, mockable)]
+pub fn get_service_intervals(db: &DbConn, p_bike_id: i32) -> Result<Vec<BikeServiceInterval>, diesel::result::Error> {
+    bike_service_intervals::table
+        .filter(bike_service_intervals::bike_id.eq(p_bike_id))
+        .order_by(bike_service_intervals::id)
+        .load::<BikeServiceInterval>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn insert_service_interval(db: &DbConn, interval: &InsertBikeServiceInterval) -> Result<BikeServiceInterval, diesel::result::Error> {
+    insert_into(bike_service_intervals::table)
+        .values(interval)
+        .get_result::<BikeServiceInterval>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn update_service_interval(db: &DbConn, p_bike_service_interval_id: i32, interval: &InsertBikeServiceInterval) -> Result<BikeServiceInterval, diesel::result::Error> {
+    update(bike_service_intervals::table
+            .filter(bike_service_intervals::bike_id.eq(interval.bike_id).and(bike_service_intervals::id.eq(p_bike_service_interval_id))))
+        .set(interval)
+        .get_result::<BikeServiceInterval>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn delete_service_interval(db: &DbConn, p_bike_id: i32, p_bike_service_interval_id: i32) -> Result<usize, diesel::result::Error> {
+    delete(bike_service_intervals::table
+            .filter(bike_service_intervals::bike_id.eq(p_bike_id).and(bike_service_intervals::id.eq(p_bike_service_interval_id))))
+        .execute(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn get_work_orders(db: &DbConn, p_bike_id: i32) -> Result<Vec<BikeWorkOrder>, diesel::result::Error> {
+    bike_work_orders::table
+        .filter(bike_work_orders::bike_id.eq(p_bike_id))
+        .order_by((bike_work_orders::performed_at.desc(), bike_work_orders::id.desc()))
+        .load::<BikeWorkOrder>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn insert_work_order(db: &DbConn, work_order: &InsertBikeWorkOrder) -> Result<BikeWorkOrder, diesel::result::Error> {
+    insert_into(bike_work_orders::table)
+        .values(work_order)
+        .get_result::<BikeWorkOrder>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn delete_work_order(db: &DbConn, p_bike_id: i32, p_bike_work_order_id: i32) -> Result<usize, diesel::result::Error> {
+    delete(bike_work_orders::table
+            .filter(bike_work_orders::bike_id.eq(p_bike_id).and(bike_work_orders::id.eq(p_bike_work_order_id))))
+        .execute(&**db)
+}
+
+/// Service intervals which have run out at `as_of`, see get_due_services.
+/// Rents count once they have ended and were not revoked.
+macro_rules! due_services_query {
+    () => {"
+        SELECT  i.bike_id,
+                i.id AS bike_service_interval_id,
+                i.title,
+                s.last_service_at,
+                EXTRACT (DAY FROM $1 - s.last_service_at)::BIGINT AS days_since,
+                r.rents_since,
+                i.blocks_bookings,
+                i.notified_at
+        FROM    bike_service_intervals i
+        CROSS   JOIN LATERAL
+        (
+            SELECT  COALESCE (MAX (w.performed_at), i.created_at) AS last_service_at
+            FROM    bike_work_orders w
+            WHERE   w.bike_service_interval_id = i.id
+            AND     w.performed_at <= $1
+        ) s
+        CROSS   JOIN LATERAL
+        (
+            SELECT  COUNT (*) AS rents_since
+            FROM    rents r
+            WHERE   r.bike_id = i.bike_id
+            AND     r.revocation_timestamp IS NULL
+            AND     r.end_timestamp > s.last_service_at
+            AND     r.end_timestamp <= $1
+        ) r
+        WHERE   s.last_service_at + i.interval_days * INTERVAL '1 day' <= $1
+        OR      i.interval_rents <= r.rents_since"
+    }
+}
+
+const DUE_SERVICES_QUERY: &str = concat!("
+    SELECT  d.*
+    FROM    (", due_services_query!(), "
+    ) d
+    ORDER   BY d.bike_id, d.bike_service_interval_id");
+
+/// Service intervals of all bikes which have run out at `as_of`.
+#[cfg_attr(test, mockable)]
+pub fn get_due_services(db: &DbConn, as_of: NaiveDateTime) -> Result<Vec<ServiceDue>, diesel::result::Error> {
+    sql_query(DUE_SERVICES_QUERY)
+        .bind::<Timestamptz, _>(as_of)
+        .load::<ServiceDue>(&**db)
+}
+
+#[derive(QueryableByName)]
+struct BlockedBike {
+    #[sql_type = "Integer"]
+    bike_id: i32,
+}
+
+/// The due services of the given bikes which block bookings.
+const BLOCKED_BIKES_QUERY: &str = concat!("
+    SELECT  DISTINCT d.bike_id
+    FROM    (", due_services_query!(), "
+    ) d
+    WHERE   d.blocks_bookings
+    AND     d.bike_id = ANY ($2)");
+
+/// Those of the bikes which cannot go out at `as_of`, as a service which
+/// blocks bookings is overdue by then. Rents booked to end before count as
+/// done, so a window later on can be blocked while an earlier one is not.
+pub fn get_blocked_bike_ids(db: &DbConn, p_bike_ids: &[i32], as_of: NaiveDateTime) -> Result<Vec<i32>, diesel::result::Error> {
+    Ok(sql_query(BLOCKED_BIKES_QUERY)
+        .bind::<Timestamptz, _>(as_of)
+        .bind::<Array<Integer>, _>(p_bike_ids)
+        .load::<BlockedBike>(&**db)?
+        .into_iter()
+        .map(|blocked| blocked.bike_id)
+        .collect())
+}
+
+#[cfg_attr(test, mockable)]
+pub fn mark_service_intervals_notified(db: &DbConn, p_bike_service_interval_ids: &[i32], p_notified_at: NaiveDateTime) -> Result<usize, diesel::result::Error> {
+    update(bike_service_intervals::table.filter(bike_service_intervals::id.eq_any(p_bike_service_interval_ids)))
+        .set(bike_service_intervals::notified_at.eq(p_notified_at))
+        .execute(&**db)
+}
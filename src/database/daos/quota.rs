@@ -0,0 +1,126 @@
+use chrono::{Duration,NaiveDateTime,Utc};
+
+use diesel::{RunQueryDsl,QueryDsl,ExpressionMethods};
+use diesel::{sql_query,update};
+use diesel::sql_types::Text;
+
+use crate::config;
+use crate::database::DbConn;
+use crate::database::models::RiderQuota;
+use crate::email_address;
+use crate::schema::rents;
+use crate::schema::tokens;
+
+use crate::routes::errors::RentError;
+
+fn limits_enabled() -> bool {
+    config::max_active_bookings() > 0 || config::max_booked_hours_per_month() > 0
+}
+
+/// The rider of a booking, if it can be told. Riders have to leave their
+/// address while the fair-use limits are in place.
+pub fn identify_rider(email: Option<&str>) -> Result<Option<String>, RentError> {
+    let hash = email.and_then(email_address::hash);
+
+    if hash.is_none() && limits_enabled() {
+        return Err(RentError::Validation(match email {
+            Some(_) => String::from("The fair-use limits are not configured."),
+            None => String::from("An email address is needed to book."),
+        }));
+    }
+
+    Ok(hash)
+}
+
+/// Checks that another `bookings` bookings of `minutes` in total keep the
+/// rider within the fair-use limits and returns what is left after them.
+/// Only rents whose address has been confirmed count, the token gets the
+/// rider's hash with the confirmation. Every occurrence of a series is a
+/// booking of its own. Bookings are active until their rents ended, the
+/// hours are those of the rents booked within the last 30 days.
+pub fn check_quota(db: &DbConn, p_rider_hash: &str, bookings: i64, minutes: i64) -> Result<Option<RiderQuota>, RentError> {
+    if !limits_enabled() {
+        return Ok(None);
+    }
+
+    // Serializes concurrent bookings of the rider until the transaction ends,
+    // which could otherwise both pass the check.
+    sql_query("SELECT pg_advisory_xact_lock(hashtext($1))")
+        .bind::<Text, _>(p_rider_hash)
+        .execute(&**db)?;
+
+    let now = Utc::now().naive_utc();
+    // The rents of the bikes booked together share the token and the start.
+    let active_bookings = rents::table
+        .inner_join(tokens::table)
+        .filter(tokens::rider_hash.eq(p_rider_hash))
+        .filter(rents::revocation_timestamp.is_null())
+        .filter(rents::end_timestamp.gt(now))
+        .select((rents::token_id, rents::start_timestamp))
+        .distinct()
+        .load::<(i32, NaiveDateTime)>(&**db)?
+        .len() as i64;
+    let booked_minutes = rents::table
+        .inner_join(tokens::table)
+        .filter(tokens::rider_hash.eq(p_rider_hash))
+        .filter(rents::revocation_timestamp.is_null())
+        .filter(rents::created_at.gt(now - Duration::days(30)))
+        .select((rents::start_timestamp, rents::end_timestamp))
+        .load::<(NaiveDateTime, NaiveDateTime)>(&**db)?
+        .iter()
+        .map(|(start, end)| (*end - *start).num_minutes())
+        .sum::<i64>();
+
+    let max_active_bookings = config::max_active_bookings();
+    let max_booked_minutes = config::max_booked_hours_per_month() * 60;
+
+    if max_active_bookings > 0 && active_bookings + bookings > max_active_bookings {
+        return Err(RentError::Validation(format!("At most {} bookings can be active at once, {} are left.",
+            max_active_bookings,
+            (max_active_bookings - active_bookings).max(0))));
+    }
+
+    if max_booked_minutes > 0 && booked_minutes + minutes > max_booked_minutes {
+        return Err(RentError::Validation(format!("At most {} hours can be booked within 30 days, {:.1} are left.",
+            config::max_booked_hours_per_month(),
+            (max_booked_minutes - booked_minutes).max(0) as f64 / 60.0)));
+    }
+
+    Ok(Some(RiderQuota {
+        active_bookings_remaining: (max_active_bookings > 0).then(|| max_active_bookings - active_bookings - bookings),
+        booked_hours_remaining: (max_booked_minutes > 0).then(|| (max_booked_minutes - booked_minutes - minutes) as f64 / 60.0),
+    }))
+}
+
+/// Makes the rents of the token count for the rider once the address has
+/// been confirmed, provided they stay within the limits.
+pub fn assign_rider(db: &DbConn, p_token_id: i32, email: Option<&str>) -> Result<(), RentError> {
+    let p_rider_hash = match identify_rider(email)? {
+        Some(p_rider_hash) => p_rider_hash,
+        None => return Ok(()),
+    };
+
+    let booked = rents::table
+        .filter(rents::token_id.eq(p_token_id))
+        .filter(rents::revocation_timestamp.is_null())
+        .select((rents::start_timestamp, rents::end_timestamp))
+        .load::<(NaiveDateTime, NaiveDateTime)>(&**db)?;
+    let now = Utc::now().naive_utc();
+    let mut starts = booked.iter()
+        .filter(|(_, end)| *end > now)
+        .map(|(start, _)| *start)
+        .collect::<Vec<_>>();
+    starts.sort();
+    starts.dedup();
+    let minutes = booked.iter()
+        .map(|(start, end)| (*end - *start).num_minutes())
+        .sum();
+
+    check_quota(db, &p_rider_hash, starts.len() as i64, minutes)?;
+
+    update(tokens::table.find(p_token_id))
+        .set(tokens::rider_hash.eq(p_rider_hash))
+        .execute(&**db)?;
+
+    Ok(())
+}
@@ -4,11 +4,13 @@ use mocktopus::macros::mockable;
 use ::uuid::Uuid;
 use chrono::{NaiveDateTime,Utc};
 
-use diesel::{Connection,RunQueryDsl,QueryDsl,BoolExpressionMethods,ExpressionMethods};
+use diesel::{Connection,RunQueryDsl,QueryDsl,ExpressionMethods};
 use diesel::{insert_into,update};
 
+use crate::config;
 use crate::database::DbConn;
-use crate::database::models::{Booking,Rent,InsertRent,InsertRentDetail,Token};
+use crate::database::daos::{approval,availability,hold,quota,token,verification};
+use crate::database::models::{ApprovalStatus,Bike,BikeStatus,Booking,BookingReceipt,Rent,InsertRent,InsertRentAccessory,InsertRentDetail,Token};
 use crate::schema::rents::dsl::*;
 use crate::schema::rent_details::dsl::*;
 use crate::schema::tokens::dsl::*;
@@ -23,46 +25,151 @@ pub fn get_rents(db: &DbConn, as_of: &NaiveDateTime) -> Result<Vec<Rent>, diesel
 }
 
 #[cfg_attr(test, mockable)]
-pub fn insert_booking(db: &DbConn, booking: &Booking) -> Result<(), RentError> {
+pub fn get_rent(db: &DbConn, p_rent_id: i32) -> Result<Rent, diesel::result::Error> {
+    rents.find(p_rent_id)
+        .get_result::<Rent>(&**db)
+}
+
+/// The rent with the id if it was booked with the token, revoked rents
+/// included. This addresses a single occurrence of a series.
+#[cfg_attr(test, mockable)]
+pub fn get_rent_by_token(db: &DbConn, p_uuid: &Uuid, p_rent_id: i32) -> Result<Rent, diesel::result::Error> {
+    rents.inner_join(tokens)
+        .filter(uuid.eq(p_uuid))
+        .filter(crate::schema::rents::id.eq(p_rent_id))
+        .select(crate::schema::rents::all_columns)
+        .get_result::<Rent>(&**db)
+}
+
+/// All rents booked with the token, i.e. one per bike of a booking and one
+/// per bike and occurrence of a series.
+#[cfg_attr(test, mockable)]
+pub fn get_rents_by_token(db: &DbConn, p_uuid: &Uuid) -> Result<Vec<Rent>, diesel::result::Error> {
+    rents.inner_join(tokens)
+        .filter(uuid.eq(p_uuid))
+        .select(crate::schema::rents::all_columns)
+        .order_by(crate::schema::rents::id)
+        .load::<Rent>(&**db)
+}
+
+/// Books the bikes under the token. If any of them requires approval the
+/// whole booking is pending until an admin decides on it. The receipt tells
+/// what is left of the rider's fair-use limits.
+#[cfg_attr(test, mockable)]
+pub fn insert_booking(db: &DbConn, booking: &Booking) -> Result<BookingReceipt, RentError> {
     (*db).transaction(|| {
-        let overlapping_rent_count = rents
-                .filter(revocation_timestamp.is_null()
-                    .and(start_timestamp.between(booking.start_timestamp, booking.end_timestamp)
-                        .or(start_timestamp.between(booking.start_timestamp, booking.end_timestamp))
-                    )
-                )
-                .count()
-                .get_result::<i64>(&**db)?;
-
-        if overlapping_rent_count > 0 {
-            return Err(RentError::Validation(String::from("There is already a rent at the same period.")));
+        if booking.start_timestamp >= booking.end_timestamp {
+            return Err(RentError::Validation(String::from("The rent has to end after it starts.")));
         }
 
-        let token = tokens
-            .filter(uuid.eq(booking.token))
-            .get_result::<Token>(&**db)?;
+        let bike_ids = booking.bike_ids();
+        let mut locked_bike_ids = bike_ids.clone();
+        locked_bike_ids.sort_unstable();
+        locked_bike_ids.dedup();
+
+        if locked_bike_ids.len() != bike_ids.len() {
+            return Err(RentError::Validation(String::from("Each bike can only be booked once per booking.")));
+        }
+
+        if bike_ids.len() > config::max_bikes_per_booking() {
+            return Err(RentError::Validation(format!("At most {} bikes can be booked at once.", config::max_bikes_per_booking())));
+        }
 
-        let rent = InsertRent {
-            token_id: token.id,
-            bike_id: booking.bike_id,
-            start_timestamp: booking.start_timestamp,
-            end_timestamp: booking.end_timestamp,
+        let (rent_start, rent_end) = availability::align_window(db, &bike_ids, booking.start_timestamp, booking.end_timestamp)?;
+
+        let token = token::get_unused_token(db, &booking.token)?;
+
+        // A hold only keeps other riders out, the slot is checked as usual
+        // once it is gone.
+        if let Some(hold_id) = booking.hold_id {
+            hold::take_hold(db, &hold_id, token.id, &bike_ids, rent_start, rent_end)?;
+        }
+
+        // Serializes concurrent bookings of the same bikes until the rents are
+        // inserted. Bikes are locked in id order, so that concurrent bookings
+        // cannot deadlock, and any unavailable bike fails the whole booking.
+        let mut requires_approval = false;
+        for p_bike_id in &locked_bike_ids {
+            let bike = crate::schema::bikes::table
+                .find(p_bike_id)
+                .for_update()
+                .get_result::<Bike>(&**db)?;
+
+            if bike.status != BikeStatus::Active.as_str() {
+                return Err(RentError::Validation(String::from("The bike cannot be booked at the moment.")));
+            }
+
+            requires_approval |= bike.requires_approval;
+
+            if !availability::is_bike_available(db, *p_bike_id, rent_start, rent_end)? {
+                return Err(RentError::Validation(String::from("There is already a rent at the same period.")));
+            }
+        }
+
+        let requested_accessories = availability::check_accessories(db, &booking.accessories, &bike_ids, rent_start, rent_end)?;
+
+        // The rents only count for the rider once the address is confirmed,
+        // see verification::verify_email.
+        let quota = match quota::identify_rider(booking.email.as_deref())? {
+            Some(p_rider_hash) => {
+                let booked_minutes = (rent_end - rent_start).num_minutes() * bike_ids.len() as i64;
+
+                quota::check_quota(db, &p_rider_hash, 1, booked_minutes)?
+            },
+            None => None,
         };
 
-        let inserted_rent = insert_into(rents)
-            .values(&rent)
-            .get_result::<Rent>(&**db)?;
+        // Accessories are attached to the rent of the bike they go with.
+        for p_bike_id in &bike_ids {
+            let rent = InsertRent {
+                token_id: token.id,
+                bike_id: *p_bike_id,
+                start_timestamp: rent_start,
+                end_timestamp: rent_end,
+            };
+
+            let inserted_rent = insert_into(rents)
+                .values(&rent)
+                .get_result::<Rent>(&**db)?;
+
+            let rent_detail = InsertRentDetail {
+                rent_id: inserted_rent.id,
+                encrypted_details: booking.encrypted_details.clone(),
+            };
+
+            insert_into(rent_details)
+                .values(&rent_detail)
+                .execute(&**db)?;
+
+            let reserved_accessories = requested_accessories.iter()
+                .filter(|requested| requested.bike_id == Some(*p_bike_id))
+                .map(|requested| InsertRentAccessory {
+                    rent_id: inserted_rent.id,
+                    accessory_id: requested.accessory_id,
+                    quantity: requested.quantity,
+                })
+                .collect::<Vec<_>>();
+
+            if !reserved_accessories.is_empty() {
+                insert_into(crate::schema::rent_accessories::table)
+                    .values(&reserved_accessories)
+                    .execute(&**db)?;
+            }
+        }
 
-        let rent_detail = InsertRentDetail {
-            rent_id: inserted_rent.id,
-            encrypted_details: booking.encrypted_details.clone(),
+        let approval_status = if requires_approval {
+            approval::insert_pending_approval(db, token.id, rent_start)?;
+            ApprovalStatus::Pending
+        } else {
+            ApprovalStatus::Approved
         };
 
-        insert_into(rent_details)
-            .values(&rent_detail)
-            .execute(&**db)?;
+        let verification = match booking.email.as_ref() {
+            Some(p_email) => Some(verification::insert_verification(db, token.id, p_email, Some(&booking.short_token), rent_start)?),
+            None => None,
+        };
 
-        Ok(())
+        Ok(BookingReceipt { approval_status, quota, verification })
     })
 }
 
@@ -73,15 +180,17 @@ pub fn revoke_booking(db: &DbConn, token: &Uuid) -> Result<(),RentError> {
             .filter(uuid.eq(token))
             .get_result::<Token>(&**db)?;
 
-        let booking = rents
-            .filter(revocation_timestamp.is_null())
-            .filter(token_id.eq(&token.id))
-            .get_result::<Rent>(&**db)?;
-
-        update(&booking)
+        // Revokes the rents of all bikes of the booking at once.
+        let revoked = update(rents
+                .filter(revocation_timestamp.is_null())
+                .filter(token_id.eq(&token.id)))
             .set(revocation_timestamp.eq(Utc::now().naive_utc()))
             .execute(&**db)?;
 
+        if revoked == 0 {
+            return Err(RentError::from(diesel::result::Error::NotFound));
+        }
+
         Ok(())
     })
 }
\ No newline at end of file
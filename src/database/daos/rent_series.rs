@@ -0,0 +1,229 @@
+#[cfg(test)]
+use mocktopus::macros::mockable;
+
+use ::uuid::Uuid;
+use chrono::{Duration,Utc};
+
+use diesel::{Connection,RunQueryDsl,QueryDsl,ExpressionMethods};
+use diesel::{insert_into,update};
+
+use crate::config;
+use crate::database::DbConn;
+use crate::database::daos::{approval,availability,hold,quota,token,verification};
+use crate::database::models::{ApprovalStatus,Bike,BikeStatus,InsertRent,InsertRentDetail,InsertRentSeries,RecurrenceFrequency,RecurringBooking,Rent,RentSeries,RentSeriesBooking,RentSeriesOccurrence,RentSeriesWithRents};
+use crate::schema::bikes;
+use crate::schema::rent_details;
+use crate::schema::rent_series;
+use crate::schema::rents;
+use crate::schema::tokens;
+use crate::timezone;
+
+use crate::routes::errors::RentError;
+
+/// Expands the rule into its occurrences. The series ends with the last
+/// occurrence starting on `until_date` or after `occurrence_count` of them.
+/// Occurrences keep the wall-clock time of the deployment timezone, also
+/// across daylight saving changes.
+pub fn expand_occurrences(booking: &RecurringBooking, frequency: RecurrenceFrequency) -> Result<Vec<RentSeriesOccurrence>, RentError> {
+    let first_start = timezone::to_local(booking.start_timestamp).naive_local();
+    let first_end = timezone::to_local(booking.end_timestamp).naive_local();
+
+    let count = match (booking.until_date, booking.occurrence_count) {
+        (Some(until_date), None) => {
+            let days = (until_date - first_start.date()).num_days();
+
+            if days < 0 {
+                return Err(RentError::Validation(String::from("The series has to end after its first occurrence.")));
+            }
+
+            (days / frequency.days() + 1) as usize
+        },
+        (None, Some(occurrence_count)) if occurrence_count > 0 => occurrence_count as usize,
+        (None, Some(_)) => return Err(RentError::Validation(String::from("A series needs at least one occurrence."))),
+        _ => return Err(RentError::Validation(String::from("A series ends either at a date or after a number of occurrences."))),
+    };
+
+    if count > config::max_series_occurrences() {
+        return Err(RentError::Validation(format!("A series can have at most {} occurrences.", config::max_series_occurrences())));
+    }
+
+    Ok((0..count as i32)
+        .map(|index| {
+            let offset = Duration::days(frequency.days() * i64::from(index));
+
+            RentSeriesOccurrence {
+                start_timestamp: timezone::from_local(first_start + offset),
+                end_timestamp: timezone::from_local(first_end + offset),
+            }
+        })
+        .collect())
+}
+
+/// Books every free occurrence of the series under the token. Occurrences
+/// which are not available are skipped and reported, the booking only fails
+/// if none of them is free. A hold can be taken for the first occurrence.
+#[cfg_attr(test, mockable)]
+pub fn insert_recurring_booking(db: &DbConn, booking: &RecurringBooking) -> Result<RentSeriesBooking, RentError> {
+    (*db).transaction(|| {
+        let frequency = booking.frequency.parse::<RecurrenceFrequency>().map_err(RentError::Validation)?;
+
+        if booking.start_timestamp >= booking.end_timestamp {
+            return Err(RentError::Validation(String::from("The rent has to end after it starts.")));
+        }
+
+        if booking.end_timestamp - booking.start_timestamp > Duration::days(frequency.days()) {
+            return Err(RentError::Validation(String::from("An occurrence has to end before the next one starts.")));
+        }
+
+        let occurrences = expand_occurrences(booking, frequency)?;
+
+        // Serializes concurrent bookings of the bike, see rent::insert_booking.
+        let bike = bikes::table
+            .find(booking.bike_id)
+            .for_update()
+            .get_result::<Bike>(&**db)?;
+
+        if bike.status != BikeStatus::Active.as_str() {
+            return Err(RentError::Validation(String::from("The bike cannot be booked at the moment.")));
+        }
+
+        let token = token::get_unused_token(db, &booking.token)?;
+
+        let mut free_occurrences: Vec<RentSeriesOccurrence> = vec![];
+        let mut conflicts = vec![];
+
+        // Occurrences are checked before any of them is inserted, so those
+        // of the series have to keep the turnaround among themselves.
+        let (before, after) = availability::buffer_minutes(&bike);
+        let turnaround = Duration::minutes(i64::from(before) + i64::from(after));
+
+        for (index, occurrence) in occurrences.into_iter().enumerate() {
+            let (start, end) = availability::align_window(db, &[booking.bike_id], occurrence.start_timestamp, occurrence.end_timestamp)?;
+            let occurrence = RentSeriesOccurrence { start_timestamp: start, end_timestamp: end };
+
+            // A hold only keeps other riders out, see rent::insert_booking.
+            if let (0, Some(hold_id)) = (index, booking.hold_id) {
+                hold::take_hold(db, &hold_id, token.id, &[booking.bike_id], start, end)?;
+            }
+
+            let overlaps_series = free_occurrences.iter()
+                .any(|free| free.start_timestamp < end + turnaround && free.end_timestamp + turnaround > start);
+
+            if overlaps_series || !availability::is_bike_available(db, booking.bike_id, start, end)? {
+                conflicts.push(occurrence);
+                continue;
+            }
+
+            free_occurrences.push(occurrence);
+        }
+
+        if free_occurrences.is_empty() {
+            return Err(RentError::Validation(String::from("None of the occurrences is available.")));
+        }
+
+        let quota = match quota::identify_rider(booking.email.as_deref())? {
+            Some(p_rider_hash) => {
+                let booked_minutes = free_occurrences.iter()
+                    .map(|occurrence| (occurrence.end_timestamp - occurrence.start_timestamp).num_minutes())
+                    .sum();
+
+                quota::check_quota(db, &p_rider_hash, free_occurrences.len() as i64, booked_minutes)?
+            },
+            None => None,
+        };
+
+        let mut booked_rents = vec![];
+
+        for occurrence in free_occurrences {
+            let rent = insert_into(rents::table)
+                .values(&InsertRent {
+                    token_id: token.id,
+                    bike_id: booking.bike_id,
+                    start_timestamp: occurrence.start_timestamp,
+                    end_timestamp: occurrence.end_timestamp,
+                })
+                .get_result::<Rent>(&**db)?;
+
+            insert_into(rent_details::table)
+                .values(&InsertRentDetail {
+                    rent_id: rent.id,
+                    encrypted_details: booking.encrypted_details.clone(),
+                })
+                .execute(&**db)?;
+
+            booked_rents.push(rent);
+        }
+
+        let approval_status = if bike.requires_approval {
+            approval::insert_pending_approval(db, token.id, booked_rents[0].start_timestamp)?;
+            ApprovalStatus::Pending
+        } else {
+            ApprovalStatus::Approved
+        };
+
+        let verification = match booking.email.as_ref() {
+            Some(email) => Some(verification::insert_verification(db, token.id, email, Some(&booking.short_token), booked_rents[0].start_timestamp)?),
+            None => None,
+        };
+
+        let series = insert_into(rent_series::table)
+            .values(&InsertRentSeries {
+                token_id: token.id,
+                bike_id: booking.bike_id,
+                frequency: frequency.as_str().to_string(),
+                start_timestamp: booking.start_timestamp,
+                end_timestamp: booking.end_timestamp,
+                until_date: booking.until_date,
+                occurrence_count: booking.occurrence_count,
+            })
+            .get_result::<RentSeries>(&**db)?;
+
+        Ok(RentSeriesBooking {
+            series: RentSeriesWithRents {
+                series,
+                rents: booked_rents,
+            },
+            conflicts,
+            approval_status,
+            quota,
+            verification,
+        })
+    })
+}
+
+#[cfg_attr(test, mockable)]
+pub fn get_series_by_token(db: &DbConn, p_uuid: &Uuid) -> Result<RentSeriesWithRents, diesel::result::Error> {
+    let series = rent_series::table
+        .inner_join(tokens::table)
+        .filter(tokens::uuid.eq(p_uuid))
+        .select(rent_series::all_columns)
+        .get_result::<RentSeries>(&**db)?;
+    let rents = rents::table
+        .filter(rents::token_id.eq(series.token_id))
+        .order_by(rents::start_timestamp)
+        .load::<Rent>(&**db)?;
+
+    Ok(RentSeriesWithRents { series, rents })
+}
+
+/// Revokes a single rent of the token, e.g. one occurrence of a series. The
+/// whole booking is revoked with rent::revoke_booking.
+#[cfg_attr(test, mockable)]
+pub fn revoke_occurrence(db: &DbConn, p_uuid: &Uuid, p_rent_id: i32) -> Result<(), RentError> {
+    let token_ids = tokens::table
+        .select(tokens::id)
+        .filter(tokens::uuid.eq(p_uuid));
+
+    let revoked = update(rents::table
+            .filter(rents::id.eq(p_rent_id))
+            .filter(rents::token_id.eq_any(token_ids))
+            .filter(rents::revocation_timestamp.is_null()))
+        .set(rents::revocation_timestamp.eq(Utc::now().naive_utc()))
+        .execute(&**db)?;
+
+    if revoked == 0 {
+        return Err(RentError::from(diesel::result::Error::NotFound));
+    }
+
+    Ok(())
+}
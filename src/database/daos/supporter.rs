@@ -1,17 +1,27 @@
 #[cfg(test)]
 use mocktopus::macros::mockable;
 
-use diesel::{RunQueryDsl,QueryDsl,NullableExpressionMethods};
+use diesel::{Connection,RunQueryDsl,QueryDsl,BoolExpressionMethods,ExpressionMethods,NullableExpressionMethods};
+use diesel::{delete,insert_into,update};
 
 use crate::database::DbConn;
-use crate::database::models::SupporterWithTypeAndTranslatable;
+use crate::database::daos::translatable;
+use crate::database::daos::translatable::{SupporterTranslations,Translation};
+use crate::database::models::{InsertSupporter,InsertSupporterTranslatable,InsertSupporterType,MissingTranslations,Supporter,SupporterTranslatable,SupporterType,SupporterWithTypeAndTranslatable};
+use crate::locale::LocalePreferences;
 use crate::schema::supporters::dsl::*;
 use crate::schema::supporter_types::dsl::*;
 use crate::schema::supporter_translatables::dsl::*;
+use crate::timezone;
 
+/// Supporters whose sponsorship covers today, ordered by the sort priority of
+/// their type and then by their own sort priority. Higher priorities come
+/// first. Optionally restricted to the supporter type with the given title.
 #[cfg_attr(test, mockable)]
-pub fn get_supporters(db: &DbConn) -> Result<Vec<SupporterWithTypeAndTranslatable>, diesel::result::Error> {
-    supporters
+pub fn get_supporters(db: &DbConn, preferences: &LocalePreferences, supporter_type: Option<&str>) -> Result<Vec<SupporterWithTypeAndTranslatable>, diesel::result::Error> {
+    let today = timezone::today();
+
+    let mut query = supporters
         .inner_join(supporter_types)
         .inner_join(supporter_translatables)
         .select((
@@ -26,5 +36,127 @@ pub fn get_supporters(db: &DbConn) -> Result<Vec<SupporterWithTypeAndTranslatabl
             crate::schema::supporter_translatables::dsl::logo_height.nullable(),
             crate::schema::supporter_translatables::dsl::logo_alt_text.nullable(),
         ))
-        .load::<SupporterWithTypeAndTranslatable>(&**db)
-}
\ No newline at end of file
+        .filter(start_date.is_null().or(start_date.le(today)))
+        .filter(end_date.is_null().or(end_date.ge(today)))
+        .into_boxed();
+
+    if let Some(supporter_type) = supporter_type {
+        query = query.filter(crate::schema::supporter_types::dsl::title.eq(supporter_type.to_string()));
+    }
+
+    let translations = query
+        .order_by((
+            crate::schema::supporter_types::dsl::sort_priority.desc(),
+            crate::schema::supporters::dsl::sort_priority.desc(),
+            crate::schema::supporters::dsl::id,
+            crate::schema::supporter_translatables::dsl::id,
+        ))
+        .load::<SupporterWithTypeAndTranslatable>(&**db)?;
+
+    Ok(translatable::select_translations(translations, preferences))
+}
+
+impl Translation for SupporterWithTypeAndTranslatable {
+    fn parent_id(&self) -> i32 {
+        self.id
+    }
+
+    fn locale(&self) -> &str {
+        &self.locale
+    }
+}
+
+#[cfg_attr(test, mockable)]
+pub fn get_supporter_types(db: &DbConn) -> Result<Vec<SupporterType>, diesel::result::Error> {
+    supporter_types
+        .order_by((crate::schema::supporter_types::dsl::sort_priority.desc(), crate::schema::supporter_types::dsl::id))
+        .load::<SupporterType>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn insert_supporter_type(db: &DbConn, supporter_type: &InsertSupporterType) -> Result<SupporterType, diesel::result::Error> {
+    insert_into(supporter_types)
+        .values(supporter_type)
+        .get_result::<SupporterType>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn update_supporter_type(db: &DbConn, p_supporter_type_id: i32, supporter_type: &InsertSupporterType) -> Result<SupporterType, diesel::result::Error> {
+    update(supporter_types.filter(crate::schema::supporter_types::dsl::id.eq(p_supporter_type_id)))
+        .set(supporter_type)
+        .get_result::<SupporterType>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn delete_supporter_type(db: &DbConn, p_supporter_type_id: i32) -> Result<usize, diesel::result::Error> {
+    delete(supporter_types.filter(crate::schema::supporter_types::dsl::id.eq(p_supporter_type_id)))
+        .execute(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn get_all_supporters(db: &DbConn) -> Result<Vec<Supporter>, diesel::result::Error> {
+    supporters
+        .order_by(crate::schema::supporters::dsl::id)
+        .load::<Supporter>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn insert_supporter(db: &DbConn, supporter: &InsertSupporter) -> Result<Supporter, diesel::result::Error> {
+    insert_into(supporters)
+        .values(supporter)
+        .get_result::<Supporter>(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn update_supporter(db: &DbConn, p_supporter_id: i32, supporter: &InsertSupporter) -> Result<Supporter, diesel::result::Error> {
+    update(supporters.filter(crate::schema::supporters::dsl::id.eq(p_supporter_id)))
+        .set(supporter)
+        .get_result::<Supporter>(&**db)
+}
+
+/// Deletes the supporter together with all of its translations.
+#[cfg_attr(test, mockable)]
+pub fn delete_supporter(db: &DbConn, p_supporter_id: i32) -> Result<usize, diesel::result::Error> {
+    (*db).transaction(|| {
+        delete(supporter_translatables.filter(supporter_id.eq(p_supporter_id)))
+            .execute(&**db)?;
+
+        delete(supporters.filter(crate::schema::supporters::dsl::id.eq(p_supporter_id)))
+            .execute(&**db)
+    })
+}
+
+#[cfg_attr(test, mockable)]
+pub fn get_supporter_translations(db: &DbConn, p_supporter_id: i32) -> Result<Vec<SupporterTranslatable>, diesel::result::Error> {
+    <SupporterTranslations as translatable::TranslatableResource>::load_translations_of(db, p_supporter_id)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn upsert_supporter_translation(db: &DbConn, translation: &InsertSupporterTranslatable) -> Result<SupporterTranslatable, diesel::result::Error> {
+    translatable::upsert_translation::<SupporterTranslations>(db, translation)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn delete_supporter_translation(db: &DbConn, p_supporter_id: i32, p_locale: &str) -> Result<usize, diesel::result::Error> {
+    delete(supporter_translatables
+            .filter(supporter_id.eq(p_supporter_id).and(locale.eq(p_locale.to_string()))))
+        .execute(&**db)
+}
+
+#[cfg_attr(test, mockable)]
+pub fn get_missing_supporter_translations(db: &DbConn) -> Result<Vec<MissingTranslations>, diesel::result::Error> {
+    translatable::missing_locales::<SupporterTranslations>(db)
+}
+
+/// Points the logo of an existing translation to an uploaded image.
+#[cfg_attr(test, mockable)]
+pub fn update_supporter_logo(db: &DbConn, p_supporter_id: i32, p_locale: &str, p_logo_url: &str, p_logo_width: i16, p_logo_height: i16) -> Result<SupporterTranslatable, diesel::result::Error> {
+    update(supporter_translatables
+            .filter(supporter_id.eq(p_supporter_id).and(locale.eq(p_locale.to_string()))))
+        .set((
+            logo_url.eq(p_logo_url.to_string()),
+            logo_width.eq(p_logo_width),
+            logo_height.eq(p_logo_height),
+        ))
+        .get_result::<SupporterTranslatable>(&**db)
+}
@@ -0,0 +1,77 @@
+#[cfg(test)]
+use mocktopus::macros::mockable;
+
+use ::uuid::Uuid;
+use chrono::{Duration,NaiveDateTime,Utc};
+
+use diesel::{RunQueryDsl,QueryDsl,ExpressionMethods,OptionalExtension};
+use diesel::{delete,insert_into};
+use diesel::dsl::not;
+
+use crate::config;
+use crate::database::DbConn;
+use crate::database::models::Token;
+use crate::schema::tokens::dsl::*;
+use crate::schema::rents::dsl::{rents};
+use crate::schema::waitlist_entries;
+
+use crate::routes::errors::RentError;
+
+#[cfg_attr(test, mockable)]
+pub fn insert_token(db: &DbConn) -> Result<Token, diesel::result::Error> {
+    insert_into(tokens)
+        .default_values()
+        .get_result::<Token>(&**db)
+}
+
+fn expiry_threshold() -> NaiveDateTime {
+    Utc::now().naive_utc() - Duration::hours(config::token_ttl_hours())
+}
+
+/// Returns the token if it may still be used for a booking, i.e. if it is
+/// known, not yet bound to a rent or a waitlist entry and younger than the
+/// configured TTL. The token stays locked until the transaction ends, so
+/// concurrent requests with the same token cannot both use it.
+#[cfg_attr(test, mockable)]
+pub fn get_unused_token(db: &DbConn, token: &Uuid) -> Result<Token, RentError> {
+    let token = tokens
+        .filter(uuid.eq(token))
+        .for_update()
+        .get_result::<Token>(&**db)
+        .optional()?
+        .ok_or(RentError::Validation(String::from("The token is not known.")))?;
+
+    let rent_count = rents
+        .filter(crate::schema::rents::dsl::token_id.eq(token.id))
+        .count()
+        .get_result::<i64>(&**db)?;
+    let waitlist_count = waitlist_entries::table
+        .filter(waitlist_entries::token_id.eq(token.id))
+        .count()
+        .get_result::<i64>(&**db)?;
+
+    if rent_count > 0 || waitlist_count > 0 {
+        return Err(RentError::TokenUsed(String::from("The token has already been used.")));
+    }
+
+    if token.created_at < expiry_threshold() {
+        return Err(RentError::TokenExpired(String::from("The token has expired.")));
+    }
+
+    Ok(token)
+}
+
+/// Deletes expired tokens which have never been used for a booking, together
+/// with their waitlist entries once the window waited for has passed.
+#[cfg_attr(test, mockable)]
+pub fn delete_stale_tokens(db: &DbConn) -> Result<usize, diesel::result::Error> {
+    let waiting_token_ids = waitlist_entries::table
+        .select(waitlist_entries::token_id)
+        .filter(waitlist_entries::end_timestamp.gt(Utc::now().naive_utc()));
+
+    delete(tokens
+            .filter(created_at.lt(expiry_threshold()))
+            .filter(not(id.eq_any(rents.select(crate::schema::rents::dsl::token_id))))
+            .filter(not(id.eq_any(waiting_token_ids))))
+        .execute(&**db)
+}
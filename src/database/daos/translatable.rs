@@ -0,0 +1,188 @@
+use diesel::{RunQueryDsl,QueryDsl,ExpressionMethods};
+use diesel::insert_into;
+
+use crate::config;
+use crate::database::DbConn;
+use crate::database::models::{AccessoryTranslatable,InsertAccessoryTranslatable,BikeAttributeOptionTranslatable,BikeAttributeTranslatable,InsertBikeAttributeOptionTranslatable,InsertBikeAttributeTranslatable,BikeMediaTranslatable,BikeTranslatable,InsertBikeMediaTranslatable,InsertBikeTranslatable,InsertSupporterTranslatable,InsertTokenChallengeTranslatable,MissingTranslations,SupporterTranslatable,TokenChallengeTranslatable};
+use crate::locale::{LanguageTag,LocalePreferences};
+
+// Resources with per-locale texts store them in a `*_translatables` table
+// which references the resource and is unique per resource and locale. The
+// trait below captures the table specific queries, everything built on top of
+// them (locale negotiation, missing locales) is implemented once.
+
+pub trait Translation {
+    fn parent_id(&self) -> i32;
+    fn locale(&self) -> &str;
+}
+
+pub trait TranslatableResource {
+    type Translation: Translation;
+    type Insert;
+
+    /// All ids of the resource, including those without any translation.
+    fn load_parent_ids(db: &DbConn) -> Result<Vec<i32>, diesel::result::Error>;
+
+    /// All translations ordered by resource.
+    fn load_translations(db: &DbConn) -> Result<Vec<Self::Translation>, diesel::result::Error>;
+
+    fn load_translations_of(db: &DbConn, parent_id: i32) -> Result<Vec<Self::Translation>, diesel::result::Error>;
+
+    /// Inserts the translation or replaces the one for the same resource and locale.
+    fn upsert_translation(db: &DbConn, translation: &Self::Insert) -> Result<Self::Translation, diesel::result::Error>;
+}
+
+macro_rules! translatable_resource {
+    ($resource:ident, $parent_table:ident, $table:ident, $parent:ident, $translation:ty, $insert:ty) => {
+        pub struct $resource;
+
+        impl Translation for $translation {
+            fn parent_id(&self) -> i32 {
+                self.$parent
+            }
+
+            fn locale(&self) -> &str {
+                &self.locale
+            }
+        }
+
+        impl TranslatableResource for $resource {
+            type Translation = $translation;
+            type Insert = $insert;
+
+            fn load_parent_ids(db: &DbConn) -> Result<Vec<i32>, diesel::result::Error> {
+                crate::schema::$parent_table::table
+                    .select(crate::schema::$parent_table::id)
+                    .order_by(crate::schema::$parent_table::id)
+                    .load::<i32>(&**db)
+            }
+
+            fn load_translations(db: &DbConn) -> Result<Vec<$translation>, diesel::result::Error> {
+                crate::schema::$table::table
+                    .order_by((crate::schema::$table::$parent, crate::schema::$table::id))
+                    .load::<$translation>(&**db)
+            }
+
+            fn load_translations_of(db: &DbConn, parent_id: i32) -> Result<Vec<$translation>, diesel::result::Error> {
+                crate::schema::$table::table
+                    .filter(crate::schema::$table::$parent.eq(parent_id))
+                    .order_by(crate::schema::$table::id)
+                    .load::<$translation>(&**db)
+            }
+
+            fn upsert_translation(db: &DbConn, translation: &$insert) -> Result<$translation, diesel::result::Error> {
+                insert_into(crate::schema::$table::table)
+                    .values(translation)
+                    .on_conflict((crate::schema::$table::$parent, crate::schema::$table::locale))
+                    .do_update()
+                    .set(translation)
+                    .get_result::<$translation>(&**db)
+            }
+        }
+    };
+}
+
+translatable_resource!(AccessoryTranslations, accessories, accessory_translatables, accessory_id, AccessoryTranslatable, InsertAccessoryTranslatable);
+translatable_resource!(BikeTranslations, bikes, bike_translatables, bike_id, BikeTranslatable, InsertBikeTranslatable);
+translatable_resource!(BikeAttributeTranslations, bike_attributes, bike_attribute_translatables, bike_attribute_id, BikeAttributeTranslatable, InsertBikeAttributeTranslatable);
+translatable_resource!(BikeAttributeOptionTranslations, bike_attribute_options, bike_attribute_option_translatables, bike_attribute_option_id, BikeAttributeOptionTranslatable, InsertBikeAttributeOptionTranslatable);
+translatable_resource!(BikeMediaTranslations, bike_media, bike_media_translatables, bike_media_id, BikeMediaTranslatable, InsertBikeMediaTranslatable);
+translatable_resource!(SupporterTranslations, supporters, supporter_translatables, supporter_id, SupporterTranslatable, InsertSupporterTranslatable);
+translatable_resource!(TokenChallengeTranslations, token_challenges, token_challenge_translatables, token_challenge_id, TokenChallengeTranslatable, InsertTokenChallengeTranslatable);
+
+/// Reduces a list of translations to the best one per resource. Resources keep
+/// the order in which they first appear. If none of the candidates matches,
+/// the first translation of the resource is used so that no resource disappears.
+pub fn select_translations<T: Translation>(translations: Vec<T>, preferences: &LocalePreferences) -> Vec<T> {
+    let mut groups: Vec<(i32, Vec<T>)> = vec![];
+
+    for translation in translations {
+        let id = translation.parent_id();
+
+        match groups.iter_mut().find(|(group_id, _)| *group_id == id) {
+            Some((_, group)) => group.push(translation),
+            None => groups.push((id, vec![translation])),
+        }
+    }
+
+    groups.into_iter()
+        .map(|(_, mut group)| {
+            let locales = group.iter().map(|t| t.locale()).collect::<Vec<_>>();
+            let index = preferences.best_match(&locales).unwrap_or(0);
+
+            group.swap_remove(index)
+        })
+        .collect()
+}
+
+pub fn load_by_locale<R: TranslatableResource>(db: &DbConn, preferences: &LocalePreferences) -> Result<Vec<R::Translation>, diesel::result::Error> {
+    Ok(select_translations(R::load_translations(db)?, preferences))
+}
+
+pub fn load_one_by_locale<R: TranslatableResource>(db: &DbConn, parent_id: i32, preferences: &LocalePreferences) -> Result<Option<R::Translation>, diesel::result::Error> {
+    Ok(select_translations(R::load_translations_of(db, parent_id)?, preferences).pop())
+}
+
+pub fn upsert_translation<R: TranslatableResource>(db: &DbConn, translation: &R::Insert) -> Result<R::Translation, diesel::result::Error> {
+    R::upsert_translation(db, translation)
+}
+
+/// Lists the supported locales each resource has no translation for.
+/// Resources which are translated completely are omitted.
+pub fn missing_locales<R: TranslatableResource>(db: &DbConn) -> Result<Vec<MissingTranslations>, diesel::result::Error> {
+    let supported = config::supported_locales().iter()
+        .filter_map(|locale| locale.parse::<LanguageTag>().ok())
+        .collect::<Vec<_>>();
+    let translations = R::load_translations(db)?;
+
+    Ok(R::load_parent_ids(db)?.into_iter()
+        .map(|parent_id| {
+            let present = translations.iter()
+                .filter(|t| t.parent_id() == parent_id)
+                .filter_map(|t| t.locale().parse::<LanguageTag>().ok())
+                .collect::<Vec<_>>();
+
+            MissingTranslations {
+                parent_id,
+                locales: supported.iter()
+                    .filter(|tag| !present.contains(tag))
+                    .map(|tag| tag.to_string())
+                    .collect(),
+            }
+        })
+        .filter(|missing| !missing.locales.is_empty())
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+
+    use crate::locale::LocalePreferences;
+
+    struct TestTranslation(i32, &'static str);
+
+    impl super::Translation for TestTranslation {
+        fn parent_id(&self) -> i32 {
+            self.0
+        }
+
+        fn locale(&self) -> &str {
+            self.1
+        }
+    }
+
+    #[test]
+    fn test_select_translations() {
+        env::set_var("LOCALE_FALLBACK_CHAIN", "de-DE,en-GB");
+
+        let preferences = LocalePreferences::parse("en-US").unwrap();
+        let translations = vec![TestTranslation(1, "de-DE"), TestTranslation(1, "en-GB"), TestTranslation(2, "de-DE"), TestTranslation(3, "it-IT")];
+
+        let actual = super::select_translations(translations, &preferences).into_iter()
+            .map(|t| (t.0, t.1))
+            .collect::<Vec<_>>();
+
+        assert_eq!(actual, vec![(1, "en-GB"), (2, "de-DE"), (3, "it-IT")]);
+    }
+}
@@ -0,0 +1,123 @@
+#[cfg(test)]
+use mocktopus::macros::mockable;
+
+use ::uuid::Uuid;
+use chrono::{Duration,NaiveDateTime,Utc};
+
+use diesel::{Connection,RunQueryDsl,QueryDsl,ExpressionMethods};
+use diesel::{insert_into,update};
+
+use crate::config;
+use crate::database::DbConn;
+use crate::database::daos::quota;
+use crate::email_address;
+use crate::database::models::{ApprovalStatus,EmailVerification,InsertEmailVerification,Rent,VerificationStatus};
+use crate::schema::email_verifications;
+use crate::schema::rent_approvals;
+use crate::schema::rents;
+
+use crate::routes::errors::RentError;
+
+/// Keeps the booking or waitlist entry of the token unconfirmed until the
+/// rider follows the link mailed to the address. Unconfirmed bookings lapse
+/// when the rent starts at the latest, but the rider always has the grace
+/// period to confirm.
+pub fn insert_verification(db: &DbConn, p_token_id: i32, p_email: &str, p_short_token: Option<&str>, rent_start: NaiveDateTime) -> Result<EmailVerification, RentError> {
+    let now = Utc::now().naive_utc();
+    let timeout = now + Duration::minutes(config::verification_timeout_minutes());
+    let grace = now + Duration::minutes(config::verification_grace_minutes());
+
+    if !email_address::is_valid(p_email) {
+        return Err(RentError::Validation(String::from("A valid email address is needed.")));
+    }
+
+    let sealed = email_address::seal(p_email)
+        .ok_or_else(|| RentError::Validation(String::from("Email addresses cannot be accepted at the moment.")))?;
+
+    Ok(insert_into(email_verifications::table)
+        .values(&InsertEmailVerification {
+            token_id: p_token_id,
+            encrypted_email: sealed,
+            short_token: p_short_token.map(String::from),
+            expires_at: timeout.min(rent_start).max(grace),
+        })
+        .get_result::<EmailVerification>(&**db)?)
+}
+
+/// The confirmed address of the token's rider, mails about the booking may
+/// only go there.
+#[cfg_attr(test, mockable)]
+pub fn get_verified_email(db: &DbConn, p_token_id: i32) -> Result<Option<String>, diesel::result::Error> {
+    Ok(email_verifications::table
+        .filter(email_verifications::token_id.eq(p_token_id))
+        .filter(email_verifications::status.eq(VerificationStatus::Verified.as_str()))
+        .select(email_verifications::encrypted_email)
+        .load::<String>(&**db)?
+        .pop()
+        .and_then(|sealed| email_address::open(&sealed)))
+}
+
+/// Confirms the booking behind the link and returns its rents.
+#[cfg_attr(test, mockable)]
+pub fn verify_email(db: &DbConn, p_uuid: &Uuid) -> Result<(EmailVerification, Vec<Rent>), RentError> {
+    (*db).transaction(|| {
+        let verification = email_verifications::table
+            .filter(email_verifications::uuid.eq(p_uuid))
+            .for_update()
+            .get_result::<EmailVerification>(&**db)?;
+
+        if verification.status == VerificationStatus::Verified.as_str() {
+            return Err(RentError::Validation(String::from("The booking has already been confirmed.")));
+        }
+
+        if verification.status == VerificationStatus::Lapsed.as_str() || verification.expires_at <= Utc::now().naive_utc() {
+            return Err(RentError::Validation(String::from("The link has expired, the booking has lapsed.")));
+        }
+
+        quota::assign_rider(db, verification.token_id, email_address::open(&verification.encrypted_email).as_deref())?;
+
+        let verification = update(email_verifications::table.find(verification.id))
+            .set((
+                email_verifications::status.eq(VerificationStatus::Verified.as_str()),
+                email_verifications::verified_at.eq(Utc::now().naive_utc()),
+            ))
+            .get_result::<EmailVerification>(&**db)?;
+        let rents = rents::table
+            .filter(rents::token_id.eq(verification.token_id))
+            .order_by(rents::id)
+            .load::<Rent>(&**db)?;
+
+        Ok((verification, rents))
+    })
+}
+
+/// Revokes the bookings whose address was not confirmed in time, together
+/// with their approval if one is still pending.
+#[cfg_attr(test, mockable)]
+pub fn lapse_unverified_bookings(db: &DbConn, as_of: NaiveDateTime) -> Result<usize, diesel::result::Error> {
+    (*db).transaction(|| {
+        let token_ids = update(email_verifications::table
+                .filter(email_verifications::status.eq(VerificationStatus::Pending.as_str()))
+                .filter(email_verifications::expires_at.le(as_of)))
+            .set(email_verifications::status.eq(VerificationStatus::Lapsed.as_str()))
+            .returning(email_verifications::token_id)
+            .get_results::<i32>(&**db)?;
+
+        update(rents::table
+                .filter(rents::token_id.eq_any(&token_ids))
+                .filter(rents::revocation_timestamp.is_null()))
+            .set(rents::revocation_timestamp.eq(as_of))
+            .execute(&**db)?;
+
+        update(rent_approvals::table
+                .filter(rent_approvals::token_id.eq_any(&token_ids))
+                .filter(rent_approvals::status.eq(ApprovalStatus::Pending.as_str())))
+            .set((
+                rent_approvals::status.eq(ApprovalStatus::Expired.as_str()),
+                rent_approvals::decided_at.eq(as_of),
+            ))
+            .execute(&**db)?;
+
+        Ok(token_ids.len())
+    })
+}
@@ -0,0 +1,113 @@
+#[cfg(test)]
+use mocktopus::macros::mockable;
+
+use ::uuid::Uuid;
+use chrono::{Duration,NaiveDateTime};
+
+use diesel::{Connection,RunQueryDsl,QueryDsl,ExpressionMethods};
+use diesel::{delete,insert_into,update};
+
+use crate::config;
+use crate::database::DbConn;
+use crate::database::daos::{availability,hold,token,verification};
+use crate::database::models::{Bike,EmailVerification,InsertRentHold,InsertWaitlistEntry,RentHold,VerificationStatus,WaitlistEntry,WaitlistRequest};
+use crate::schema::bikes;
+use crate::schema::email_verifications;
+use crate::schema::waitlist_entries;
+
+use crate::routes::errors::RentError;
+
+#[cfg_attr(test, mockable)]
+pub fn get_waitlist_entries(db: &DbConn) -> Result<Vec<WaitlistEntry>, diesel::result::Error> {
+    waitlist_entries::table
+        .order_by((waitlist_entries::created_at, waitlist_entries::id))
+        .load::<WaitlistEntry>(&**db)
+}
+
+/// Puts the rider on the waitlist. The entry spends the token like a booking
+/// does, its address has to be confirmed before any slot is offered.
+#[cfg_attr(test, mockable)]
+pub fn insert_waitlist_entry(db: &DbConn, request: &WaitlistRequest) -> Result<(WaitlistEntry, EmailVerification), RentError> {
+    (*db).transaction(|| {
+        let token = token::get_unused_token(db, &request.token)?;
+
+        let entry = insert_into(waitlist_entries::table)
+            .values(&InsertWaitlistEntry {
+                token_id: token.id,
+                bike_id: request.bike_id,
+                start_timestamp: request.start_timestamp,
+                end_timestamp: request.end_timestamp,
+            })
+            .get_result::<WaitlistEntry>(&**db)?;
+        let verification = verification::insert_verification(db, token.id, &request.email, None, request.start_timestamp)?;
+
+        Ok((entry, verification))
+    })
+}
+
+#[cfg_attr(test, mockable)]
+pub fn delete_waitlist_entry(db: &DbConn, p_uuid: &Uuid) -> Result<usize, diesel::result::Error> {
+    delete(waitlist_entries::table.filter(waitlist_entries::uuid.eq(p_uuid)))
+        .execute(&**db)
+}
+
+/// Gives the waiting riders with a confirmed address, first come first
+/// served, a hold on their window once it is free again. Each rider is
+/// offered a slot once, a hold which runs out unused frees the slot for the
+/// next one.
+#[cfg_attr(test, mockable)]
+pub fn offer_free_slots(db: &DbConn, as_of: NaiveDateTime) -> Result<Vec<(WaitlistEntry, RentHold)>, diesel::result::Error> {
+    (*db).transaction(|| {
+        let verified_token_ids = email_verifications::table
+            .select(email_verifications::token_id)
+            .filter(email_verifications::status.eq(VerificationStatus::Verified.as_str()));
+        let entries = waitlist_entries::table
+            .filter(waitlist_entries::notified_at.is_null())
+            .filter(waitlist_entries::start_timestamp.gt(as_of))
+            .filter(waitlist_entries::token_id.eq_any(verified_token_ids))
+            .order_by((waitlist_entries::created_at, waitlist_entries::id))
+            .for_update()
+            .load::<WaitlistEntry>(&**db)?;
+
+        // Serializes the offers with concurrent bookings and holds of the
+        // bikes. Bikes are locked in id order like bookings do, so that they
+        // cannot deadlock.
+        let mut bike_ids = entries.iter().map(|entry| entry.bike_id).collect::<Vec<i32>>();
+        bike_ids.sort_unstable();
+        bike_ids.dedup();
+
+        for bike_id in &bike_ids {
+            bikes::table
+                .find(bike_id)
+                .for_update()
+                .get_result::<Bike>(&**db)?;
+        }
+
+        let mut offers = vec![];
+
+        for entry in entries {
+            // Earlier offers hold their slots, so overlapping entries further
+            // down the list are no longer available.
+            if !availability::is_bike_available(db, entry.bike_id, entry.start_timestamp, entry.end_timestamp)? {
+                continue;
+            }
+
+            let hold = hold::insert_hold(db, &InsertRentHold {
+                bike_id: entry.bike_id,
+                start_timestamp: entry.start_timestamp,
+                end_timestamp: entry.end_timestamp,
+                expires_at: as_of + Duration::minutes(config::waitlist_hold_minutes()),
+                waitlist_entry_id: Some(entry.id),
+                token_id: None,
+            })?;
+
+            let entry = update(waitlist_entries::table.find(entry.id))
+                .set(waitlist_entries::notified_at.eq(as_of))
+                .get_result::<WaitlistEntry>(&**db)?;
+
+            offers.push((entry, hold));
+        }
+
+        Ok(offers)
+    })
+}
@@ -1,69 +1,1294 @@
-use chrono::prelude::NaiveDateTime;
+use std::str::FromStr;
+
+use chrono::Duration;
+use chrono::prelude::{NaiveDate,NaiveDateTime};
 
 use serde::{Deserialize,Serialize};
 
-use uuid::Uuid;
+use uuid::Uuid;
+
+use crate::schema::*;
+use crate::timezone;
+
+#[derive(Queryable,Serialize)]
+#[derive(Debug)]
+pub struct Bike {
+    pub id: i32,
+    pub status: String,
+    pub buffer_before_minutes: Option<i32>,
+    pub buffer_after_minutes: Option<i32>,
+    pub booking_granularity: String,
+    pub requires_approval: bool,
+}
+
+/// How finely a bike can be booked. Slots are contiguous, half days are split
+/// at a configured hour and whole days run from midnight to midnight.
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum BookingGranularity {
+    Free,
+    Hourly,
+    HalfDay,
+    Day,
+}
+
+impl BookingGranularity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BookingGranularity::Free => "free",
+            BookingGranularity::Hourly => "hourly",
+            BookingGranularity::HalfDay => "half_day",
+            BookingGranularity::Day => "day",
+        }
+    }
+
+    /// Slots of the day in the deployment timezone in order, free-form
+    /// bookings have none. Hours skipped when the clocks go forward get no
+    /// slot of their own.
+    pub fn slots(self, date: NaiveDate, half_day_split_hour: u32) -> Vec<BookingSlot> {
+        let midnight = date.and_hms(0, 0, 0);
+        let hours = match self {
+            BookingGranularity::Free => vec![],
+            BookingGranularity::Hourly => (0..24).collect(),
+            BookingGranularity::HalfDay => vec![0, half_day_split_hour],
+            BookingGranularity::Day => vec![0],
+        };
+
+        hours.iter()
+            .enumerate()
+            .map(|(index, hour)| BookingSlot {
+                start_timestamp: timezone::from_local(midnight + Duration::hours(i64::from(*hour))),
+                end_timestamp: timezone::from_local(midnight + Duration::hours(i64::from(hours.get(index + 1).cloned().unwrap_or(24)))),
+            })
+            .filter(|slot| slot.start_timestamp < slot.end_timestamp)
+            .collect()
+    }
+
+    /// Widens the window to whole slots.
+    pub fn snap(self, start: NaiveDateTime, end: NaiveDateTime, half_day_split_hour: u32) -> (NaiveDateTime, NaiveDateTime) {
+        let slot_of = |timestamp: NaiveDateTime| self.slots(timezone::to_local(timestamp).naive_local().date(), half_day_split_hour).into_iter()
+            .find(|slot| slot.start_timestamp <= timestamp && timestamp < slot.end_timestamp);
+
+        (
+            slot_of(start).map_or(start, |slot| slot.start_timestamp),
+            slot_of(end).map_or(end, |slot| if slot.start_timestamp == end { end } else { slot.end_timestamp }),
+        )
+    }
+}
+
+impl FromStr for BookingGranularity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<BookingGranularity, String> {
+        match s {
+            "free" => Ok(BookingGranularity::Free),
+            "hourly" => Ok(BookingGranularity::Hourly),
+            "half_day" => Ok(BookingGranularity::HalfDay),
+            "day" => Ok(BookingGranularity::Day),
+            other => Err(format!("Unknown booking granularity '{}'.", other)),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[derive(Debug,Clone,PartialEq)]
+pub struct BookingSlot {
+    #[serde(with = "crate::timezone::timestamp")]
+    pub start_timestamp: NaiveDateTime,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub end_timestamp: NaiveDateTime,
+}
+
+/// The slots a bike can be booked in on a day, for rendering a picker.
+#[derive(Serialize)]
+#[derive(Debug)]
+pub struct BikeSlots {
+    pub bike_id: i32,
+    pub booking_granularity: String,
+    pub slots: Vec<BookingSlot>,
+}
+
+#[derive(Deserialize)]
+#[derive(Debug)]
+pub struct BikeGranularityRequest {
+    pub booking_granularity: String,
+}
+
+#[derive(Deserialize)]
+#[derive(Debug)]
+pub struct BikeApprovalRequest {
+    pub requires_approval: bool,
+}
+
+/// Turnaround buffers of a bike, empty values fall back to the global ones.
+#[derive(AsChangeset,Deserialize)]
+#[table_name="bikes"]
+#[changeset_options(treat_none_as_null="true")]
+#[derive(Debug)]
+pub struct BikeBufferData {
+    pub buffer_before_minutes: Option<i32>,
+    pub buffer_after_minutes: Option<i32>,
+}
+
+/// Operational status of a bike. Only active bikes can be booked, retired
+/// and hidden bikes are not listed at all.
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum BikeStatus {
+    Active,
+    Maintenance,
+    Retired,
+    Hidden,
+}
+
+impl BikeStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BikeStatus::Active => "active",
+            BikeStatus::Maintenance => "maintenance",
+            BikeStatus::Retired => "retired",
+            BikeStatus::Hidden => "hidden",
+        }
+    }
+
+    pub fn is_listed(self) -> bool {
+        self == BikeStatus::Active || self == BikeStatus::Maintenance
+    }
+}
+
+impl FromStr for BikeStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<BikeStatus, String> {
+        match s {
+            "active" => Ok(BikeStatus::Active),
+            "maintenance" => Ok(BikeStatus::Maintenance),
+            "retired" => Ok(BikeStatus::Retired),
+            "hidden" => Ok(BikeStatus::Hidden),
+            other => Err(format!("Unknown bike status '{}'.", other)),
+        }
+    }
+}
+
+#[derive(Queryable,Identifiable,Serialize)]
+#[derive(Debug)]
+pub struct BikeStatusChange {
+    pub id: i32,
+    pub bike_id: i32,
+    pub status: String,
+    pub comment: Option<String>,
+    pub changed_by: String,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub changed_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name="bike_status_changes"]
+#[derive(Debug)]
+pub struct InsertBikeStatusChange {
+    pub bike_id: i32,
+    pub status: String,
+    pub comment: Option<String>,
+    pub changed_by: String,
+}
+
+#[derive(Deserialize)]
+#[derive(Debug)]
+pub struct BikeStatusRequest {
+    pub status: String,
+    pub comment: Option<String>,
+}
+
+/// The recorded change together with the upcoming rents which were flagged
+/// because of it.
+#[derive(Serialize)]
+#[derive(Debug)]
+pub struct BikeStatusUpdate {
+    pub change: BikeStatusChange,
+    pub flagged_rents: Vec<Rent>,
+}
+
+#[derive(Queryable,Serialize)]
+#[derive(Debug)]
+pub struct BikeTranslatable {
+    pub id: i32,
+    pub bike_id: i32,
+    pub locale: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub url: Option<String>
+}
+
+#[derive(Insertable,AsChangeset,Deserialize)]
+#[table_name="bike_translatables"]
+#[changeset_options(treat_none_as_null="true")]
+#[derive(Debug)]
+pub struct InsertBikeTranslatable {
+    pub bike_id: i32,
+    pub locale: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub url: Option<String>
+}
+
+#[derive(Queryable,Identifiable,Serialize)]
+#[table_name="bike_media"]
+#[derive(Debug)]
+pub struct BikeMedia {
+    pub id: i32,
+    pub bike_id: i32,
+    pub position: i32,
+    pub url: String,
+    pub width: i32,
+    pub height: i32,
+    pub thumbnail_url: String,
+    pub thumbnail_width: i32,
+    pub thumbnail_height: i32,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name="bike_media"]
+#[derive(Debug)]
+pub struct InsertBikeMedia {
+    pub bike_id: i32,
+    pub position: i32,
+    pub url: String,
+    pub width: i32,
+    pub height: i32,
+    pub thumbnail_url: String,
+    pub thumbnail_width: i32,
+    pub thumbnail_height: i32,
+}
+
+#[derive(Deserialize)]
+#[derive(Debug)]
+pub struct BikeMediaPosition {
+    pub position: i32,
+}
+
+#[derive(Queryable,Serialize)]
+#[derive(Debug)]
+pub struct BikeMediaTranslatable {
+    pub id: i32,
+    pub bike_media_id: i32,
+    pub locale: String,
+    pub alt_text: String,
+}
+
+#[derive(Insertable,AsChangeset,Deserialize)]
+#[table_name="bike_media_translatables"]
+#[derive(Debug)]
+pub struct InsertBikeMediaTranslatable {
+    pub bike_media_id: i32,
+    pub locale: String,
+    pub alt_text: String,
+}
+
+#[derive(Deserialize)]
+#[derive(Debug)]
+pub struct BikeMediaTranslationData {
+    pub alt_text: String,
+}
+
+#[derive(Serialize)]
+#[derive(Debug)]
+pub struct BikeMediaWithAltText {
+    pub id: i32,
+    pub position: i32,
+    pub url: String,
+    pub width: i32,
+    pub height: i32,
+    pub thumbnail_url: String,
+    pub thumbnail_width: i32,
+    pub thumbnail_height: i32,
+    pub alt_text: Option<String>,
+}
+
+#[derive(Queryable,Identifiable,Serialize)]
+#[derive(Debug,Clone)]
+pub struct BikeAttribute {
+    pub id: i32,
+    pub key: String,
+    pub value_type: String,
+    pub unit: Option<String>,
+    pub sort_priority: i32,
+}
+
+#[derive(Insertable,AsChangeset,Deserialize)]
+#[table_name="bike_attributes"]
+#[changeset_options(treat_none_as_null="true")]
+#[derive(Debug)]
+pub struct InsertBikeAttribute {
+    pub key: String,
+    pub value_type: String,
+    pub unit: Option<String>,
+    pub sort_priority: i32,
+}
+
+#[derive(Queryable,Serialize)]
+#[derive(Debug)]
+pub struct BikeAttributeTranslatable {
+    pub id: i32,
+    pub bike_attribute_id: i32,
+    pub locale: String,
+    pub label: String,
+}
+
+#[derive(Insertable,AsChangeset)]
+#[table_name="bike_attribute_translatables"]
+#[derive(Debug)]
+pub struct InsertBikeAttributeTranslatable {
+    pub bike_attribute_id: i32,
+    pub locale: String,
+    pub label: String,
+}
+
+#[derive(Queryable,Identifiable,Serialize)]
+#[derive(Debug)]
+pub struct BikeAttributeOption {
+    pub id: i32,
+    pub bike_attribute_id: i32,
+    pub key: String,
+}
+
+#[derive(Insertable)]
+#[table_name="bike_attribute_options"]
+#[derive(Debug)]
+pub struct InsertBikeAttributeOption {
+    pub bike_attribute_id: i32,
+    pub key: String,
+}
+
+#[derive(Deserialize)]
+#[derive(Debug)]
+pub struct BikeAttributeOptionData {
+    pub key: String,
+}
+
+#[derive(Queryable,Serialize)]
+#[derive(Debug)]
+pub struct BikeAttributeOptionTranslatable {
+    pub id: i32,
+    pub bike_attribute_option_id: i32,
+    pub locale: String,
+    pub label: String,
+}
+
+#[derive(Insertable,AsChangeset)]
+#[table_name="bike_attribute_option_translatables"]
+#[derive(Debug)]
+pub struct InsertBikeAttributeOptionTranslatable {
+    pub bike_attribute_option_id: i32,
+    pub locale: String,
+    pub label: String,
+}
+
+#[derive(Deserialize)]
+#[derive(Debug)]
+pub struct LabelTranslationData {
+    pub label: String,
+}
+
+#[derive(Queryable,Identifiable,Serialize)]
+#[derive(Debug)]
+pub struct BikeAttributeValue {
+    pub id: i32,
+    pub bike_id: i32,
+    pub bike_attribute_id: i32,
+    pub number_value: Option<f64>,
+    pub boolean_value: Option<bool>,
+    pub bike_attribute_option_id: Option<i32>,
+}
+
+#[derive(Insertable,AsChangeset)]
+#[table_name="bike_attribute_values"]
+#[changeset_options(treat_none_as_null="true")]
+#[derive(Debug)]
+pub struct InsertBikeAttributeValue {
+    pub bike_id: i32,
+    pub bike_attribute_id: i32,
+    pub number_value: Option<f64>,
+    pub boolean_value: Option<bool>,
+    pub bike_attribute_option_id: Option<i32>,
+}
+
+#[derive(Deserialize)]
+#[derive(Debug)]
+pub struct BikeAttributeValueData {
+    pub number_value: Option<f64>,
+    pub boolean_value: Option<bool>,
+    pub bike_attribute_option_id: Option<i32>,
+}
+
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum AttributeType {
+    Number,
+    Boolean,
+    Enum,
+}
+
+impl FromStr for AttributeType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<AttributeType, String> {
+        match s {
+            "number" => Ok(AttributeType::Number),
+            "boolean" => Ok(AttributeType::Boolean),
+            "enum" => Ok(AttributeType::Enum),
+            other => Err(format!("Unknown attribute type '{}'.", other)),
+        }
+    }
+}
+
+/// Value of a bike attribute, enum values are represented by the key of
+/// the option.
+#[derive(Serialize)]
+#[serde(untagged)]
+#[derive(Debug,Clone,PartialEq)]
+pub enum AttributeValue {
+    Number(f64),
+    Boolean(bool),
+    Option(String),
+}
+
+#[derive(Serialize)]
+#[derive(Debug)]
+pub struct BikeAttributeWithValue {
+    pub key: String,
+    pub label: Option<String>,
+    pub unit: Option<String>,
+    pub value: AttributeValue,
+    pub value_label: Option<String>,
+}
+
+/// A listed bike. Its buffers are those in effect, global ones included.
+#[derive(Serialize)]
+#[derive(Debug)]
+pub struct BikeWithDetails {
+    #[serde(flatten)]
+    pub bike: BikeTranslatable,
+    pub status: String,
+    pub buffer_before_minutes: i32,
+    pub buffer_after_minutes: i32,
+    pub booking_granularity: String,
+    pub requires_approval: bool,
+    pub attributes: Vec<BikeAttributeWithValue>,
+    pub media: Vec<BikeMediaWithAltText>,
+}
+
+/// A period in which a bike, or every bike if `bike_id` is empty, cannot be
+/// booked.
+#[derive(Queryable,Identifiable,Serialize)]
+#[derive(Debug)]
+pub struct BikeBlackout {
+    pub id: i32,
+    pub bike_id: Option<i32>,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub start_timestamp: NaiveDateTime,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub end_timestamp: NaiveDateTime,
+    pub reason: Option<String>,
+    pub created_by: String,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name="bike_blackouts"]
+#[derive(Debug)]
+pub struct InsertBikeBlackout {
+    pub bike_id: Option<i32>,
+    pub start_timestamp: NaiveDateTime,
+    pub end_timestamp: NaiveDateTime,
+    pub reason: Option<String>,
+    pub created_by: String,
+}
+
+#[derive(Deserialize)]
+#[derive(Debug)]
+pub struct BikeBlackoutRequest {
+    pub bike_id: Option<i32>,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub start_timestamp: NaiveDateTime,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub end_timestamp: NaiveDateTime,
+    pub reason: Option<String>,
+}
+
+/// A recurring service of a bike which is due after a number of days, a
+/// number of completed rents or whatever comes first.
+#[derive(Queryable,Identifiable,Serialize)]
+#[derive(Debug,Clone)]
+pub struct BikeServiceInterval {
+    pub id: i32,
+    pub bike_id: i32,
+    pub title: String,
+    pub interval_days: Option<i32>,
+    pub interval_rents: Option<i32>,
+    pub blocks_bookings: bool,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub created_at: NaiveDateTime,
+    #[serde(with = "crate::timezone::optional_timestamp")]
+    pub notified_at: Option<NaiveDateTime>,
+}
+
+#[derive(Insertable,AsChangeset)]
+#[table_name="bike_service_intervals"]
+#[changeset_options(treat_none_as_null="true")]
+#[derive(Debug)]
+pub struct InsertBikeServiceInterval {
+    pub bike_id: i32,
+    pub title: String,
+    pub interval_days: Option<i32>,
+    pub interval_rents: Option<i32>,
+    pub blocks_bookings: bool,
+}
+
+#[derive(Deserialize)]
+#[derive(Debug)]
+pub struct BikeServiceIntervalData {
+    pub title: String,
+    pub interval_days: Option<i32>,
+    pub interval_rents: Option<i32>,
+    #[serde(default)]
+    pub blocks_bookings: bool,
+}
+
+#[derive(Queryable,Identifiable,Serialize)]
+#[derive(Debug)]
+pub struct BikeWorkOrder {
+    pub id: i32,
+    pub bike_id: i32,
+    pub bike_service_interval_id: Option<i32>,
+    pub description: String,
+    pub parts: Option<String>,
+    pub cost_cents: Option<i32>,
+    pub performed_by: String,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub performed_at: NaiveDateTime,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name="bike_work_orders"]
+#[derive(Debug)]
+pub struct InsertBikeWorkOrder {
+    pub bike_id: i32,
+    pub bike_service_interval_id: Option<i32>,
+    pub description: String,
+    pub parts: Option<String>,
+    pub cost_cents: Option<i32>,
+    pub performed_by: String,
+    pub performed_at: NaiveDateTime,
+}
+
+/// A work order as entered by the mechanics. The admin creating it and the
+/// current time are used if `performed_by` or `performed_at` are missing.
+/// Referencing a service interval marks that service as done.
+#[derive(Deserialize)]
+#[derive(Debug)]
+pub struct BikeWorkOrderData {
+    pub bike_service_interval_id: Option<i32>,
+    pub description: String,
+    pub parts: Option<String>,
+    pub cost_cents: Option<i32>,
+    pub performed_by: Option<String>,
+    #[serde(default, with = "crate::timezone::optional_timestamp")]
+    pub performed_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum IncidentCategory {
+    FlatTyre,
+    Damage,
+    Crash,
+    Theft,
+    Other,
+}
+
+impl IncidentCategory {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IncidentCategory::FlatTyre => "flat_tyre",
+            IncidentCategory::Damage => "damage",
+            IncidentCategory::Crash => "crash",
+            IncidentCategory::Theft => "theft",
+            IncidentCategory::Other => "other",
+        }
+    }
+}
+
+impl FromStr for IncidentCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<IncidentCategory, String> {
+        match s {
+            "flat_tyre" => Ok(IncidentCategory::FlatTyre),
+            "damage" => Ok(IncidentCategory::Damage),
+            "crash" => Ok(IncidentCategory::Crash),
+            "theft" => Ok(IncidentCategory::Theft),
+            "other" => Ok(IncidentCategory::Other),
+            other => Err(format!("Unknown incident category '{}'.", other)),
+        }
+    }
+}
+
+/// A problem reported for a rent. `reported_by` is the staff member who filed
+/// it, incidents filed by the renter have none.
+#[derive(Queryable,Identifiable,Serialize)]
+#[derive(Debug,Clone)]
+pub struct BikeIncident {
+    pub id: i32,
+    pub rent_id: i32,
+    pub bike_id: i32,
+    pub category: String,
+    pub description: String,
+    pub reported_by: Option<String>,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name="bike_incidents"]
+#[derive(Debug)]
+pub struct InsertBikeIncident {
+    pub rent_id: i32,
+    pub bike_id: i32,
+    pub category: String,
+    pub description: String,
+    pub reported_by: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[derive(Debug)]
+pub struct BikeIncidentData {
+    pub category: String,
+    pub description: String,
+    /// Bike of the booking the incident is about, defaults to the first one.
+    /// Not needed for an occurrence of a series.
+    #[serde(default)]
+    pub bike_id: Option<i32>,
+}
+
+#[derive(Queryable,Identifiable,Serialize)]
+#[derive(Debug,Clone)]
+pub struct BikeIncidentPhoto {
+    pub id: i32,
+    pub bike_incident_id: i32,
+    pub url: String,
+    pub width: i32,
+    pub height: i32,
+    pub thumbnail_url: String,
+    pub thumbnail_width: i32,
+    pub thumbnail_height: i32,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name="bike_incident_photos"]
+#[derive(Debug)]
+pub struct InsertBikeIncidentPhoto {
+    pub bike_incident_id: i32,
+    pub url: String,
+    pub width: i32,
+    pub height: i32,
+    pub thumbnail_url: String,
+    pub thumbnail_width: i32,
+    pub thumbnail_height: i32,
+}
+
+#[derive(Serialize)]
+#[derive(Debug)]
+pub struct BikeIncidentWithPhotos {
+    #[serde(flatten)]
+    pub incident: BikeIncident,
+    pub photos: Vec<BikeIncidentPhoto>,
+}
+
+/// Entry of the maintenance history of a bike, newest first.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[derive(Debug)]
+pub enum MaintenanceHistoryEntry {
+    WorkOrder(BikeWorkOrder),
+    Incident(BikeIncidentWithPhotos),
+}
+
+impl MaintenanceHistoryEntry {
+    pub fn timestamp(&self) -> NaiveDateTime {
+        match self {
+            MaintenanceHistoryEntry::WorkOrder(work_order) => work_order.performed_at,
+            MaintenanceHistoryEntry::Incident(incident) => incident.incident.created_at,
+        }
+    }
+}
+
+/// A service interval which has run out. Intervals count from the last work
+/// order done for them, or from their creation if there is none yet.
+#[derive(QueryableByName,Serialize)]
+#[derive(Debug,Clone)]
+pub struct ServiceDue {
+    #[sql_type = "diesel::sql_types::Integer"]
+    pub bike_id: i32,
+    #[sql_type = "diesel::sql_types::Integer"]
+    pub bike_service_interval_id: i32,
+    #[sql_type = "diesel::sql_types::Text"]
+    pub title: String,
+    #[sql_type = "diesel::sql_types::Timestamptz"]
+    #[serde(with = "crate::timezone::timestamp")]
+    pub last_service_at: NaiveDateTime,
+    #[sql_type = "diesel::sql_types::BigInt"]
+    pub days_since: i64,
+    #[sql_type = "diesel::sql_types::BigInt"]
+    pub rents_since: i64,
+    #[sql_type = "diesel::sql_types::Bool"]
+    pub blocks_bookings: bool,
+    #[sql_type = "diesel::sql_types::Nullable<diesel::sql_types::Timestamptz>"]
+    #[serde(with = "crate::timezone::optional_timestamp")]
+    pub notified_at: Option<NaiveDateTime>,
+}
+
+#[derive(Queryable,Identifiable,Serialize)]
+#[derive(Debug)]
+pub struct Rent {
+    pub id: i32,
+    pub token_id: i32,
+    pub bike_id: i32,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub created_at: NaiveDateTime,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub start_timestamp: NaiveDateTime,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub end_timestamp: NaiveDateTime,
+    #[serde(with = "crate::timezone::optional_timestamp")]
+    pub revocation_timestamp: Option<NaiveDateTime>,
+    #[serde(with = "crate::timezone::optional_timestamp")]
+    pub flagged_at: Option<NaiveDateTime>,
+    pub flag_reason: Option<String>,
+}
+
+#[derive(Queryable,Identifiable,Serialize)]
+#[derive(Debug)]
+pub struct RentFeedback {
+    pub id: i32,
+    pub rent_id: i32,
+    pub rating: i16,
+    pub comment: Option<String>,
+    pub distance_km: Option<f64>,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name="rent_feedbacks"]
+#[derive(Debug)]
+pub struct InsertRentFeedback {
+    pub rent_id: i32,
+    pub rating: i16,
+    pub comment: Option<String>,
+    pub distance_km: Option<f64>,
+}
+
+#[derive(Deserialize)]
+#[derive(Debug)]
+pub struct RentFeedbackData {
+    /// Bike of the booking the feedback is about, not needed for an
+    /// occurrence of a series.
+    #[serde(default)]
+    pub bike_id: Option<i32>,
+    pub rating: i16,
+    pub comment: Option<String>,
+    pub distance_km: Option<f64>,
+}
+
+/// Feedback of all rents of a bike. Rents without a distance do not count
+/// into `total_distance_km`.
+#[derive(Serialize)]
+#[derive(Debug,PartialEq)]
+pub struct BikeFeedbackSummary {
+    pub bike_id: i32,
+    pub feedback_count: i64,
+    pub average_rating: f64,
+    pub total_distance_km: f64,
+}
+
+#[derive(Queryable,Identifiable,Serialize)]
+#[table_name="tokens"]
+#[derive(Debug)]
+pub struct Token {
+    pub id: i32,
+    pub uuid: Uuid,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub created_at: NaiveDateTime,
+    #[serde(skip_serializing)]
+    pub rider_hash: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct Booking {
+    pub token: Uuid,
+    pub bike_id: i32,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub start_timestamp: NaiveDateTime,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub end_timestamp: NaiveDateTime,
+    pub encrypted_details: String,
+    pub short_token: String,
+    pub email: Option<String>,
+    #[serde(default)]
+    pub additional_bike_ids: Vec<i32>,
+    #[serde(default)]
+    pub accessories: Vec<BookingAccessory>,
+    /// Hold on the slot which is turned into the rent, see `RentHold`.
+    #[serde(default)]
+    pub hold_id: Option<Uuid>,
+}
+
+impl Booking {
+    /// Bikes reserved by the booking, all of them under the same token.
+    pub fn bike_ids(&self) -> Vec<i32> {
+        let mut bike_ids = vec![self.bike_id];
+        bike_ids.extend(&self.additional_bike_ids);
 
-use crate::schema::*;
+        bike_ids
+    }
+}
 
-#[derive(Queryable,Serialize)]
-#[derive(Debug)]
-pub struct Bike {
-    pub id: i32
+#[derive(Deserialize)]
+#[derive(Debug,Clone)]
+pub struct BookingAccessory {
+    pub accessory_id: i32,
+    pub quantity: i32,
+    /// Bike of the booking the accessory goes with, defaults to the first one.
+    #[serde(default)]
+    pub bike_id: Option<i32>,
 }
 
-#[derive(Queryable,Serialize)]
+/// An accessory kind, `quantity` is the number of items in the inventory.
+#[derive(Queryable,Identifiable,Serialize)]
+#[table_name="accessories"]
+#[derive(Debug,Clone)]
+pub struct Accessory {
+    pub id: i32,
+    pub quantity: i32,
+    pub sort_priority: i32,
+}
+
+#[derive(Insertable,AsChangeset,Deserialize)]
+#[table_name="accessories"]
 #[derive(Debug)]
-pub struct BikeTranslatable {
+pub struct InsertAccessory {
+    pub quantity: i32,
+    pub sort_priority: i32,
+}
+
+#[derive(Queryable,Identifiable,Serialize)]
+#[derive(Debug,Clone)]
+pub struct AccessoryTranslatable {
     pub id: i32,
-    pub bike_id: i32,
+    pub accessory_id: i32,
     pub locale: String,
     pub title: String,
     pub description: Option<String>,
-    pub url: Option<String>
 }
 
-#[derive(Insertable,Deserialize)]
-#[table_name="bike_translatables"]
+#[derive(Insertable,AsChangeset)]
+#[table_name="accessory_translatables"]
+#[changeset_options(treat_none_as_null="true")]
 #[derive(Debug)]
-pub struct InsertBikeTranslatable {
-    pub bike_id: i32,
+pub struct InsertAccessoryTranslatable {
+    pub accessory_id: i32,
     pub locale: String,
     pub title: String,
     pub description: Option<String>,
-    pub url: Option<String>
+}
+
+#[derive(Deserialize)]
+#[derive(Debug)]
+pub struct AccessoryTranslationData {
+    pub title: String,
+    pub description: Option<String>,
+}
+
+/// A localized accessory. `available` is only set when a window was asked
+/// for and tells how many items are free for the whole window.
+#[derive(Serialize)]
+#[derive(Debug)]
+pub struct AccessoryWithDetails {
+    #[serde(flatten)]
+    pub accessory: AccessoryTranslatable,
+    pub quantity: i32,
+    pub sort_priority: i32,
+    pub available: Option<i32>,
 }
 
 #[derive(Queryable,Identifiable,Serialize)]
+#[table_name="rent_accessories"]
 #[derive(Debug)]
-pub struct Rent {
+pub struct RentAccessory {
+    pub id: i32,
+    pub rent_id: i32,
+    pub accessory_id: i32,
+    pub quantity: i32,
+}
+
+#[derive(Insertable)]
+#[table_name="rent_accessories"]
+#[derive(Debug)]
+pub struct InsertRentAccessory {
+    pub rent_id: i32,
+    pub accessory_id: i32,
+    pub quantity: i32,
+}
+
+/// Restricts an accessory to the listed bikes, accessories without any go
+/// with every bike.
+#[derive(Insertable)]
+#[table_name="accessory_bikes"]
+#[derive(Debug)]
+pub struct InsertAccessoryBike {
+    pub accessory_id: i32,
+    pub bike_id: i32,
+}
+
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum RecurrenceFrequency {
+    Weekly,
+    Biweekly,
+}
+
+impl RecurrenceFrequency {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RecurrenceFrequency::Weekly => "weekly",
+            RecurrenceFrequency::Biweekly => "biweekly",
+        }
+    }
+
+    /// Days between the starts of two occurrences.
+    pub fn days(self) -> i64 {
+        match self {
+            RecurrenceFrequency::Weekly => 7,
+            RecurrenceFrequency::Biweekly => 14,
+        }
+    }
+}
+
+impl FromStr for RecurrenceFrequency {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<RecurrenceFrequency, String> {
+        match s {
+            "weekly" => Ok(RecurrenceFrequency::Weekly),
+            "biweekly" => Ok(RecurrenceFrequency::Biweekly),
+            other => Err(format!("Unknown recurrence frequency '{}'.", other)),
+        }
+    }
+}
+
+/// The rule of a recurring booking. The timestamps are those of the first
+/// occurrence, the series ends at `until_date` or after `occurrence_count`
+/// occurrences. Every occurrence is booked as a rent of the series' token.
+#[derive(Queryable,Identifiable,Serialize)]
+#[table_name="rent_series"]
+#[derive(Debug)]
+pub struct RentSeries {
     pub id: i32,
     pub token_id: i32,
     pub bike_id: i32,
+    pub frequency: String,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub start_timestamp: NaiveDateTime,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub end_timestamp: NaiveDateTime,
+    pub until_date: Option<NaiveDate>,
+    pub occurrence_count: Option<i32>,
+    #[serde(with = "crate::timezone::timestamp")]
     pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name="rent_series"]
+#[derive(Debug)]
+pub struct InsertRentSeries {
+    pub token_id: i32,
+    pub bike_id: i32,
+    pub frequency: String,
+    pub start_timestamp: NaiveDateTime,
+    pub end_timestamp: NaiveDateTime,
+    pub until_date: Option<NaiveDate>,
+    pub occurrence_count: Option<i32>,
+}
+
+#[derive(Deserialize)]
+#[derive(Debug)]
+pub struct RecurringBooking {
+    pub token: Uuid,
+    pub bike_id: i32,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub start_timestamp: NaiveDateTime,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub end_timestamp: NaiveDateTime,
+    pub encrypted_details: String,
+    pub short_token: String,
+    pub email: Option<String>,
+    pub frequency: String,
+    pub until_date: Option<NaiveDate>,
+    pub occurrence_count: Option<i32>,
+    pub hold_id: Option<Uuid>,
+}
+
+#[derive(Serialize)]
+#[derive(Debug,Clone,PartialEq)]
+pub struct RentSeriesOccurrence {
+    #[serde(with = "crate::timezone::timestamp")]
     pub start_timestamp: NaiveDateTime,
+    #[serde(with = "crate::timezone::timestamp")]
     pub end_timestamp: NaiveDateTime,
-    pub revocation_timestamp: Option<NaiveDateTime>
 }
 
+#[derive(Serialize)]
+#[derive(Debug)]
+pub struct RentSeriesWithRents {
+    #[serde(flatten)]
+    pub series: RentSeries,
+    pub rents: Vec<Rent>,
+}
+
+/// Result of booking a series. Occurrences which collide with other rents or
+/// blackouts are not booked and listed in `conflicts` instead.
+#[derive(Serialize)]
+#[derive(Debug)]
+pub struct RentSeriesBooking {
+    #[serde(flatten)]
+    pub series: RentSeriesWithRents,
+    pub conflicts: Vec<RentSeriesOccurrence>,
+    pub approval_status: ApprovalStatus,
+    pub quota: Option<RiderQuota>,
+    #[serde(skip)]
+    pub verification: Option<EmailVerification>,
+}
+
+/// A rider waiting for a bike in a window which was taken when they asked.
+/// The entry spends the token, the rider is only offered slots once the
+/// address left with it has been confirmed. `notified_at` is set once the
+/// rider has been offered a hold on the slot.
 #[derive(Queryable,Identifiable,Serialize)]
-#[table_name="tokens"]
+#[table_name="waitlist_entries"]
+#[derive(Debug,Clone)]
+pub struct WaitlistEntry {
+    pub id: i32,
+    pub uuid: Uuid,
+    pub bike_id: i32,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub start_timestamp: NaiveDateTime,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub end_timestamp: NaiveDateTime,
+    pub token_id: i32,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub created_at: NaiveDateTime,
+    #[serde(with = "crate::timezone::optional_timestamp")]
+    pub notified_at: Option<NaiveDateTime>,
+}
+
+#[derive(Deserialize)]
 #[derive(Debug)]
-pub struct Token {
+pub struct WaitlistRequest {
+    pub token: Uuid,
+    pub bike_id: i32,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub start_timestamp: NaiveDateTime,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub end_timestamp: NaiveDateTime,
+    pub email: String,
+}
+
+#[derive(Insertable)]
+#[table_name="waitlist_entries"]
+#[derive(Debug)]
+pub struct InsertWaitlistEntry {
+    pub token_id: i32,
+    pub bike_id: i32,
+    pub start_timestamp: NaiveDateTime,
+    pub end_timestamp: NaiveDateTime,
+}
+
+/// Keeps a slot free for a single rider until `expires_at`. Active holds
+/// block the bike like rents, a booking presenting the hold's uuid takes it.
+/// Holds requested by a rider can only be taken with the same token, holds
+/// offered to waitlisted riders have none.
+#[derive(Queryable,Identifiable,Serialize)]
+#[table_name="rent_holds"]
+#[derive(Debug,Clone)]
+pub struct RentHold {
     pub id: i32,
     pub uuid: Uuid,
-    pub created_at: NaiveDateTime
+    pub bike_id: i32,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub start_timestamp: NaiveDateTime,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub end_timestamp: NaiveDateTime,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub expires_at: NaiveDateTime,
+    pub waitlist_entry_id: Option<i32>,
+    pub token_id: Option<i32>,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub created_at: NaiveDateTime,
 }
 
 #[derive(Deserialize)]
-pub struct Booking {
+#[derive(Debug,Clone)]
+pub struct HoldRequest {
     pub token: Uuid,
     pub bike_id: i32,
+    #[serde(with = "crate::timezone::timestamp")]
     pub start_timestamp: NaiveDateTime,
+    #[serde(with = "crate::timezone::timestamp")]
     pub end_timestamp: NaiveDateTime,
-    pub encrypted_details: String,
-    pub short_token: String,
-    pub email: Option<String>
+    #[serde(default)]
+    pub accessories: Vec<BookingAccessory>,
+}
+
+#[derive(Insertable)]
+#[table_name="rent_hold_accessories"]
+#[derive(Debug)]
+pub struct InsertRentHoldAccessory {
+    pub rent_hold_id: i32,
+    pub accessory_id: i32,
+    pub quantity: i32,
+}
+
+#[derive(Insertable)]
+#[table_name="rent_holds"]
+#[derive(Debug)]
+pub struct InsertRentHold {
+    pub bike_id: i32,
+    pub start_timestamp: NaiveDateTime,
+    pub end_timestamp: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    pub waitlist_entry_id: Option<i32>,
+    pub token_id: Option<i32>,
+}
+
+/// What is left of the fair-use limits after a booking, empty where there is
+/// no limit.
+#[derive(Serialize)]
+#[derive(Debug,Clone,PartialEq)]
+pub struct RiderQuota {
+    pub active_bookings_remaining: Option<i64>,
+    pub booked_hours_remaining: Option<f64>,
+}
+
+/// Outcome of a booking besides its rents. The verification holds the code
+/// which only the mail may reveal.
+#[derive(Debug,Clone,PartialEq)]
+pub struct BookingReceipt {
+    pub approval_status: ApprovalStatus,
+    pub quota: Option<RiderQuota>,
+    pub verification: Option<EmailVerification>,
+}
+
+/// Whether the rider confirmed the email address left with a booking. The
+/// rents of unconfirmed bookings reserve the slot until the link lapses.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum VerificationStatus {
+    Pending,
+    Verified,
+    Lapsed,
+}
+
+impl VerificationStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            VerificationStatus::Pending => "pending",
+            VerificationStatus::Verified => "verified",
+            VerificationStatus::Lapsed => "lapsed",
+        }
+    }
+}
+
+/// The address is only stored sealed, see `email_address::seal`.
+#[derive(Queryable,Identifiable)]
+#[derive(Debug,Clone,PartialEq)]
+pub struct EmailVerification {
+    pub id: i32,
+    pub token_id: i32,
+    pub uuid: Uuid,
+    pub encrypted_email: String,
+    pub short_token: Option<String>,
+    pub status: String,
+    pub expires_at: NaiveDateTime,
+    pub verified_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name="email_verifications"]
+#[derive(Debug)]
+pub struct InsertEmailVerification {
+    pub token_id: i32,
+    pub encrypted_email: String,
+    pub short_token: Option<String>,
+    pub expires_at: NaiveDateTime,
+}
+
+/// Decision on a booking of bikes which require approval. Its rents reserve
+/// the slot while pending and are revoked if the booking is declined or
+/// nobody decides before `expires_at`.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+#[derive(Debug,Clone,Copy,PartialEq)]
+pub enum ApprovalStatus {
+    Pending,
+    Approved,
+    Declined,
+    Expired,
+}
+
+impl ApprovalStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ApprovalStatus::Pending => "pending",
+            ApprovalStatus::Approved => "approved",
+            ApprovalStatus::Declined => "declined",
+            ApprovalStatus::Expired => "expired",
+        }
+    }
+}
+
+#[derive(Queryable,Identifiable,Serialize)]
+#[derive(Debug,Clone)]
+pub struct RentApproval {
+    pub id: i32,
+    pub token_id: i32,
+    pub status: String,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub expires_at: NaiveDateTime,
+    pub decided_by: Option<String>,
+    #[serde(with = "crate::timezone::optional_timestamp")]
+    pub decided_at: Option<NaiveDateTime>,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name="rent_approvals"]
+#[derive(Debug)]
+pub struct InsertRentApproval {
+    pub token_id: i32,
+    pub expires_at: NaiveDateTime,
+}
+
+#[derive(Serialize)]
+#[derive(Debug)]
+pub struct RentApprovalWithRents {
+    #[serde(flatten)]
+    pub approval: RentApproval,
+    pub rents: Vec<Rent>,
 }
 
 #[derive(Queryable,Serialize)]
@@ -83,8 +1308,9 @@ pub struct TokenChallengeTranslatable {
     pub url: Option<String>
 }
 
-#[derive(Insertable,Deserialize)]
+#[derive(Insertable,AsChangeset,Deserialize)]
 #[table_name="token_challenge_translatables"]
+#[changeset_options(treat_none_as_null="true")]
 #[derive(Debug)]
 pub struct InsertTokenChallengeTranslatable {
     pub token_challenge_id: i32,
@@ -100,13 +1326,32 @@ pub struct ChallengeResponse {
     pub answer_hash: String
 }
 
+#[derive(Serialize,Deserialize)]
+#[derive(Debug,Clone)]
+pub struct ProofOfWorkChallenge {
+    pub nonce: String,
+    pub difficulty: u8,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub expires_at: NaiveDateTime,
+    pub signature: String
+}
+
+#[derive(Deserialize)]
+#[derive(Debug)]
+pub struct ProofOfWorkResponse {
+    pub challenge: ProofOfWorkChallenge,
+    pub solution: String
+}
+
 #[derive(Insertable,Deserialize)]
 #[table_name="rents"]
 #[derive(Debug)]
 pub struct InsertRent {
     pub token_id: i32,
     pub bike_id: i32,
+    #[serde(with = "crate::timezone::timestamp")]
     pub start_timestamp: NaiveDateTime,
+    #[serde(with = "crate::timezone::timestamp")]
     pub end_timestamp: NaiveDateTime
 }
 
@@ -118,6 +1363,86 @@ pub struct InsertRentDetail {
     pub encrypted_details: String,
 }
 
+#[derive(Queryable,Identifiable,Serialize)]
+#[derive(Debug)]
+pub struct SupporterType {
+    pub id: i32,
+    pub title: String,
+    pub sort_priority: i32,
+}
+
+#[derive(Insertable,AsChangeset,Deserialize)]
+#[table_name="supporter_types"]
+#[derive(Debug)]
+pub struct InsertSupporterType {
+    pub title: String,
+    pub sort_priority: i32,
+}
+
+#[derive(Queryable,Identifiable,Serialize)]
+#[derive(Debug)]
+pub struct Supporter {
+    pub id: i32,
+    pub supporter_type_id: Option<i32>,
+    pub sort_priority: i32,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+#[derive(Insertable,AsChangeset,Deserialize)]
+#[table_name="supporters"]
+#[changeset_options(treat_none_as_null="true")]
+#[derive(Debug)]
+pub struct InsertSupporter {
+    pub supporter_type_id: Option<i32>,
+    pub sort_priority: i32,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+#[derive(Queryable,Serialize)]
+#[derive(Debug)]
+pub struct SupporterTranslatable {
+    pub id: i32,
+    pub supporter_id: i32,
+    pub locale: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub logo_url: Option<String>,
+    pub logo_width: Option<i16>,
+    pub logo_height: Option<i16>,
+    pub logo_alt_text: Option<String>,
+}
+
+#[derive(Insertable,AsChangeset,Deserialize)]
+#[table_name="supporter_translatables"]
+#[changeset_options(treat_none_as_null="true")]
+#[derive(Debug)]
+pub struct InsertSupporterTranslatable {
+    pub supporter_id: i32,
+    pub locale: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub logo_url: Option<String>,
+    pub logo_width: Option<i16>,
+    pub logo_height: Option<i16>,
+    pub logo_alt_text: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[derive(Debug)]
+pub struct SupporterTranslationData {
+    pub title: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub logo_url: Option<String>,
+    pub logo_width: Option<i16>,
+    pub logo_height: Option<i16>,
+    pub logo_alt_text: Option<String>,
+}
+
 #[derive(Queryable,Serialize)]
 #[derive(Debug)]
 pub struct SupporterWithTypeAndTranslatable {
@@ -131,4 +1456,88 @@ pub struct SupporterWithTypeAndTranslatable {
     pub logo_width: Option<i16>,
     pub logo_height: Option<i16>,
     pub logo_alt_text: Option<String>,
-}
\ No newline at end of file
+}
+
+#[derive(Queryable,Identifiable,Serialize)]
+#[derive(Debug)]
+pub struct InviteCode {
+    pub id: i32,
+    pub code: String,
+    pub group_name: String,
+    pub usage_limit: i32,
+    #[serde(with = "crate::timezone::optional_timestamp")]
+    pub valid_from: Option<NaiveDateTime>,
+    #[serde(with = "crate::timezone::optional_timestamp")]
+    pub valid_until: Option<NaiveDateTime>,
+    pub created_by: String,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[table_name="invite_codes"]
+#[derive(Debug)]
+pub struct InsertInviteCode {
+    pub code: String,
+    pub group_name: String,
+    pub usage_limit: i32,
+    pub valid_from: Option<NaiveDateTime>,
+    pub valid_until: Option<NaiveDateTime>,
+    pub created_by: String,
+}
+
+#[derive(Deserialize)]
+#[derive(Debug)]
+pub struct InviteCodeRequest {
+    pub group_name: String,
+    pub usage_limit: i32,
+    #[serde(default, with = "crate::timezone::optional_timestamp")]
+    pub valid_from: Option<NaiveDateTime>,
+    #[serde(default, with = "crate::timezone::optional_timestamp")]
+    pub valid_until: Option<NaiveDateTime>,
+    pub count: Option<u32>,
+}
+
+#[derive(Serialize)]
+#[derive(Debug)]
+pub struct InviteCodeWithUsage {
+    #[serde(flatten)]
+    pub invite_code: InviteCode,
+    pub usage_count: i64,
+}
+
+#[derive(Deserialize)]
+pub struct InviteCodeRedemptionRequest {
+    pub code: String
+}
+
+#[derive(Insertable)]
+#[table_name="invite_code_redemptions"]
+#[derive(Debug)]
+pub struct InsertInviteCodeRedemption {
+    pub invite_code_id: i32,
+    pub token_id: Option<i32>,
+}
+
+#[derive(Queryable,Serialize)]
+#[derive(Debug)]
+pub struct InviteCodeRedemptionWithRent {
+    pub id: i32,
+    #[serde(with = "crate::timezone::timestamp")]
+    pub redeemed_at: NaiveDateTime,
+    pub rent_id: Option<i32>,
+    pub bike_id: Option<i32>,
+    #[serde(with = "crate::timezone::optional_timestamp")]
+    pub start_timestamp: Option<NaiveDateTime>,
+    #[serde(with = "crate::timezone::optional_timestamp")]
+    pub end_timestamp: Option<NaiveDateTime>,
+    #[serde(with = "crate::timezone::optional_timestamp")]
+    pub revocation_timestamp: Option<NaiveDateTime>,
+}
+
+#[derive(Serialize)]
+#[derive(Debug,PartialEq)]
+pub struct MissingTranslations {
+    pub parent_id: i32,
+    pub locales: Vec<String>,
+}
@@ -0,0 +1,133 @@
+use aes_gcm::Aes256Gcm;
+use aes_gcm::aead::{Aead,NewAead};
+use aes_gcm::aead::generic_array::GenericArray;
+
+use hmac::{Hmac,Mac,NewMac};
+use lettre::Address;
+use sha2::Sha256;
+
+use rand::RngCore;
+
+use crate::config;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// Rider addresses are only kept sealed, i.e. encrypted with a key derived from
+// `RIDER_HASH_SECRET`. A sealed address is the hex encoded random nonce
+// followed by the ciphertext.
+
+const NONCE_LENGTH: usize = 12;
+
+fn cipher() -> Option<Aes256Gcm> {
+    let secret = config::rider_hash_secret()?;
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).ok()?;
+
+    // Addresses contain an `@`, so the key cannot collide with a rider hash.
+    mac.update(b"email address encryption key");
+
+    Some(Aes256Gcm::new(GenericArray::clone_from_slice(&mac.finalize().into_bytes())))
+}
+
+/// Identifies the rider across tokens by a keyed hash of the address, so that
+/// the address itself does not have to be compared. Tags after a `+` are
+/// dropped, they lead to the same mailbox.
+pub fn hash(email: &str) -> Option<String> {
+    let secret = config::rider_hash_secret()?;
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).ok()?;
+
+    mac.update(normalize(email).as_bytes());
+
+    Some(hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Whether mails can be sent to the address, checked before an address is
+/// accepted for a booking or the waitlist.
+pub fn is_valid(email: &str) -> bool {
+    email.parse::<Address>().is_ok()
+}
+
+fn normalize(email: &str) -> String {
+    let email = email.trim().to_lowercase();
+
+    match email.rsplitn(2, '@').collect::<Vec<_>>().as_slice() {
+        [domain, local] => format!("{}@{}", local.split('+').next().unwrap_or(local), domain),
+        _ => email,
+    }
+}
+
+/// Encrypts the address for storage, `None` if no secret is configured.
+pub fn seal(email: &str) -> Option<String> {
+    let mut nonce = [0u8; NONCE_LENGTH];
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let ciphertext = cipher()?
+        .encrypt(GenericArray::from_slice(&nonce), email.trim().as_bytes())
+        .ok()?;
+
+    Some(hex::encode([&nonce[..], &ciphertext].concat()))
+}
+
+/// Decrypts a sealed address, `None` if it was sealed with another secret.
+pub fn open(sealed: &str) -> Option<String> {
+    let sealed = hex::decode(sealed).ok()?;
+
+    if sealed.len() < NONCE_LENGTH {
+        return None;
+    }
+
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LENGTH);
+    let email = cipher()?
+        .decrypt(GenericArray::from_slice(nonce), ciphertext)
+        .ok()?;
+
+    String::from_utf8(email).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use mocktopus::mocking::Mockable;
+    use mocktopus::mocking::MockResult;
+
+    use crate::config;
+
+    #[test]
+    fn test_hash() {
+        config::rider_hash_secret.mock_safe(|| MockResult::Return(Some("test secret".to_string())));
+
+        let hash = super::hash("someone@somewhere.near").unwrap();
+
+        assert_eq!(hash.len(), 64);
+        assert_eq!(super::hash(" Someone@Somewhere.near "), Some(hash.clone()));
+        assert_eq!(super::hash("someone+bikes@somewhere.near"), Some(hash.clone()));
+        assert_ne!(super::hash("someone.else@somewhere.near"), Some(hash));
+
+        config::rider_hash_secret.mock_safe(|| MockResult::Return(None));
+        assert_eq!(super::hash("someone@somewhere.near"), None);
+    }
+
+    #[test]
+    fn test_is_valid() {
+        assert!(super::is_valid("someone@somewhere.near"));
+        assert!(super::is_valid("someone+bikes@somewhere.near"));
+        assert!(!super::is_valid("nobody"));
+        assert!(!super::is_valid("someone@"));
+        assert!(!super::is_valid(" someone@somewhere.near"));
+    }
+
+    #[test]
+    fn test_seal_and_open() {
+        config::rider_hash_secret.mock_safe(|| MockResult::Return(Some("test secret".to_string())));
+
+        let sealed = super::seal(" someone@somewhere.near").unwrap();
+        assert!(!sealed.contains("someone"));
+        assert_ne!(super::seal("someone@somewhere.near").unwrap(), sealed);
+        assert_eq!(super::open(&sealed), Some("someone@somewhere.near".to_string()));
+        assert_eq!(super::open("00"), None);
+
+        config::rider_hash_secret.mock_safe(|| MockResult::Return(Some("other secret".to_string())));
+        assert_eq!(super::open(&sealed), None);
+
+        config::rider_hash_secret.mock_safe(|| MockResult::Return(None));
+        assert_eq!(super::seal("someone@somewhere.near"), None);
+    }
+}
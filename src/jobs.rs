@@ -0,0 +1,85 @@
+use std::thread;
+use std::time::Duration;
+
+use chrono::Utc;
+
+use log::{error,info};
+
+use rocket::fairing::AdHoc;
+
+use crate::config;
+use crate::database;
+use crate::database::DbConn;
+use crate::database::daos::{challenge,hold,maintenance,token};
+use crate::mailer;
+use crate::routes::{approval,verification,waitlist};
+
+// Housekeeping that has to happen independently of incoming requests. The
+// jobs run on a dedicated thread which checks out a connection of the pool
+// for every run and returns it afterwards.
+
+pub fn run_jobs(db: &DbConn) {
+    match token::delete_stale_tokens(db) {
+        Ok(count) => info!("Deleted {} stale tokens", count),
+        Err(err) => error!("Deleting stale tokens failed: {}", err),
+    }
+
+    match hold::delete_expired_holds(db, Utc::now().naive_utc()) {
+        Ok(count) => info!("Deleted {} expired holds", count),
+        Err(err) => error!("Deleting expired holds failed: {}", err),
+    }
+
+    match challenge::delete_expired_proof_of_work_nonces(db, Utc::now().naive_utc()) {
+        Ok(count) => info!("Deleted {} expired proof of work nonces", count),
+        Err(err) => error!("Deleting expired proof of work nonces failed: {}", err),
+    }
+
+    verification::lapse_unverified_bookings(db);
+
+    approval::expire_approvals(db);
+
+    waitlist::notify_waitlist(db);
+
+    if mailer::is_mail_config_available() {
+        match notify_due_services(db) {
+            Ok(count) => info!("Notified about {} due services", count),
+            Err(err) => error!("Notifying about due services failed: {}", err),
+        }
+    }
+}
+
+/// Mails the services which became due since the last notification, each
+/// service is reported once per interval.
+fn notify_due_services(db: &DbConn) -> Result<usize, String> {
+    let now = Utc::now().naive_utc();
+    let services = maintenance::get_due_services(db, now)
+        .map_err(|err| err.to_string())?
+        .into_iter()
+        .filter(|due| due.notified_at.map_or(true, |notified_at| notified_at < due.last_service_at))
+        .collect::<Vec<_>>();
+
+    if services.is_empty() {
+        return Ok(0);
+    }
+
+    mailer::send_maintenance_mail(&services).map_err(|err| err.to_string())?;
+
+    let ids = services.iter().map(|due| due.bike_service_interval_id).collect::<Vec<_>>();
+    maintenance::mark_service_intervals_notified(db, &ids, now).map_err(|err| err.to_string())
+}
+
+pub fn fairing() -> AdHoc {
+    AdHoc::on_launch("Start periodic jobs", |rocket| {
+        let pool = database::pool(rocket).expect("database pool for periodic jobs");
+        let interval = Duration::from_secs(config::job_interval_seconds());
+
+        thread::spawn(move || loop {
+            match pool.get() {
+                Ok(connection) => run_jobs(&DbConn(connection)),
+                Err(err) => error!("Getting a database connection for the periodic jobs failed: {}", err),
+            }
+
+            thread::sleep(interval);
+        });
+    })
+}
@@ -1,7 +1,8 @@
 #![feature(proc_macro_hygiene, decl_macro)]
 
 #[macro_use] extern crate diesel;
-#[macro_use] extern crate lazy_static;
+
+pub mod config;
 
 pub mod routes;
 
@@ -11,5 +12,17 @@ pub mod database;
 
 pub mod mailer;
 
+pub mod jobs;
+
+pub mod locale;
+
+pub mod proof_of_work;
+
+pub mod media;
+
+pub mod timezone;
+
+pub mod email_address;
+
 #[cfg(test)]
 pub mod tests;
\ No newline at end of file
@@ -0,0 +1,314 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rocket::Outcome;
+use rocket::http::Status;
+use rocket::request::{self,FromRequest,Request};
+
+use crate::config;
+
+/// A BCP 47 language tag reduced to the subtags relevant for picking a
+/// translation. Extensions and private use subtags are accepted but dropped.
+#[derive(Debug,Clone,PartialEq)]
+pub struct LanguageTag {
+    pub language: String,
+    pub script: Option<String>,
+    pub region: Option<String>,
+    pub variants: Vec<String>,
+}
+
+fn is_alpha(s: &str, min: usize, max: usize) -> bool {
+    s.len() >= min && s.len() <= max && s.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+fn is_alphanumeric(s: &str, min: usize, max: usize) -> bool {
+    s.len() >= min && s.len() <= max && s.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+fn is_digit(s: &str, len: usize) -> bool {
+    s.len() == len && s.chars().all(|c| c.is_ascii_digit())
+}
+
+fn is_variant(s: &str) -> bool {
+    is_alphanumeric(s, 5, 8)
+        || (s.len() == 4 && s.starts_with(|c: char| c.is_ascii_digit()) && is_alphanumeric(s, 4, 4))
+}
+
+impl FromStr for LanguageTag {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<LanguageTag, String> {
+        let invalid = || format!("'{}' is not a valid language tag.", s);
+        let mut subtags = s.trim().split(|c| c == '-' || c == '_').peekable();
+
+        let language = match subtags.next() {
+            Some(language) if is_alpha(language, 2, 3) || is_alpha(language, 5, 8) => language.to_lowercase(),
+            _ => return Err(invalid()),
+        };
+
+        let script = match subtags.peek() {
+            Some(script) if is_alpha(script, 4, 4) => {
+                let script = script.to_lowercase();
+                subtags.next();
+                Some(script[..1].to_uppercase() + &script[1..])
+            },
+            _ => None,
+        };
+
+        let region = match subtags.peek() {
+            Some(region) if is_alpha(region, 2, 2) || is_digit(region, 3) => {
+                let region = region.to_uppercase();
+                subtags.next();
+                Some(region)
+            },
+            _ => None,
+        };
+
+        let mut variants = vec![];
+
+        while let Some(subtag) = subtags.next() {
+            if is_variant(subtag) {
+                variants.push(subtag.to_lowercase());
+            } else if is_alphanumeric(subtag, 1, 1) {
+                // Extensions and private use subtags run until the end of the tag.
+                if subtags.peek().is_none() || !subtags.all(|s| is_alphanumeric(s, 1, 8)) {
+                    return Err(invalid());
+                }
+                break;
+            } else {
+                return Err(invalid());
+            }
+        }
+
+        Ok(LanguageTag { language, script, region, variants })
+    }
+}
+
+impl fmt::Display for LanguageTag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.language)?;
+
+        if let Some(ref script) = self.script {
+            write!(f, "-{}", script)?;
+        }
+
+        if let Some(ref region) = self.region {
+            write!(f, "-{}", region)?;
+        }
+
+        for variant in &self.variants {
+            write!(f, "-{}", variant)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl LanguageTag {
+    pub fn matches(&self, other: &LanguageTag) -> bool {
+        self == other
+    }
+
+    pub fn matches_language(&self, other: &LanguageTag) -> bool {
+        self.language == other.language
+    }
+}
+
+/// Canonical form of a locale passed by an editor. The `*_translatables`
+/// tables only hold locales of the form `xx-XX`.
+pub fn parse_stored_locale(locale: &str) -> Result<String, String> {
+    let tag = locale.parse::<LanguageTag>()?;
+    let stored_form = tag.language.len() == 2
+        && tag.script.is_none()
+        && tag.region.as_ref().map_or(false, |region| region.len() == 2)
+        && tag.variants.is_empty();
+
+    if !stored_form {
+        return Err(String::from("Only locales of the form xx-XX are supported."));
+    }
+
+    Ok(tag.to_string())
+}
+
+/// The locales a client asked for, most preferred first. Negotiation always
+/// continues with the configured fallback chain once these are exhausted.
+#[derive(Debug,Clone,PartialEq)]
+pub struct LocalePreferences {
+    pub tags: Vec<LanguageTag>,
+}
+
+impl LocalePreferences {
+    pub fn new(tags: Vec<LanguageTag>) -> LocalePreferences {
+        LocalePreferences { tags }
+    }
+
+    /// Parses a comma separated list of tags, e.g. the `?locale=` parameter.
+    pub fn parse(list: &str) -> Result<LocalePreferences, String> {
+        let tags = list.split(',')
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| s.parse::<LanguageTag>())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(LocalePreferences::new(tags))
+    }
+
+    /// Parses an `Accept-Language` header. Malformed entries, wildcards and
+    /// entries with a weight of zero are ignored.
+    pub fn from_accept_language(header: &str) -> LocalePreferences {
+        let mut weighted = header.split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.split(';');
+                let tag = parts.next()?.trim().parse::<LanguageTag>().ok()?;
+                let weight = parts
+                    .filter_map(|p| p.trim().strip_prefix("q="))
+                    .filter_map(|q| q.trim().parse::<f32>().ok())
+                    .next()
+                    .unwrap_or(1.0);
+
+                if weight > 0.0 { Some((tag, weight)) } else { None }
+            })
+            .collect::<Vec<_>>();
+
+        weighted.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        LocalePreferences::new(weighted.into_iter().map(|(tag, _)| tag).collect())
+    }
+
+    /// The requested tags followed by the configured fallback chain.
+    pub fn candidates(&self) -> Vec<LanguageTag> {
+        let mut candidates = self.tags.clone();
+
+        for tag in config::locale_fallback_chain().iter().filter_map(|s| s.parse::<LanguageTag>().ok()) {
+            if !candidates.contains(&tag) {
+                candidates.push(tag);
+            }
+        }
+
+        candidates
+    }
+
+    /// Index of the best match among the available locales. For every
+    /// candidate an exact match wins over a match of the language only.
+    pub fn best_match<S: AsRef<str>>(&self, available: &[S]) -> Option<usize> {
+        let available = available.iter()
+            .map(|locale| locale.as_ref().parse::<LanguageTag>().ok())
+            .collect::<Vec<_>>();
+
+        for candidate in self.candidates() {
+            let exact = available.iter()
+                .position(|tag| tag.as_ref().map_or(false, |tag| tag.matches(&candidate)));
+            let language = available.iter()
+                .position(|tag| tag.as_ref().map_or(false, |tag| tag.matches_language(&candidate)));
+
+            if exact.is_some() {
+                return exact;
+            }
+
+            if language.is_some() {
+                return language;
+            }
+        }
+
+        None
+    }
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for LocalePreferences {
+    type Error = String;
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<LocalePreferences, String> {
+        if let Some(locale) = request.get_query_value::<String>("locale") {
+            return match locale.map_err(|_| String::from("No valid locale passed.")).and_then(|l| LocalePreferences::parse(&l)) {
+                Ok(preferences) => Outcome::Success(preferences),
+                Err(err) => Outcome::Failure((Status::BadRequest, err)),
+            };
+        }
+
+        let preferences = request.headers()
+            .get_one("Accept-Language")
+            .map(LocalePreferences::from_accept_language)
+            .unwrap_or(LocalePreferences::new(vec![]));
+
+        Outcome::Success(preferences)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+
+    use super::{LanguageTag,LocalePreferences};
+
+    fn setup() {
+        env::set_var("LOCALE_FALLBACK_CHAIN", "de-DE,en-GB");
+    }
+
+    fn tag(s: &str) -> LanguageTag {
+        s.parse::<LanguageTag>().unwrap()
+    }
+
+    #[test]
+    fn test_parse_language_tag() {
+        assert_eq!(tag("de-DE").to_string(), "de-DE");
+        assert_eq!(tag("DE_at").to_string(), "de-AT");
+        assert_eq!(tag("zh-hant-tw").to_string(), "zh-Hant-TW");
+        assert_eq!(tag("es-419").to_string(), "es-419");
+        assert_eq!(tag("de-CH-1901").to_string(), "de-CH-1901");
+        assert_eq!(tag("en-US-u-ca-gregory").to_string(), "en-US");
+        assert_eq!(tag("en").to_string(), "en");
+    }
+
+    #[test]
+    fn test_parse_invalid_language_tag() {
+        assert!("xxde-DEyy".parse::<LanguageTag>().is_err());
+        assert!("de-DE-yy".parse::<LanguageTag>().is_err());
+        assert!("".parse::<LanguageTag>().is_err());
+        assert!("d".parse::<LanguageTag>().is_err());
+        assert!("de-DE-u".parse::<LanguageTag>().is_err());
+        assert!("de DE".parse::<LanguageTag>().is_err());
+    }
+
+    #[test]
+    fn test_parse_stored_locale() {
+        assert_eq!(super::parse_stored_locale("en_gb"), Ok("en-GB".to_string()));
+        assert!(super::parse_stored_locale("zh-Hant-TW").is_err());
+        assert!(super::parse_stored_locale("xx-").is_err());
+        assert!(super::parse_stored_locale("de").is_err());
+        assert!(super::parse_stored_locale("es-419").is_err());
+    }
+
+    #[test]
+    fn test_from_accept_language() {
+        let preferences = LocalePreferences::from_accept_language("en-GB;q=0.5, de-AT, *;q=0.1, fr;q=0, xx-invalid-tag-here;q=0.9");
+
+        assert_eq!(preferences.tags, vec![tag("de-AT"), tag("en-GB")]);
+    }
+
+    #[test]
+    fn test_best_match_prefers_exact_match() {
+        setup();
+
+        let preferences = LocalePreferences::new(vec![tag("de-AT")]);
+
+        assert_eq!(preferences.best_match(&["en-GB", "de-DE", "de-AT"]), Some(2));
+    }
+
+    #[test]
+    fn test_best_match_falls_back_to_language() {
+        setup();
+
+        let preferences = LocalePreferences::new(vec![tag("de-AT")]);
+
+        assert_eq!(preferences.best_match(&["en-GB", "de-DE"]), Some(1));
+    }
+
+    #[test]
+    fn test_best_match_falls_back_to_chain() {
+        setup();
+
+        let preferences = LocalePreferences::new(vec![tag("fr-FR")]);
+
+        assert_eq!(preferences.best_match(&["it-IT", "en-GB"]), Some(1));
+        assert_eq!(preferences.best_match(&["it-IT"]), None);
+    }
+}
@@ -10,8 +10,10 @@ use lettre::transport::smtp::response::Response;
 use lettre::transport::smtp::authentication::Credentials;
 use lettre::{Message, SmtpTransport, Transport};
 
-use crate::database::models::Booking;
+use crate::config;
+use crate::database::models::{ApprovalStatus,BikeIncident,Booking,EmailVerification,RecurringBooking,Rent,RentApprovalWithRents,RentHold,RentSeriesBooking,ServiceDue,WaitlistEntry};
 use crate::mailer::errors::MailerError;
+use crate::timezone;
 
 fn send_mail_from_backend(to: Vec<&str>, subject: String, body: String) -> Result<Response, Error> {
     let mut message_builder = Message::builder();
@@ -37,6 +39,7 @@ fn send_mail_from_backend(to: Vec<&str>, subject: String, body: String) -> Resul
     mailer.send(&email)
 }
 
+#[cfg_attr(test, mockable)]
 pub fn is_mail_config_available() -> bool {
     env::var("SMTP_USER").is_ok()
         && env::var("SMTP_PASSWORD").is_ok()
@@ -60,19 +63,144 @@ pub fn send_startup_mail() -> Result<Response, MailerError> {
     }
 }
 
+/// Pending bookings are marked in the subject, the admins have to decide
+/// on them. The rider only hears about the booking once the address is
+/// confirmed, see send_confirmation_mail.
+#[cfg_attr(test, mockable)]
+pub fn send_rent_mail(booking: &Booking, approval_status: ApprovalStatus) -> Result<Response, MailerError> {
+    let start_date = timezone::to_local(booking.start_timestamp).format("%Y-%m-%d");
+    let end_date = timezone::to_local(booking.end_timestamp).format("%Y-%m-%d");
+    let bike_count = booking.bike_ids().len();
+    let mut subject = if bike_count > 1 {
+        format!("New rent of {} bikes from {} to {}", bike_count, start_date, end_date)
+    } else {
+        format!("New rent from {} to {}", start_date, end_date)
+    };
+
+    if approval_status == ApprovalStatus::Pending {
+        subject = format!("{} awaiting approval", subject);
+    }
+    let email_to = env::var("EMAIL_TO").unwrap();
+    let to = email_to.split(",").collect::<Vec<_>>();
+
+    let body = format!("{}", booking.short_token);
+
+    let result = send_mail_from_backend(to, subject, body);
+
+    match result {
+        Ok(response) => Ok(response),
+        Err(err) => Err(From::from(err))
+    }
+}
+
+/// Lists the booked occurrences of a series, skipped ones are only counted.
 #[cfg_attr(test, mockable)]
-pub fn send_rent_mail(booking: &Booking) -> Result<Response, MailerError> {
-    let start_date = booking.start_timestamp.format("%Y-%m-%d");
-    let end_date = booking.end_timestamp.format("%Y-%m-%d");
-    let subject = format!("New rent from {} to {}", start_date, end_date);
+pub fn send_series_mail(booking: &RecurringBooking, result: &RentSeriesBooking) -> Result<Response, MailerError> {
     let email_to = env::var("EMAIL_TO").unwrap();
-    let mut to = email_to.split(",").collect::<Vec<_>>();
+    let to = email_to.split(',').collect::<Vec<_>>();
+
+    let mut subject = format!("New recurring rent with {} occurrence(s) from {}",
+        result.series.rents.len(),
+        timezone::to_local(booking.start_timestamp).format("%Y-%m-%d"));
 
-    if booking.email.is_some() {
-        to.push(booking.email.as_ref().unwrap());
+    if result.approval_status == ApprovalStatus::Pending {
+        subject = format!("{} awaiting approval", subject);
     }
+    let mut body = result.series.rents.iter()
+        .map(|rent| format!("{} - {}", timezone::to_local(rent.start_timestamp).format("%Y-%m-%d %H:%M %Z"), timezone::to_local(rent.end_timestamp).format("%Y-%m-%d %H:%M %Z")))
+        .collect::<Vec<_>>();
 
-    let body = format!("{}", booking.short_token);
+    if !result.conflicts.is_empty() {
+        body.push(format!("{} occurrence(s) could not be booked.", result.conflicts.len()));
+    }
+
+    body.push(booking.short_token.clone());
+
+    let result = send_mail_from_backend(to, subject, body.join("\n"));
+
+    match result {
+        Ok(response) => Ok(response),
+        Err(err) => Err(From::from(err))
+    }
+}
+
+/// Asks the rider to confirm the address left with a booking or a waitlist
+/// entry. The mail carries no details, it may reach someone who never asked.
+#[cfg_attr(test, mockable)]
+pub fn send_verification_mail(email: &str, verification: &EmailVerification) -> Result<Response, MailerError> {
+    let subject = "Please confirm your email address".to_string();
+    let body = format!("Someone left this address with a request at our cargo bike share. To confirm it, open\n\n{}/email-verifications/{}\n\nuntil {}. Otherwise the request lapses and nothing else happens.",
+        config::verification_base_url().trim_end_matches('/'),
+        verification.uuid,
+        timezone::to_local(verification.expires_at).format("%Y-%m-%d %H:%M %Z"));
+
+    let result = send_mail_from_backend(vec![email], subject, body);
+
+    match result {
+        Ok(response) => Ok(response),
+        Err(err) => Err(From::from(err))
+    }
+}
+
+/// Sends the booking details once the rider confirmed the address.
+#[cfg_attr(test, mockable)]
+pub fn send_confirmation_mail(email: &str, verification: &EmailVerification, rents: &[Rent]) -> Result<Response, MailerError> {
+    let subject = "Your rent is confirmed".to_string();
+    let mut body = rents.iter()
+        .filter(|rent| rent.revocation_timestamp.is_none())
+        .map(|rent| format!("Bike {}: {} - {}",
+            rent.bike_id,
+            timezone::to_local(rent.start_timestamp).format("%Y-%m-%d %H:%M %Z"),
+            timezone::to_local(rent.end_timestamp).format("%Y-%m-%d %H:%M %Z")))
+        .collect::<Vec<_>>();
+
+    body.extend(verification.short_token.clone());
+
+    let result = send_mail_from_backend(vec![email], subject, body.join("\n"));
+
+    match result {
+        Ok(response) => Ok(response),
+        Err(err) => Err(From::from(err))
+    }
+}
+
+/// Tells the rider whether the booking was approved, declined or expired.
+#[cfg_attr(test, mockable)]
+pub fn send_approval_mail(email: &str, approval: &RentApprovalWithRents) -> Result<Response, MailerError> {
+    let subject = format!("Your rent has been {}", approval.approval.status);
+    let body = approval.rents.iter()
+        .map(|rent| format!("Bike {}: {} - {}",
+            rent.bike_id,
+            timezone::to_local(rent.start_timestamp).format("%Y-%m-%d %H:%M %Z"),
+            timezone::to_local(rent.end_timestamp).format("%Y-%m-%d %H:%M %Z")))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let result = send_mail_from_backend(vec![email], subject, body);
+
+    match result {
+        Ok(response) => Ok(response),
+        Err(err) => Err(From::from(err))
+    }
+}
+
+/// Tells the operators which bikes are due for service.
+#[cfg_attr(test, mockable)]
+pub fn send_maintenance_mail(services: &[ServiceDue]) -> Result<Response, MailerError> {
+    let email_to = env::var("EMAIL_TO").unwrap();
+    let to = email_to.split(',').collect::<Vec<_>>();
+
+    let subject = format!("{} bike service(s) due", services.len());
+    let body = services.iter()
+        .map(|due| format!("Bike {}: {} (last service {}, {} days and {} rents ago{})",
+            due.bike_id,
+            due.title,
+            timezone::to_local(due.last_service_at).format("%Y-%m-%d"),
+            due.days_since,
+            due.rents_since,
+            if due.blocks_bookings { ", bookings blocked" } else { "" }))
+        .collect::<Vec<_>>()
+        .join("\n");
 
     let result = send_mail_from_backend(to, subject, body);
 
@@ -80,4 +208,46 @@ pub fn send_rent_mail(booking: &Booking) -> Result<Response, MailerError> {
         Ok(response) => Ok(response),
         Err(err) => Err(From::from(err))
     }
-}
\ No newline at end of file
+}
+
+#[cfg_attr(test, mockable)]
+pub fn send_incident_mail(incident: &BikeIncident) -> Result<Response, MailerError> {
+    let email_to = env::var("EMAIL_TO").unwrap();
+    let to = email_to.split(',').collect::<Vec<_>>();
+
+    let subject = format!("Incident '{}' reported for bike {}", incident.category, incident.bike_id);
+    let body = format!("Rent {}, reported by {}:\n\n{}",
+        incident.rent_id,
+        incident.reported_by.as_deref().unwrap_or("the renter"),
+        incident.description);
+
+    let result = send_mail_from_backend(to, subject, body);
+
+    match result {
+        Ok(response) => Ok(response),
+        Err(err) => Err(From::from(err))
+    }
+}
+
+/// Offers a freed slot to a waitlisted rider. The token of the waitlist entry
+/// is spent, so the rider needs a fresh one from a challenge or an invite
+/// code and has to send the hold id along with the booking before the hold
+/// expires.
+#[cfg_attr(test, mockable)]
+pub fn send_waitlist_mail(email: &str, entry: &WaitlistEntry, hold: &RentHold) -> Result<Response, MailerError> {
+    let subject = format!("Bike {} is available from {} to {}",
+        entry.bike_id,
+        timezone::to_local(entry.start_timestamp).format("%Y-%m-%d %H:%M %Z"),
+        timezone::to_local(entry.end_timestamp).format("%Y-%m-%d %H:%M %Z"));
+    let body = format!("The slot is held for you until {}. To book it, get a new token by solving a challenge or redeeming an invite code, the token of your waitlist entry cannot be used again. Then book the slot with the new token and the hold id at\n\n{}/rents\n\nHold: {}",
+        timezone::to_local(hold.expires_at).format("%Y-%m-%d %H:%M %Z"),
+        config::verification_base_url().trim_end_matches('/'),
+        hold.uuid);
+
+    let result = send_mail_from_backend(vec![email], subject, body);
+
+    match result {
+        Ok(response) => Ok(response),
+        Err(err) => Err(From::from(err))
+    }
+}
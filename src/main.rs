@@ -10,14 +10,16 @@ use rocket::fairing::AdHoc;
 
 use cargobike_share_backend::database::{DbConn};
 use cargobike_share_backend::routes;
-use cargobike_share_backend::routes::{bike,rent,challenge,supporter};
+use cargobike_share_backend::routes::{accessory,approval,availability,bike,bike_attribute,rent,challenge,feedback,incident,invite,maintenance,supporter,verification,waitlist,media};
 use cargobike_share_backend::mailer;
+use cargobike_share_backend::jobs;
 
 fn main() {
     dotenv().ok();
 
     rocket::ignite()
         .attach(DbConn::fairing())
+        .attach(jobs::fairing())
         .attach(AdHoc::on_launch("Send launch mail", |_| {
             if mailer::is_mail_config_available() == false {
                 panic!("Launch failed due to missing mail configuration");
@@ -30,12 +32,102 @@ fn main() {
         }))
         .mount("/", routes![routes::index,
             bike::get_bikes,
+            bike::get_bike_media,
+            bike::upload_bike_media,
+            bike::update_bike_media,
+            bike::delete_bike_media,
+            bike::get_bike_media_translations,
+            bike::put_bike_media_translation,
+            bike::get_bike_status_history,
+            bike::put_bike_status,
+            bike::get_bike_buffers,
+            bike::put_bike_buffers,
+            bike::put_bike_granularity,
+            bike::put_bike_approval,
+            bike::get_bike_slots,
+            maintenance::get_due_services,
+            maintenance::get_service_intervals,
+            maintenance::create_service_interval,
+            maintenance::update_service_interval,
+            maintenance::delete_service_interval,
+            maintenance::get_work_orders,
+            maintenance::create_work_order,
+            maintenance::delete_work_order,
+            maintenance::get_maintenance_history,
+            incident::report_incident,
+            incident::report_occurrence_incident,
+            incident::upload_incident_photo,
+            incident::get_incidents,
+            incident::create_incident,
+            incident::upload_admin_incident_photo,
+            feedback::submit_feedback,
+            feedback::submit_occurrence_feedback,
+            feedback::get_feedback_summaries,
+            feedback::get_bike_feedback,
+            accessory::get_accessories,
+            accessory::get_accessory_inventory,
+            accessory::create_accessory,
+            accessory::update_accessory,
+            accessory::delete_accessory,
+            accessory::get_accessory_bikes,
+            accessory::put_accessory_bikes,
+            accessory::get_accessory_translations,
+            accessory::put_accessory_translation,
+            bike_attribute::get_bike_attributes,
+            bike_attribute::create_bike_attribute,
+            bike_attribute::update_bike_attribute,
+            bike_attribute::delete_bike_attribute,
+            bike_attribute::put_bike_attribute_translation,
+            bike_attribute::get_bike_attribute_options,
+            bike_attribute::create_bike_attribute_option,
+            bike_attribute::delete_bike_attribute_option,
+            bike_attribute::put_bike_attribute_option_translation,
+            bike_attribute::put_bike_attribute_value,
+            bike_attribute::delete_bike_attribute_value,
+            availability::get_blackouts,
+            availability::create_blackout,
+            availability::delete_blackout,
             rent::get_rents,
             rent::book,
             rent::revoke_booking,
+            rent::create_hold,
+            rent::release_hold,
+            rent::book_series,
+            rent::get_series,
+            rent::revoke_occurrence,
+            verification::verify_email,
+            approval::get_pending_approvals,
+            approval::approve_booking,
+            approval::decline_booking,
+            approval::get_approval,
+            waitlist::create_waitlist_entry,
+            waitlist::delete_waitlist_entry,
+            waitlist::get_waitlist_entries,
+            challenge::get_challenge_types,
             challenge::get_random_challenge,
+            challenge::get_random_challenge_for_locale,
             challenge::test_challenge,
+            challenge::get_proof_of_work_challenge,
+            challenge::test_proof_of_work,
+            invite::create_invite_codes,
+            invite::get_invite_codes,
+            invite::get_redemptions,
+            invite::redeem_invite_code,
             supporter::get_supporters,
+            supporter::get_supporter_types,
+            supporter::create_supporter_type,
+            supporter::update_supporter_type,
+            supporter::delete_supporter_type,
+            supporter::get_all_supporters,
+            supporter::create_supporter,
+            supporter::update_supporter,
+            supporter::delete_supporter,
+            supporter::get_supporter_translations,
+            supporter::put_supporter_translation,
+            supporter::delete_supporter_translation,
+            supporter::upload_supporter_logo,
+            supporter::get_missing_supporter_translations,
+            media::get_upload,
         ])
         .launch();
 }
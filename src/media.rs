@@ -0,0 +1,287 @@
+pub mod errors;
+
+pub mod storage;
+
+use std::io::{Cursor,Read};
+
+use image::{DynamicImage,GenericImageView,ImageFormat,ImageOutputFormat};
+use image::imageops::FilterType;
+use image::io::Reader;
+
+use log::error;
+
+use rocket::Data;
+
+use sha2::{Digest,Sha256};
+
+use crate::config;
+use crate::media::errors::MediaError;
+use crate::media::storage::Storage;
+
+/// A scaled down copy generated for every uploaded image. `name` becomes
+/// part of the file name.
+#[derive(Debug)]
+pub struct ImageVariant {
+    pub name: &'static str,
+    pub max_width: u32,
+    pub max_height: u32,
+}
+
+#[derive(Debug)]
+pub struct StoredVariant {
+    pub url: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// An uploaded image after it has been stored, with its variants in the
+/// order they were requested.
+#[derive(Debug)]
+pub struct StoredImage {
+    pub original_url: String,
+    pub width: u32,
+    pub height: u32,
+    pub variants: Vec<StoredVariant>,
+    /// Keys of the files added by this upload.
+    pub created: Vec<String>,
+}
+
+/// Reads an upload into memory, rejecting bodies above the configured limit.
+pub fn read_upload(data: Data) -> Result<Vec<u8>, MediaError> {
+    let limit = config::upload_limit_bytes();
+    let mut buffer = vec![];
+
+    data.open().take(limit + 1).read_to_end(&mut buffer)?;
+
+    if buffer.len() as u64 > limit {
+        return Err(MediaError::TooLarge(format!("Uploads are limited to {} bytes.", limit)));
+    }
+
+    Ok(buffer)
+}
+
+fn extension(format: ImageFormat) -> Result<&'static str, MediaError> {
+    match format {
+        ImageFormat::Png => Ok("png"),
+        ImageFormat::Jpeg => Ok("jpg"),
+        ImageFormat::Gif => Ok("gif"),
+        _ => Err(MediaError::UnsupportedFormat(String::from("Only PNG, JPEG and GIF images are supported."))),
+    }
+}
+
+/// Scales the image down to fit into the bounding box of the variant.
+/// Smaller images are left as they are.
+fn scale(image: &DynamicImage, variant: &ImageVariant) -> DynamicImage {
+    if image.width() <= variant.max_width && image.height() <= variant.max_height {
+        return image.clone();
+    }
+
+    image.resize(variant.max_width, variant.max_height, FilterType::Lanczos3)
+}
+
+/// Writes the file unless an earlier upload of the same content already did.
+/// The keys written are added to `created`.
+fn store_new<S: Storage>(storage: &S, key: &str, data: &[u8], created: &mut Vec<String>) -> Result<(), MediaError> {
+    if !storage.exists(key) {
+        storage.store(key, data)?;
+        created.push(key.to_string());
+    }
+
+    Ok(())
+}
+
+/// Removes the files the upload added, for uploads which could not be
+/// recorded. Files shared with earlier uploads of the same image are kept.
+pub fn discard_image<S: Storage>(storage: &S, image: &StoredImage) {
+    discard(storage, &image.created);
+}
+
+fn discard<S: Storage>(storage: &S, keys: &[String]) {
+    for key in keys {
+        if let Err(err) = storage.remove(key) {
+            error!("Removing the file {} failed: {}", key, err);
+        }
+    }
+}
+
+/// Stores the original image and a PNG file per variant below `prefix`. File
+/// names are derived from the content, so the same upload always ends up at
+/// the same URL and the files never change.
+pub fn store_image<S: Storage>(storage: &S, prefix: &str, data: &[u8], variants: &[ImageVariant]) -> Result<StoredImage, MediaError> {
+    let reader = Reader::new(Cursor::new(data)).with_guessed_format()?;
+    let format = reader.format()
+        .ok_or_else(|| MediaError::UnsupportedFormat(String::from("The upload is not an image.")))?;
+    let extension = extension(format)?;
+
+    let (width, height) = reader.into_dimensions()?;
+    if u64::from(width) * u64::from(height) > config::upload_max_pixels() {
+        return Err(MediaError::TooLarge(format!("Images are limited to {} pixels.", config::upload_max_pixels())));
+    }
+
+    let image = image::load_from_memory_with_format(data, format)?;
+
+    let hash = hex::encode(Sha256::digest(data));
+    let original_key = format!("{}/{}.{}", prefix, hash, extension);
+    let mut created = vec![];
+    let mut stored = vec![];
+
+    let result = variants.iter().try_for_each(|variant| {
+        let scaled = scale(&image, variant);
+        let mut encoded = vec![];
+        scaled.write_to(&mut encoded, ImageOutputFormat::Png)?;
+
+        let key = format!("{}/{}-{}.png", prefix, hash, variant.name);
+        store_new(storage, &key, &encoded, &mut created)?;
+
+        stored.push(StoredVariant {
+            url: storage.url(&key),
+            width: scaled.width(),
+            height: scaled.height(),
+        });
+
+        Ok(())
+    }).and_then(|_| store_new(storage, &original_key, data, &mut created));
+
+    if let Err(err) = result {
+        discard(storage, &created);
+        return Err(err);
+    }
+
+    Ok(StoredImage {
+        original_url: storage.url(&original_key),
+        width: image.width(),
+        height: image.height(),
+        variants: stored,
+        created,
+    })
+}
+
+#[cfg(test)]
+pub mod test {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    use image::{DynamicImage,GenericImageView,ImageOutputFormat,RgbaImage};
+
+    use mocktopus::mocking::{Mockable,MockResult};
+
+    use crate::config;
+
+    use crate::media::ImageVariant;
+    use crate::media::errors::MediaError;
+    use crate::media::storage::Storage;
+
+    pub fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut data = vec![];
+
+        DynamicImage::ImageRgba8(RgbaImage::new(width, height))
+            .write_to(&mut data, ImageOutputFormat::Png)
+            .unwrap();
+
+        data
+    }
+
+    #[derive(Default)]
+    struct MemoryStorage {
+        files: RefCell<HashMap<String, Vec<u8>>>,
+    }
+
+    impl Storage for MemoryStorage {
+        fn store(&self, key: &str, data: &[u8]) -> Result<(), MediaError> {
+            self.files.borrow_mut().insert(key.to_string(), data.to_vec());
+            Ok(())
+        }
+
+        fn load(&self, key: &str) -> Result<Vec<u8>, MediaError> {
+            self.files.borrow().get(key).cloned().ok_or_else(|| MediaError::NotFound(key.to_string()))
+        }
+
+        fn exists(&self, key: &str) -> bool {
+            self.files.borrow().contains_key(key)
+        }
+
+        fn remove(&self, key: &str) -> Result<(), MediaError> {
+            self.files.borrow_mut().remove(key).map(|_| ()).ok_or_else(|| MediaError::NotFound(key.to_string()))
+        }
+
+        fn url(&self, key: &str) -> String {
+            format!("/uploads/{}", key)
+        }
+    }
+
+    const WEB: ImageVariant = ImageVariant { name: "web", max_width: 400, max_height: 200 };
+    const THUMBNAIL: ImageVariant = ImageVariant { name: "thumbnail", max_width: 100, max_height: 100 };
+
+    #[test]
+    fn test_store_image_resizes_variants() {
+        let storage = MemoryStorage::default();
+        let data = png(800, 200);
+
+        let stored = super::store_image(&storage, "bikes", &data, &[WEB, THUMBNAIL]).unwrap();
+
+        assert_eq!((stored.width, stored.height), (800, 200));
+        assert!(stored.original_url.starts_with("/uploads/bikes/") && stored.original_url.ends_with(".png"));
+        assert_eq!(storage.load(&stored.original_url["/uploads/".len()..]).unwrap(), data);
+
+        let web = &stored.variants[0];
+        assert_eq!((web.width, web.height), (400, 100));
+        assert!(web.url.ends_with("-web.png"));
+
+        let thumbnail = &stored.variants[1];
+        assert_eq!((thumbnail.width, thumbnail.height), (100, 25));
+        assert!(thumbnail.url.ends_with("-thumbnail.png"));
+
+        let decoded = image::load_from_memory(&storage.load(&thumbnail.url["/uploads/".len()..]).unwrap()).unwrap();
+        assert_eq!(decoded.dimensions(), (100, 25));
+    }
+
+    #[test]
+    fn test_discard_image_keeps_shared_files() {
+        let storage = MemoryStorage::default();
+        let data = png(120, 80);
+
+        let first = super::store_image(&storage, "bikes", &data, &[WEB]).unwrap();
+        assert_eq!(first.created.len(), 2);
+
+        let second = super::store_image(&storage, "bikes", &data, &[WEB, THUMBNAIL]).unwrap();
+        assert_eq!(second.created.len(), 1);
+
+        super::discard_image(&storage, &second);
+
+        assert_eq!(storage.files.borrow().len(), 2);
+        assert!(storage.exists(&first.original_url["/uploads/".len()..]));
+    }
+
+    #[test]
+    fn test_store_image_keeps_small_images() {
+        let storage = MemoryStorage::default();
+
+        let stored = super::store_image(&storage, "logos", &png(120, 80), &[WEB]).unwrap();
+
+        assert_eq!((stored.variants[0].width, stored.variants[0].height), (120, 80));
+    }
+
+    #[test]
+    fn test_store_image_rejects_huge_images() {
+        let storage = MemoryStorage::default();
+
+        config::upload_max_pixels.mock_safe(|| MockResult::Return(100));
+
+        match super::store_image(&storage, "logos", &png(20, 20), &[WEB]) {
+            Err(MediaError::TooLarge(_)) => (),
+            other => panic!("Unexpected result {:?}", other),
+        }
+        assert!(storage.files.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_store_image_rejects_other_data() {
+        let storage = MemoryStorage::default();
+
+        match super::store_image(&storage, "logos", b"<svg></svg>", &[WEB]) {
+            Err(MediaError::UnsupportedFormat(_)) => (),
+            other => panic!("Unexpected result {:?}", other),
+        }
+        assert!(storage.files.borrow().is_empty());
+    }
+}
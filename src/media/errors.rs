@@ -0,0 +1,46 @@
+use std::fmt;
+use std::io;
+
+use rocket::Responder;
+
+#[derive(Responder)]
+#[derive(Debug)]
+pub enum MediaError {
+    #[response(status = 404)]
+    NotFound(String),
+    #[response(status = 413)]
+    TooLarge(String),
+    #[response(status = 415)]
+    UnsupportedFormat(String),
+    #[response(status = 500)]
+    Storage(String),
+}
+
+impl fmt::Display for MediaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MediaError::NotFound(ref err) => write!(f, "{}", err),
+            MediaError::TooLarge(ref err) => write!(f, "{}", err),
+            MediaError::UnsupportedFormat(ref err) => write!(f, "{}", err),
+            MediaError::Storage(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<io::Error> for MediaError {
+    fn from(err: io::Error) -> MediaError {
+        match err.kind() {
+            io::ErrorKind::NotFound => MediaError::NotFound(err.to_string()),
+            _ => MediaError::Storage(err.to_string()),
+        }
+    }
+}
+
+impl From<image::ImageError> for MediaError {
+    fn from(err: image::ImageError) -> MediaError {
+        match err {
+            image::ImageError::IoError(err) => MediaError::from(err),
+            _ => MediaError::UnsupportedFormat(err.to_string()),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::fs;
+use std::path::{Component,Path,PathBuf};
+
+use crate::config;
+use crate::media::errors::MediaError;
+
+/// Where uploaded files end up. Keys are relative, slash separated paths such
+/// as `logos/<hash>.png`.
+pub trait Storage {
+    fn store(&self, key: &str, data: &[u8]) -> Result<(), MediaError>;
+    fn load(&self, key: &str) -> Result<Vec<u8>, MediaError>;
+    fn exists(&self, key: &str) -> bool;
+    fn remove(&self, key: &str) -> Result<(), MediaError>;
+
+    /// Public URL the file with the given key is served from.
+    fn url(&self, key: &str) -> String;
+}
+
+pub struct FileStorage {
+    root: PathBuf,
+    base_url: String,
+}
+
+impl FileStorage {
+    pub fn new<P: Into<PathBuf>>(root: P, base_url: &str) -> FileStorage {
+        FileStorage {
+            root: root.into(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub fn from_config() -> FileStorage {
+        FileStorage::new(config::upload_directory(), &config::upload_base_url())
+    }
+
+    /// Resolves the key below the storage root. Keys that could escape the
+    /// root are rejected.
+    fn path(&self, key: &str) -> Result<PathBuf, MediaError> {
+        let key = Path::new(key);
+
+        if key.as_os_str().is_empty() || !key.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(MediaError::NotFound(format!("Invalid key '{}'.", key.display())));
+        }
+
+        Ok(self.root.join(key))
+    }
+}
+
+impl Storage for FileStorage {
+    fn store(&self, key: &str, data: &[u8]) -> Result<(), MediaError> {
+        let path = self.path(key)?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Write to a temporary file first so a file is never served half written.
+        let temporary = path.with_extension("part");
+        fs::write(&temporary, data)?;
+        fs::rename(&temporary, &path)?;
+
+        Ok(())
+    }
+
+    fn load(&self, key: &str) -> Result<Vec<u8>, MediaError> {
+        Ok(fs::read(self.path(key)?)?)
+    }
+
+    fn exists(&self, key: &str) -> bool {
+        self.path(key).map_or(false, |path| path.is_file())
+    }
+
+    fn remove(&self, key: &str) -> Result<(), MediaError> {
+        Ok(fs::remove_file(self.path(key)?)?)
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("{}/{}", self.base_url, key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+
+    use super::{FileStorage,Storage};
+
+    #[test]
+    fn test_store_and_load() {
+        let storage = FileStorage::new(env::temp_dir().join("cbs-storage-test"), "/uploads/");
+
+        storage.store("logos/test.txt", b"logo").unwrap();
+
+        assert_eq!(storage.load("logos/test.txt").unwrap(), b"logo".to_vec());
+        assert_eq!(storage.url("logos/test.txt"), "/uploads/logos/test.txt");
+        assert!(storage.exists("logos/test.txt"));
+
+        storage.remove("logos/test.txt").unwrap();
+
+        assert!(!storage.exists("logos/test.txt"));
+    }
+
+    #[test]
+    fn test_rejects_keys_outside_of_root() {
+        let storage = FileStorage::new(env::temp_dir().join("cbs-storage-test"), "/uploads");
+
+        assert!(storage.load("../etc/passwd").is_err());
+        assert!(storage.load("/etc/passwd").is_err());
+        assert!(storage.store("", b"").is_err());
+        assert!(storage.load("logos/missing.png").is_err());
+    }
+}
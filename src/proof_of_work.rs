@@ -0,0 +1,159 @@
+use chrono::{Duration,NaiveDateTime,Utc};
+
+use hmac::{Hmac,Mac,NewMac};
+use sha2::{Digest,Sha256};
+
+use rand::RngCore;
+
+use crate::config;
+use crate::database::models::{ProofOfWorkChallenge,ProofOfWorkResponse};
+use crate::routes::errors::ChallengeError;
+
+type HmacSha256 = Hmac<Sha256>;
+
+// Hashcash-style challenges: the server hands out a random nonce together with
+// a difficulty and an expiry and signs all three with an HMAC. A solution is
+// any string for which SHA-256("<nonce>:<solution>") starts with `difficulty`
+// zero bits. As the signature covers the challenge, only the nonces of solved
+// challenges are stored, until they expire, so a solution cannot be replayed.
+
+fn mac(secret: &str, nonce: &str, difficulty: u8, expires_at: &NaiveDateTime) -> Result<HmacSha256, ChallengeError> {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+        .map_err(|_| ChallengeError::Validation(String::from("Invalid proof of work secret.")))?;
+
+    mac.update(format!("{}:{}:{}", nonce, difficulty, expires_at.timestamp()).as_bytes());
+
+    Ok(mac)
+}
+
+fn signature(secret: &str, nonce: &str, difficulty: u8, expires_at: &NaiveDateTime) -> Result<String, ChallengeError> {
+    let mac = mac(secret, nonce, difficulty, expires_at)?;
+
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+fn secret() -> Result<String, ChallengeError> {
+    config::proof_of_work_secret()
+        .ok_or(ChallengeError::Disabled(String::from("Proof of work challenges are not configured.")))
+}
+
+pub fn leading_zero_bits(hash: &[u8]) -> u32 {
+    let mut bits = 0;
+
+    for byte in hash {
+        if *byte == 0 {
+            bits += 8;
+        } else {
+            bits += byte.leading_zeros();
+            break;
+        }
+    }
+
+    bits
+}
+
+pub fn is_solution(nonce: &str, solution: &str, difficulty: u8) -> bool {
+    let hash = Sha256::digest(format!("{}:{}", nonce, solution).as_bytes());
+
+    leading_zero_bits(&hash) >= difficulty as u32
+}
+
+pub fn issue_challenge() -> Result<ProofOfWorkChallenge, ChallengeError> {
+    let secret = secret()?;
+
+    let mut nonce = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let nonce = hex::encode(nonce);
+
+    let difficulty = config::proof_of_work_difficulty();
+    let expires_at = Utc::now().naive_utc() + Duration::seconds(config::proof_of_work_ttl_seconds());
+    let expires_at = NaiveDateTime::from_timestamp(expires_at.timestamp(), 0);
+
+    Ok(ProofOfWorkChallenge {
+        signature: signature(&secret, &nonce, difficulty, &expires_at)?,
+        nonce,
+        difficulty,
+        expires_at,
+    })
+}
+
+pub fn verify_solution(response: &ProofOfWorkResponse) -> Result<(), ChallengeError> {
+    let secret = secret()?;
+    let challenge = &response.challenge;
+
+    let signature = hex::decode(&challenge.signature).unwrap_or_default();
+
+    if mac(&secret, &challenge.nonce, challenge.difficulty, &challenge.expires_at)?.verify(&signature).is_err() {
+        return Err(ChallengeError::Validation(String::from("The challenge was not issued by this server.")));
+    }
+
+    if challenge.expires_at < Utc::now().naive_utc() {
+        return Err(ChallengeError::Validation(String::from("The challenge has expired.")));
+    }
+
+    if !is_solution(&challenge.nonce, &response.solution, challenge.difficulty) {
+        return Err(ChallengeError::Validation(String::from("The solution is not valid.")));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+
+    use chrono::{Duration,Utc};
+
+    use crate::database::models::ProofOfWorkResponse;
+
+    fn setup() {
+        env::set_var("PROOF_OF_WORK_SECRET", "test secret");
+        env::set_var("PROOF_OF_WORK_DIFFICULTY", "8");
+    }
+
+    fn solve(nonce: &str, difficulty: u8) -> String {
+        (0u64..).map(|i| i.to_string())
+            .find(|solution| super::is_solution(nonce, solution, difficulty))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_leading_zero_bits() {
+        assert_eq!(super::leading_zero_bits(&[0x00, 0x00, 0xff]), 16);
+        assert_eq!(super::leading_zero_bits(&[0x00, 0x10, 0x00]), 11);
+        assert_eq!(super::leading_zero_bits(&[0x80]), 0);
+    }
+
+    #[test]
+    fn test_verify_solution_succeeds() {
+        setup();
+
+        let challenge = super::issue_challenge().unwrap();
+        let solution = solve(&challenge.nonce, challenge.difficulty);
+
+        assert!(super::verify_solution(&ProofOfWorkResponse { challenge, solution }).is_ok());
+    }
+
+    #[test]
+    fn test_verify_solution_fails_for_tampered_difficulty() {
+        setup();
+
+        let mut challenge = super::issue_challenge().unwrap();
+        challenge.difficulty = 1;
+        let solution = solve(&challenge.nonce, challenge.difficulty);
+
+        assert!(super::verify_solution(&ProofOfWorkResponse { challenge, solution }).is_err());
+    }
+
+    #[test]
+    fn test_verify_solution_fails_for_expired_challenge() {
+        setup();
+
+        let mut challenge = super::issue_challenge().unwrap();
+        challenge.expires_at = Utc::now().naive_utc() - Duration::seconds(1);
+        challenge.signature = super::signature("test secret", &challenge.nonce, challenge.difficulty, &challenge.expires_at).unwrap();
+        let solution = solve(&challenge.nonce, challenge.difficulty);
+
+        assert!(super::verify_solution(&ProofOfWorkResponse { challenge, solution }).is_err());
+    }
+}
@@ -1,8 +1,20 @@
+pub mod auth;
 pub mod errors;
+pub mod accessory;
+pub mod availability;
 pub mod bike;
+pub mod bike_attribute;
 pub mod rent;
+pub mod approval;
 pub mod challenge;
+pub mod feedback;
+pub mod incident;
+pub mod invite;
+pub mod maintenance;
 pub mod supporter;
+pub mod waitlist;
+pub mod verification;
+pub mod media;
 
 use rocket::get;
 use rocket_contrib::json;
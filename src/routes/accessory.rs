@@ -0,0 +1,168 @@
+use rocket::{delete,get,post,put};
+use rocket::http::RawStr;
+use rocket_contrib::json::Json;
+
+use crate::database::DbConn;
+use crate::database::models::{Accessory,AccessoryTranslatable,AccessoryTranslationData,AccessoryWithDetails,InsertAccessory,InsertAccessoryTranslatable};
+use crate::database::daos::{accessory,availability};
+use crate::locale;
+use crate::locale::LocalePreferences;
+use crate::timezone;
+
+use crate::routes::auth::Admin;
+use crate::routes::errors::AccessoryError;
+
+/// Localized accessories. Given a window by `available_from` and
+/// `available_to`, the number of items free for the whole window is added.
+#[get("/accessories?<available_from>&<available_to>")]
+pub fn get_accessories(db: DbConn, locale: LocalePreferences, available_from: Option<String>, available_to: Option<String>) -> Result<Json<Vec<AccessoryWithDetails>>,AccessoryError> {
+    let mut accessories = accessory::get_accessories(&db, &locale)?;
+
+    let window = match (available_from, available_to) {
+        (None, None) => None,
+        (Some(from), Some(to)) => Some((
+            timezone::parse_timestamp(&from).map_err(AccessoryError::Validation)?,
+            timezone::parse_timestamp(&to).map_err(AccessoryError::Validation)?,
+        )),
+        _ => return Err(AccessoryError::Validation(String::from("available_from and available_to have to be given together."))),
+    };
+
+    if let Some((from, to)) = window {
+        if from >= to {
+            return Err(AccessoryError::Validation(String::from("available_to has to be after available_from.")));
+        }
+
+        let available = availability::get_available_accessory_quantities(&db, from, to)?;
+
+        for item in &mut accessories {
+            item.available = Some(available.iter()
+                .find(|(id, _)| *id == item.accessory.accessory_id)
+                .map_or(0, |(_, quantity)| *quantity));
+        }
+    }
+
+    Ok(Json(accessories))
+}
+
+fn validate_accessory(accessory: &InsertAccessory) -> Result<(),AccessoryError> {
+    if accessory.quantity < 0 {
+        return Err(AccessoryError::Validation(String::from("The quantity cannot be negative.")));
+    }
+
+    Ok(())
+}
+
+#[get("/admin/accessories")]
+pub fn get_accessory_inventory(db: DbConn, _admin: Admin) -> Result<Json<Vec<Accessory>>,AccessoryError> {
+    Ok(Json(accessory::get_accessory_inventory(&db)?))
+}
+
+#[post("/admin/accessories", data = "<data>")]
+pub fn create_accessory(db: DbConn, _admin: Admin, data: Json<InsertAccessory>) -> Result<Json<Accessory>,AccessoryError> {
+    validate_accessory(&data)?;
+
+    Ok(Json(accessory::insert_accessory(&db, &data)?))
+}
+
+/// Lowering the quantity does not touch existing rents, it only limits new
+/// bookings.
+#[put("/admin/accessories/<id>", data = "<data>")]
+pub fn update_accessory(db: DbConn, _admin: Admin, id: i32, data: Json<InsertAccessory>) -> Result<Json<Accessory>,AccessoryError> {
+    validate_accessory(&data)?;
+
+    Ok(Json(accessory::update_accessory(&db, id, &data)?))
+}
+
+#[delete("/admin/accessories/<id>")]
+pub fn delete_accessory(db: DbConn, _admin: Admin, id: i32) -> Result<(),AccessoryError> {
+    if accessory::delete_accessory(&db, id)? == 0 {
+        return Err(AccessoryError::NotFound(String::from("Accessory not found")));
+    }
+
+    Ok(())
+}
+
+/// Bikes the accessory is made for, it goes with every bike if there are
+/// none.
+#[get("/admin/accessories/<id>/bikes")]
+pub fn get_accessory_bikes(db: DbConn, _admin: Admin, id: i32) -> Result<Json<Vec<i32>>,AccessoryError> {
+    Ok(Json(accessory::get_compatible_bike_ids(&db, id)?))
+}
+
+#[put("/admin/accessories/<id>/bikes", data = "<data>")]
+pub fn put_accessory_bikes(db: DbConn, _admin: Admin, id: i32, data: Json<Vec<i32>>) -> Result<Json<Vec<i32>>,AccessoryError> {
+    Ok(Json(accessory::set_compatible_bike_ids(&db, id, &data)?))
+}
+
+#[get("/admin/accessories/<id>/translations")]
+pub fn get_accessory_translations(db: DbConn, _admin: Admin, id: i32) -> Result<Json<Vec<AccessoryTranslatable>>,AccessoryError> {
+    Ok(Json(accessory::get_accessory_translations(&db, id)?))
+}
+
+#[put("/admin/accessories/<id>/translations/<p_locale>", data = "<data>")]
+pub fn put_accessory_translation(db: DbConn, _admin: Admin, id: i32, p_locale: &RawStr, data: Json<AccessoryTranslationData>) -> Result<Json<AccessoryTranslatable>,AccessoryError> {
+    let data = data.into_inner();
+    let translation = InsertAccessoryTranslatable {
+        accessory_id: id,
+        locale: locale::parse_stored_locale(p_locale.as_str()).map_err(AccessoryError::Validation)?,
+        title: data.title,
+        description: data.description,
+    };
+
+    Ok(Json(accessory::upsert_accessory_translation(&db, &translation)?))
+}
+
+#[cfg(test)]
+mod test {
+    use mocktopus::mocking::Mockable;
+    use mocktopus::mocking::MockResult;
+
+    use chrono::NaiveDate;
+
+    use rocket;
+    use rocket::routes;
+    use rocket::local::Client;
+    use rocket::http::Status;
+
+    use crate::database::DbConn;
+    use crate::database::daos::{accessory,availability};
+    use crate::database::models::{AccessoryTranslatable,AccessoryWithDetails};
+
+    #[test]
+    fn test_get_accessories_with_window() {
+        crate::database::test::setup();
+
+        accessory::get_accessories.mock_safe(|_, _| MockResult::Return(Ok(vec![AccessoryWithDetails {
+            accessory: AccessoryTranslatable {
+                id: 1,
+                accessory_id: 2,
+                locale: "de-DE".to_string(),
+                title: "Kindersitz".to_string(),
+                description: None,
+            },
+            quantity: 3,
+            sort_priority: 0,
+            available: None,
+        }])));
+        availability::get_available_accessory_quantities.mock_safe(|_, from, to| {
+            assert_eq!(from, NaiveDate::from_ymd(2021, 5, 1).and_hms(8, 0, 0));
+            assert_eq!(to, NaiveDate::from_ymd(2021, 5, 1).and_hms(18, 0, 0));
+            MockResult::Return(Ok(vec![(2, 1)]))
+        });
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::get_accessories]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.get("/accessories").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("[{\"id\":1,\"accessory_id\":2,\"locale\":\"de-DE\",\"title\":\"Kindersitz\",\"description\":null,\"quantity\":3,\"sort_priority\":0,\"available\":null}]".to_string()));
+
+        let mut response = client.get("/accessories?available_from=2021-05-01T08:00:00&available_to=2021-05-01T18:00:00").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("[{\"id\":1,\"accessory_id\":2,\"locale\":\"de-DE\",\"title\":\"Kindersitz\",\"description\":null,\"quantity\":3,\"sort_priority\":0,\"available\":1}]".to_string()));
+
+        assert_eq!(client.get("/accessories?available_from=2021-05-01T08:00:00").dispatch().status(), Status::BadRequest);
+    }
+}
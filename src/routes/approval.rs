@@ -0,0 +1,157 @@
+use chrono::Utc;
+
+use log::{error,info};
+
+use rocket::{get,post};
+use rocket::http::RawStr;
+use rocket_contrib::json::Json;
+
+use crate::database::DbConn;
+use crate::database::models::{RentApproval,RentApprovalWithRents};
+use crate::database::daos::{approval,verification};
+use crate::mailer;
+
+use crate::routes::auth::Admin;
+use crate::routes::errors::RentError;
+use crate::routes::waitlist;
+
+/// Mails the decision to the rider, if the address left with the booking
+/// has been confirmed.
+pub fn notify_rider(db: &DbConn, approval: &RentApprovalWithRents) {
+    if !mailer::is_mail_config_available() {
+        return;
+    }
+
+    match verification::get_verified_email(db, approval.approval.token_id) {
+        Ok(Some(email)) => if let Err(err) = mailer::send_approval_mail(&email, approval) {
+            error!("Sending the mail for approval {} failed: {}", approval.approval.id, err);
+        },
+        Ok(None) => (),
+        Err(err) => error!("Looking up the address for approval {} failed: {}", approval.approval.id, err),
+    }
+}
+
+/// Expires the bookings nobody decided on in time. Runs as a periodic job,
+/// the freed slots are offered to the waitlist afterwards.
+pub fn expire_approvals(db: &DbConn) {
+    match approval::expire_pending_approvals(db, Utc::now().naive_utc()) {
+        Ok(expired) => {
+            for approval in &expired {
+                notify_rider(db, approval);
+            }
+
+            if !expired.is_empty() {
+                info!("Expired {} pending bookings", expired.len());
+                waitlist::notify_waitlist(db);
+            }
+        },
+        Err(err) => error!("Expiring pending bookings failed: {}", err),
+    }
+}
+
+#[get("/admin/approvals")]
+pub fn get_pending_approvals(db: DbConn, _admin: Admin) -> Result<Json<Vec<RentApprovalWithRents>>,RentError> {
+    Ok(Json(approval::get_pending_approvals(&db)?))
+}
+
+#[post("/admin/approvals/<id>/approve")]
+pub fn approve_booking(db: DbConn, admin: Admin, id: i32) -> Result<Json<RentApprovalWithRents>,RentError> {
+    let approved = approval::decide_approval(&db, id, true, &admin.name)?;
+    notify_rider(&db, &approved);
+
+    Ok(Json(approved))
+}
+
+#[post("/admin/approvals/<id>/decline")]
+pub fn decline_booking(db: DbConn, admin: Admin, id: i32) -> Result<Json<RentApprovalWithRents>,RentError> {
+    let declined = approval::decide_approval(&db, id, false, &admin.name)?;
+    notify_rider(&db, &declined);
+    waitlist::notify_waitlist(&db);
+
+    Ok(Json(declined))
+}
+
+/// Lets the rider follow the decision on a booking which needed approval.
+#[get("/rents/<token>/approval")]
+pub fn get_approval(db: DbConn, token: &RawStr) -> Result<Json<RentApproval>,RentError> {
+    let parsed_token = ::uuid::Uuid::parse_str(token)?;
+
+    Ok(Json(approval::get_approval_by_token(&db, &parsed_token)?))
+}
+
+#[cfg(test)]
+mod test {
+    use mocktopus::mocking::Mockable;
+    use mocktopus::mocking::MockResult;
+
+    use chrono::NaiveDate;
+
+    use rocket;
+    use rocket::routes;
+    use rocket::local::Client;
+    use rocket::http::Status;
+
+    use crate::database::DbConn;
+    use crate::database::daos::approval;
+    use crate::database::models::{ApprovalStatus,RentApproval,RentApprovalWithRents};
+    use crate::mailer;
+    use crate::routes::auth;
+    use crate::routes::errors::RentError;
+
+    fn rent_approval(status: ApprovalStatus, decided_by: Option<&str>) -> RentApproval {
+        RentApproval {
+            id: 1,
+            token_id: 2,
+            status: status.as_str().to_string(),
+            expires_at: NaiveDate::from_ymd(2021, 4, 20).and_hms(0, 0, 0),
+            decided_by: decided_by.map(String::from),
+            decided_at: decided_by.map(|_| NaiveDate::from_ymd(2021, 4, 19).and_hms(0, 0, 0)),
+            created_at: NaiveDate::from_ymd(2021, 4, 18).and_hms(0, 0, 0),
+        }
+    }
+
+    #[test]
+    fn test_approve_booking() {
+        crate::database::test::setup();
+        auth::test::setup();
+
+        mailer::is_mail_config_available.mock_safe(|| MockResult::Return(false));
+        approval::decide_approval.mock_safe(|_, id, approve, admin_name| MockResult::Return(if id == 1 {
+            assert!(approve);
+            Ok(RentApprovalWithRents { approval: rent_approval(ApprovalStatus::Approved, Some(admin_name)), rents: vec![] })
+        } else {
+            Err(RentError::Validation(String::from("The booking is already declined.")))
+        }));
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::approve_booking]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let response = client.post("/admin/approvals/1/approve").dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+
+        let mut response = client.post("/admin/approvals/1/approve").header(auth::test::authorization()).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("{\"id\":1,\"token_id\":2,\"status\":\"approved\",\"expires_at\":\"2021-04-20T00:00:00+00:00\",\"decided_by\":\"admin\",\"decided_at\":\"2021-04-19T00:00:00+00:00\",\"created_at\":\"2021-04-18T00:00:00+00:00\",\"rents\":[]}".to_string()));
+
+        let response = client.post("/admin/approvals/2/approve").header(auth::test::authorization()).dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn test_get_approval() {
+        crate::database::test::setup();
+
+        approval::get_approval_by_token.mock_safe(|_, _| MockResult::Return(Ok(rent_approval(ApprovalStatus::Pending, None))));
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::get_approval]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.get("/rents/00a791f1-68b8-457c-82d9-a060f48efbae/approval").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("{\"id\":1,\"token_id\":2,\"status\":\"pending\",\"expires_at\":\"2021-04-20T00:00:00+00:00\",\"decided_by\":null,\"decided_at\":null,\"created_at\":\"2021-04-18T00:00:00+00:00\"}".to_string()));
+    }
+}
@@ -0,0 +1,97 @@
+use rocket::Outcome;
+use rocket::http::Status;
+use rocket::request::{self,FromRequest,Request};
+
+use subtle::ConstantTimeEq;
+
+use crate::config;
+
+/// Request guard for the admin endpoints. Expects one of the configured admin
+/// API keys in an `Authorization: Bearer <key>` header.
+#[derive(Debug)]
+pub struct Admin {
+    pub name: String
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for Admin {
+    type Error = ();
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<Admin, ()> {
+        let key = request.headers()
+            .get_one("Authorization")
+            .and_then(|value| value.strip_prefix("Bearer "));
+
+        let key = match key {
+            Some(key) => key.trim(),
+            None => return Outcome::Failure((Status::Unauthorized, ())),
+        };
+
+        // Every configured key is compared in constant time, so the response
+        // time does not tell how much of a key was right.
+        let admin = config::admin_api_keys().into_iter()
+            .fold(None, |admin, (name, k)| {
+                let matches: bool = k.as_bytes().ct_eq(key.as_bytes()).into();
+
+                admin.or(if matches { Some(name) } else { None })
+            });
+
+        match admin {
+            Some(name) => Outcome::Success(Admin { name }),
+            None => Outcome::Failure((Status::Unauthorized, ())),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use std::env;
+
+    use rocket;
+    use rocket::get;
+    use rocket::routes;
+    use rocket::local::Client;
+    use rocket::http::{Header,Status};
+
+    pub const ADMIN_KEY: &str = "secret-admin-key";
+
+    pub fn setup() {
+        env::set_var("ADMIN_API_KEYS", format!("admin:{}", ADMIN_KEY));
+    }
+
+    pub fn authorization() -> Header<'static> {
+        Header::new("Authorization", format!("Bearer {}", ADMIN_KEY))
+    }
+
+    #[get("/whoami")]
+    fn whoami(admin: super::Admin) -> String {
+        admin.name
+    }
+
+    #[test]
+    fn test_admin_with_valid_key() {
+        setup();
+
+        let rocket = rocket::ignite().mount("/", routes![whoami]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.get("/whoami").header(authorization()).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("admin".to_string()));
+    }
+
+    #[test]
+    fn test_admin_without_valid_key() {
+        setup();
+
+        let rocket = rocket::ignite().mount("/", routes![whoami]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let response = client.get("/whoami").dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+
+        let response = client.get("/whoami")
+            .header(Header::new("Authorization", "Bearer wrong"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+    }
+}
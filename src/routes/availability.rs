@@ -0,0 +1,102 @@
+use chrono::Utc;
+
+use rocket::{delete,get,post};
+use rocket_contrib::json::Json;
+
+use crate::database::DbConn;
+use crate::database::models::{BikeBlackout,BikeBlackoutRequest,InsertBikeBlackout};
+use crate::database::daos::availability;
+
+use crate::routes::auth::Admin;
+use crate::routes::errors::BikeError;
+
+/// Blackouts which have not ended yet.
+#[get("/admin/blackouts")]
+pub fn get_blackouts(db: DbConn, _admin: Admin) -> Result<Json<Vec<BikeBlackout>>,BikeError> {
+    Ok(Json(availability::get_blackouts(&db, Utc::now().naive_utc())?))
+}
+
+#[post("/admin/blackouts", data = "<request>")]
+pub fn create_blackout(db: DbConn, admin: Admin, request: Json<BikeBlackoutRequest>) -> Result<Json<BikeBlackout>,BikeError> {
+    let request = request.into_inner();
+
+    if request.start_timestamp >= request.end_timestamp {
+        return Err(BikeError::Validation(String::from("The blackout has to end after it starts.")));
+    }
+
+    let blackout = InsertBikeBlackout {
+        bike_id: request.bike_id,
+        start_timestamp: request.start_timestamp,
+        end_timestamp: request.end_timestamp,
+        reason: request.reason,
+        created_by: admin.name,
+    };
+
+    Ok(Json(availability::insert_blackout(&db, &blackout)?))
+}
+
+#[delete("/admin/blackouts/<id>")]
+pub fn delete_blackout(db: DbConn, _admin: Admin, id: i32) -> Result<(),BikeError> {
+    if availability::delete_blackout(&db, id)? == 0 {
+        return Err(BikeError::NotFound(String::from("Blackout not found")));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use mocktopus::mocking::Mockable;
+    use mocktopus::mocking::MockResult;
+
+    use chrono::NaiveDate;
+
+    use rocket;
+    use rocket::routes;
+    use rocket::local::Client;
+    use rocket::http::Status;
+
+    use crate::database::DbConn;
+    use crate::database::daos::availability;
+    use crate::database::models::BikeBlackout;
+
+    use crate::routes::auth;
+
+    #[test]
+    fn test_create_blackout() {
+        crate::database::test::setup();
+        auth::test::setup();
+
+        availability::insert_blackout.mock_safe(|_, blackout| {
+            assert_eq!(blackout.created_by, "admin");
+
+            MockResult::Return(Ok(BikeBlackout {
+                id: 1,
+                bike_id: blackout.bike_id,
+                start_timestamp: blackout.start_timestamp,
+                end_timestamp: blackout.end_timestamp,
+                reason: blackout.reason.clone(),
+                created_by: blackout.created_by.clone(),
+                created_at: NaiveDate::from_ymd(2021, 4, 18).and_hms(0, 0, 0),
+            }))
+        });
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::create_blackout]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.post("/admin/blackouts")
+            .header(auth::test::authorization())
+            .body(r#"{"bike_id":null,"start_timestamp":"2021-12-24T00:00:00","end_timestamp":"2021-12-27T00:00:00","reason":"Christmas"}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("{\"id\":1,\"bike_id\":null,\"start_timestamp\":\"2021-12-24T00:00:00+00:00\",\"end_timestamp\":\"2021-12-27T00:00:00+00:00\",\"reason\":\"Christmas\",\"created_by\":\"admin\",\"created_at\":\"2021-04-18T00:00:00+00:00\"}".to_string()));
+
+        let response = client.post("/admin/blackouts")
+            .header(auth::test::authorization())
+            .body(r#"{"bike_id":1,"start_timestamp":"2021-12-27T00:00:00","end_timestamp":"2021-12-24T00:00:00","reason":null}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+}
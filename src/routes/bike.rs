@@ -1,52 +1,419 @@
-use rocket::{get};
+use chrono::{NaiveDate,NaiveDateTime};
+
+use rocket::{delete,get,post,put};
+use rocket::{Data,Outcome};
+use rocket::http::{RawStr,Status};
+use rocket::request::{self,FormItems,FromRequest,Request};
 use rocket_contrib::json::Json;
 
+use crate::config;
 use crate::database::DbConn;
-use crate::database::models::BikeTranslatable;
-use crate::database::daos::bike;
+use crate::database::models::{AttributeType,AttributeValue,Bike,BikeApprovalRequest,BikeAttribute,BikeBufferData,BikeGranularityRequest,BikeMedia,BikeMediaPosition,BikeMediaTranslatable,BikeMediaTranslationData,BikeSlots,BikeStatus,BikeStatusChange,BikeStatusRequest,BikeStatusUpdate,BikeWithDetails,BookingGranularity,InsertBikeMedia,InsertBikeMediaTranslatable,InsertBikeStatusChange};
+use crate::database::daos::{availability,bike,bike_attribute};
+use crate::locale;
+use crate::locale::LocalePreferences;
+use crate::media;
+use crate::media::ImageVariant;
+use crate::media::storage::FileStorage;
+use crate::timezone;
+
+use crate::routes::auth::Admin;
+use crate::routes::errors::BikeError;
+
+/// Attribute conditions passed as query parameters: `<key>=<value>` compares
+/// the value of an attribute, `min_<key>` and `max_<key>` limit numeric
+/// attributes, e.g. `?electric=true&min_capacity_kg=80`.
+#[derive(Debug,PartialEq)]
+pub enum BikeFilter {
+    Equals(String, String),
+    Min(String, f64),
+    Max(String, f64),
+}
+
+/// Query parameters of `GET /bikes`. Besides the attribute filters the list
+/// can be restricted to bikes which can be booked for a whole window given by
+/// `available_from` and `available_to`.
+#[derive(Debug)]
+pub struct BikeFilters {
+    pub attributes: Vec<BikeFilter>,
+    pub window: Option<(NaiveDateTime, NaiveDateTime)>,
+}
+
+impl<'a, 'r> FromRequest<'a, 'r> for BikeFilters {
+    type Error = String;
+
+    fn from_request(request: &'a Request<'r>) -> request::Outcome<BikeFilters, String> {
+        let query = match request.uri().query() {
+            Some(query) => query,
+            None => return Outcome::Success(BikeFilters { attributes: vec![], window: None }),
+        };
+
+        let mut attributes = vec![];
+        let mut available_from = None;
+        let mut available_to = None;
+
+        for item in FormItems::from(RawStr::from_str(query)) {
+            let (key, value) = item.key_value_decoded();
+            let number = || value.parse::<f64>().map_err(|_| format!("'{}' is not a number.", value));
+
+            match key.as_str() {
+                "locale" => continue,
+                "available_from" | "available_to" => {
+                    let timestamp = match timezone::parse_timestamp(&value) {
+                        Ok(timestamp) => timestamp,
+                        Err(err) => return Outcome::Failure((Status::BadRequest, err)),
+                    };
+
+                    if key == "available_from" {
+                        available_from = Some(timestamp);
+                    } else {
+                        available_to = Some(timestamp);
+                    }
+
+                    continue;
+                },
+                _ => (),
+            }
+
+            // Any other parameter has to name an attribute, unknown ones are
+            // rejected rather than silently ignored.
+            let filter = if let Some(key) = key.strip_prefix("min_") {
+                number().map(|v| BikeFilter::Min(key.to_string(), v))
+            } else if let Some(key) = key.strip_prefix("max_") {
+                number().map(|v| BikeFilter::Max(key.to_string(), v))
+            } else {
+                Ok(BikeFilter::Equals(key.clone(), value.clone()))
+            };
+
+            match filter {
+                Ok(filter) => attributes.push(filter),
+                Err(err) => return Outcome::Failure((Status::BadRequest, err)),
+            }
+        }
+
+        let window = match (available_from, available_to) {
+            (Some(from), Some(to)) if from < to => Some((from, to)),
+            (None, None) => None,
+            _ => return Outcome::Failure((Status::BadRequest, String::from("available_from and available_to have to be given together and form a valid period."))),
+        };
+
+        Outcome::Success(BikeFilters { attributes, window })
+    }
+}
+
+enum Condition {
+    Is(AttributeValue),
+    AtLeast(f64),
+    AtMost(f64),
+}
+
+/// Checks the filters against the attribute definitions and converts their
+/// values to the type of the attribute.
+fn conditions(filters: &[BikeFilter], attributes: &[BikeAttribute]) -> Result<Vec<(String, Condition)>,BikeError> {
+    filters.iter()
+        .map(|filter| {
+            let key = match filter {
+                BikeFilter::Equals(key, _) | BikeFilter::Min(key, _) | BikeFilter::Max(key, _) => key,
+            };
+            let attribute_type = attributes.iter()
+                .find(|a| &a.key == key)
+                .and_then(|a| a.value_type.parse::<AttributeType>().ok())
+                .ok_or_else(|| BikeError::Validation(format!("Unknown attribute '{}'.", key)))?;
+
+            let condition = match (filter, attribute_type) {
+                (BikeFilter::Equals(_, value), AttributeType::Number) => value.parse::<f64>().ok().map(|v| Condition::Is(AttributeValue::Number(v))),
+                (BikeFilter::Equals(_, value), AttributeType::Boolean) => value.parse::<bool>().ok().map(|v| Condition::Is(AttributeValue::Boolean(v))),
+                (BikeFilter::Equals(_, value), AttributeType::Enum) => Some(Condition::Is(AttributeValue::Option(value.clone()))),
+                (BikeFilter::Min(_, value), AttributeType::Number) => Some(Condition::AtLeast(*value)),
+                (BikeFilter::Max(_, value), AttributeType::Number) => Some(Condition::AtMost(*value)),
+                _ => None,
+            };
+
+            condition
+                .map(|condition| (key.clone(), condition))
+                .ok_or_else(|| BikeError::Validation(format!("Invalid filter for attribute '{}'.", key)))
+        })
+        .collect()
+}
+
+/// Bikes lacking an attribute never match a condition on it.
+fn matches(bike: &BikeWithDetails, conditions: &[(String, Condition)]) -> bool {
+    conditions.iter().all(|(key, condition)| {
+        bike.attributes.iter()
+            .filter(|a| &a.key == key)
+            .any(|a| match (condition, &a.value) {
+                (Condition::Is(expected), value) => expected == value,
+                (Condition::AtLeast(min), AttributeValue::Number(value)) => value >= min,
+                (Condition::AtMost(max), AttributeValue::Number(value)) => value <= max,
+                _ => false,
+            })
+    })
+}
 
 #[get("/bikes")]
-pub fn get_bikes(db: DbConn) -> Result<Json<Vec<BikeTranslatable>>,diesel::result::Error> {
-    let data = bike::get_bikes(&db);
+pub fn get_bikes(db: DbConn, locale: LocalePreferences, filters: BikeFilters) -> Result<Json<Vec<BikeWithDetails>>,BikeError> {
+    let conditions = if filters.attributes.is_empty() {
+        vec![]
+    } else {
+        conditions(&filters.attributes, &bike_attribute::get_bike_attributes(&db)?)?
+    };
+
+    let mut data = bike::get_bikes(&db, &locale)?
+        .into_iter()
+        .filter(|b| matches(b, &conditions))
+        .collect::<Vec<_>>();
+
+    if let Some((from, to)) = filters.window {
+        let ids = data.iter().map(|b| b.bike.bike_id).collect::<Vec<_>>();
+        let available = availability::get_available_bike_ids(&db, &ids, from, to)?;
+
+        data.retain(|b| available.contains(&b.bike.bike_id));
+    }
+
+    Ok(Json(data))
+}
+
+pub(crate) fn parse_locale(p_locale: &RawStr) -> Result<String,BikeError> {
+    let locale = p_locale.url_decode()
+        .map_err(|_| BikeError::Validation(String::from("No valid locale passed.")))?;
+
+    locale::parse_stored_locale(&locale).map_err(BikeError::Validation)
+}
+
+fn ensure_media_of_bike(db: &DbConn, id: i32, media_id: i32) -> Result<(),BikeError> {
+    if !bike::get_bike_media(db, id)?.iter().any(|m| m.id == media_id) {
+        return Err(BikeError::NotFound(String::from("Media not found")));
+    }
+
+    Ok(())
+}
+
+#[get("/admin/bikes/<id>/media")]
+pub fn get_bike_media(db: DbConn, _admin: Admin, id: i32) -> Result<Json<Vec<BikeMedia>>,BikeError> {
+    Ok(Json(bike::get_bike_media(&db, id)?))
+}
+
+/// Stores the uploaded image together with a web and a thumbnail variant and
+/// appends it to the gallery of the bike.
+#[post("/admin/bikes/<id>/media", data = "<data>")]
+pub fn upload_bike_media(db: DbConn, _admin: Admin, id: i32, data: Data) -> Result<Json<BikeMedia>,BikeError> {
+    bike::get_bike(&db, id)?;
 
-    match data {
-        Ok(v) => Ok(Json(v)),
-        Err(e) => Err(e),
+    let storage = FileStorage::from_config();
+    let upload = media::read_upload(data)?;
+    let image = media::store_image(&storage, "bikes", &upload, &[
+        ImageVariant { name: "web", max_width: config::bike_image_max_width(), max_height: config::bike_image_max_height() },
+        ImageVariant { name: "thumbnail", max_width: config::thumbnail_max_width(), max_height: config::thumbnail_max_height() },
+    ])?;
+    let (web, thumbnail) = (&image.variants[0], &image.variants[1]);
+
+    let inserted = bike::get_bike_media(&db, id).and_then(|existing| {
+        bike::insert_bike_media(&db, &InsertBikeMedia {
+            bike_id: id,
+            position: existing.last().map_or(0, |m| m.position + 1),
+            url: web.url.clone(),
+            width: web.width as i32,
+            height: web.height as i32,
+            thumbnail_url: thumbnail.url.clone(),
+            thumbnail_width: thumbnail.width as i32,
+            thumbnail_height: thumbnail.height as i32,
+        })
+    });
+
+    if inserted.is_err() {
+        media::discard_image(&storage, &image);
     }
+
+    Ok(Json(inserted?))
+}
+
+#[put("/admin/bikes/<id>/media/<media_id>", data = "<data>")]
+pub fn update_bike_media(db: DbConn, _admin: Admin, id: i32, media_id: i32, data: Json<BikeMediaPosition>) -> Result<Json<BikeMedia>,BikeError> {
+    Ok(Json(bike::update_bike_media_position(&db, id, media_id, data.position)?))
+}
+
+#[delete("/admin/bikes/<id>/media/<media_id>")]
+pub fn delete_bike_media(db: DbConn, _admin: Admin, id: i32, media_id: i32) -> Result<(),BikeError> {
+    if bike::delete_bike_media(&db, id, media_id)? == 0 {
+        return Err(BikeError::NotFound(String::from("Media not found")));
+    }
+
+    Ok(())
+}
+
+#[get("/admin/bikes/<id>/media/<media_id>/translations")]
+pub fn get_bike_media_translations(db: DbConn, _admin: Admin, id: i32, media_id: i32) -> Result<Json<Vec<BikeMediaTranslatable>>,BikeError> {
+    ensure_media_of_bike(&db, id, media_id)?;
+
+    Ok(Json(bike::get_bike_media_translations(&db, media_id)?))
+}
+
+#[put("/admin/bikes/<id>/media/<media_id>/translations/<p_locale>", data = "<data>")]
+pub fn put_bike_media_translation(db: DbConn, _admin: Admin, id: i32, media_id: i32, p_locale: &RawStr, data: Json<BikeMediaTranslationData>) -> Result<Json<BikeMediaTranslatable>,BikeError> {
+    let locale = parse_locale(p_locale)?;
+
+    ensure_media_of_bike(&db, id, media_id)?;
+
+    let translation = InsertBikeMediaTranslatable {
+        bike_media_id: media_id,
+        locale,
+        alt_text: data.into_inner().alt_text,
+    };
+
+    Ok(Json(bike::upsert_bike_media_translation(&db, &translation)?))
+}
+
+#[get("/admin/bikes/<id>/status")]
+pub fn get_bike_status_history(db: DbConn, _admin: Admin, id: i32) -> Result<Json<Vec<BikeStatusChange>>,BikeError> {
+    Ok(Json(bike::get_bike_status_history(&db, id)?))
+}
+
+/// Changes the status of a bike. Rents flagged because the bike was retired
+/// are returned with the change.
+#[put("/admin/bikes/<id>/status", data = "<data>")]
+pub fn put_bike_status(db: DbConn, admin: Admin, id: i32, data: Json<BikeStatusRequest>) -> Result<Json<BikeStatusUpdate>,BikeError> {
+    let data = data.into_inner();
+    let status = data.status.parse::<BikeStatus>().map_err(BikeError::Validation)?;
+
+    let change = InsertBikeStatusChange {
+        bike_id: id,
+        status: status.as_str().to_string(),
+        comment: data.comment,
+        changed_by: admin.name,
+    };
+
+    Ok(Json(bike::update_bike_status(&db, &change)?))
+}
+
+#[get("/admin/bikes/<id>/buffers")]
+pub fn get_bike_buffers(db: DbConn, _admin: Admin, id: i32) -> Result<Json<Bike>,BikeError> {
+    Ok(Json(bike::get_bike(&db, id)?))
+}
+
+/// Sets the turnaround buffers of a bike, empty ones use the global buffers.
+#[put("/admin/bikes/<id>/buffers", data = "<data>")]
+pub fn put_bike_buffers(db: DbConn, _admin: Admin, id: i32, data: Json<BikeBufferData>) -> Result<Json<Bike>,BikeError> {
+    if data.buffer_before_minutes.map_or(false, |minutes| minutes < 0) || data.buffer_after_minutes.map_or(false, |minutes| minutes < 0) {
+        return Err(BikeError::Validation(String::from("Buffers cannot be negative.")));
+    }
+
+    Ok(Json(bike::update_bike_buffers(&db, id, &data)?))
+}
+
+#[put("/admin/bikes/<id>/granularity", data = "<data>")]
+pub fn put_bike_granularity(db: DbConn, _admin: Admin, id: i32, data: Json<BikeGranularityRequest>) -> Result<Json<Bike>,BikeError> {
+    let granularity = data.booking_granularity.parse::<BookingGranularity>().map_err(BikeError::Validation)?;
+
+    Ok(Json(bike::update_bike_granularity(&db, id, granularity)?))
+}
+
+/// Bookings of bikes requiring approval stay pending until an admin decides.
+#[put("/admin/bikes/<id>/approval", data = "<data>")]
+pub fn put_bike_approval(db: DbConn, _admin: Admin, id: i32, data: Json<BikeApprovalRequest>) -> Result<Json<Bike>,BikeError> {
+    Ok(Json(bike::update_bike_approval(&db, id, data.requires_approval)?))
+}
+
+/// Slots of the bike on the day, today if no date is given. Free-form bikes
+/// have no slots, any window can be booked.
+#[get("/bikes/<id>/slots?<date>")]
+pub fn get_bike_slots(db: DbConn, id: i32, date: Option<String>) -> Result<Json<BikeSlots>,BikeError> {
+    let date = match date {
+        Some(date) => NaiveDate::parse_from_str(&date, "%Y-%m-%d").map_err(|err| BikeError::Validation(err.to_string()))?,
+        None => timezone::today(),
+    };
+    let bike = bike::get_bike(&db, id)?;
+    let granularity = bike.booking_granularity.parse::<BookingGranularity>().map_err(BikeError::Validation)?;
+
+    Ok(Json(BikeSlots {
+        bike_id: bike.id,
+        booking_granularity: bike.booking_granularity,
+        slots: granularity.slots(date, config::half_day_split_hour()),
+    }))
 }
 
 #[cfg(test)]
 mod test {
+    use std::env;
+
+    use chrono::NaiveDate;
+
     use mocktopus::mocking::Mockable;
     use mocktopus::mocking::MockResult;
 
     use rocket;
     use rocket::routes;
     use rocket::local::Client;
-    use rocket::http::Status;
+    use rocket::http::{ContentType,Header,Status};
 
     use crate::database::DbConn;
     use crate::database::daos::bike;
-    use crate::database::models::BikeTranslatable;
+    use crate::database::daos::{availability,bike_attribute};
+    use crate::database::models::{AttributeValue,Bike,BikeAttribute,BikeAttributeWithValue,BikeMedia,BikeMediaTranslatable,BikeMediaWithAltText,BikeStatusChange,BikeStatusUpdate,BikeTranslatable,BikeWithDetails};
+    use crate::locale::LanguageTag;
+    use crate::routes::auth;
+
+    fn media(id: i32, position: i32) -> BikeMedia {
+        BikeMedia {
+            id,
+            bike_id: 1,
+            position,
+            url: "/uploads/bikes/a-web.png".to_string(),
+            width: 1600,
+            height: 1200,
+            thumbnail_url: "/uploads/bikes/a-thumbnail.png".to_string(),
+            thumbnail_width: 320,
+            thumbnail_height: 240,
+            created_at: NaiveDate::from_ymd(2021, 4, 18).and_hms(0, 0, 0),
+        }
+    }
 
     #[test]
     fn test_get_bikes() {
         crate::database::test::setup();
 
-        bike::get_bikes.mock_safe(|_| MockResult::Return(Ok(vec![
-            BikeTranslatable { id: 1,
-                bike_id: 1,
-                locale: "de-DE".to_string(),
-                title: "Test".to_string(),
-                description: None,
-                url: None
+        bike::get_bikes.mock_safe(|_, _| MockResult::Return(Ok(vec![
+            BikeWithDetails {
+                attributes: vec![],
+                bike: BikeTranslatable { id: 1,
+                    bike_id: 1,
+                    locale: "de-DE".to_string(),
+                    title: "Test".to_string(),
+                    description: None,
+                    url: None
+                },
+                status: "active".to_string(),
+                buffer_before_minutes: 0,
+                buffer_after_minutes: 30,
+                booking_granularity: "day".to_string(),
+                requires_approval: true,
+                media: vec![],
             },
-            BikeTranslatable { id: 2,
-                bike_id: 1,
-                locale: "de-DE".to_string(),
-                title: "Test 2".to_string(),
-                description: Some("Test description".to_string()),
-                url: Some("https://bikes.test.rs/2".to_string())
+            BikeWithDetails {
+                attributes: vec![],
+                bike: BikeTranslatable { id: 2,
+                    bike_id: 1,
+                    locale: "de-DE".to_string(),
+                    title: "Test 2".to_string(),
+                    description: Some("Test description".to_string()),
+                    url: Some("https://bikes.test.rs/2".to_string())
+                },
+                status: "maintenance".to_string(),
+                buffer_before_minutes: 0,
+                buffer_after_minutes: 0,
+                booking_granularity: "free".to_string(),
+                requires_approval: false,
+                media: vec![
+                    BikeMediaWithAltText { id: 3,
+                        position: 0,
+                        url: "/uploads/bikes/a-web.png".to_string(),
+                        width: 1600,
+                        height: 1200,
+                        thumbnail_url: "/uploads/bikes/a-thumbnail.png".to_string(),
+                        thumbnail_width: 320,
+                        thumbnail_height: 240,
+                        alt_text: Some("Side view".to_string()),
+                    },
+                ],
             },
         ])));
 
@@ -57,14 +424,14 @@ mod test {
 
         let mut response = client.get("/bikes").dispatch();
         assert_eq!(response.status(), Status::Ok);
-        assert_eq!(response.body_string(), Some("[{\"id\":1,\"bike_id\":1,\"locale\":\"de-DE\",\"title\":\"Test\",\"description\":null,\"url\":null},{\"id\":2,\"bike_id\":1,\"locale\":\"de-DE\",\"title\":\"Test 2\",\"description\":\"Test description\",\"url\":\"https://bikes.test.rs/2\"}]".to_string()));
+        assert_eq!(response.body_string(), Some("[{\"id\":1,\"bike_id\":1,\"locale\":\"de-DE\",\"title\":\"Test\",\"description\":null,\"url\":null,\"status\":\"active\",\"buffer_before_minutes\":0,\"buffer_after_minutes\":30,\"booking_granularity\":\"day\",\"requires_approval\":true,\"attributes\":[],\"media\":[]},{\"id\":2,\"bike_id\":1,\"locale\":\"de-DE\",\"title\":\"Test 2\",\"description\":\"Test description\",\"url\":\"https://bikes.test.rs/2\",\"status\":\"maintenance\",\"buffer_before_minutes\":0,\"buffer_after_minutes\":0,\"booking_granularity\":\"free\",\"requires_approval\":false,\"attributes\":[],\"media\":[{\"id\":3,\"position\":0,\"url\":\"/uploads/bikes/a-web.png\",\"width\":1600,\"height\":1200,\"thumbnail_url\":\"/uploads/bikes/a-thumbnail.png\",\"thumbnail_width\":320,\"thumbnail_height\":240,\"alt_text\":\"Side view\"}]}]".to_string()));
     }
 
     #[test]
     fn test_get_bikes_with_empty_result() {
         crate::database::test::setup();
 
-        bike::get_bikes.mock_safe(|_| MockResult::Return(Ok(vec![])));
+        bike::get_bikes.mock_safe(|_, _| MockResult::Return(Ok(vec![])));
 
         let rocket = rocket::ignite()
             .attach(DbConn::fairing())
@@ -75,4 +442,283 @@ mod test {
         assert_eq!(response.status(), Status::Ok);
         assert_eq!(response.body_string(), Some("[]".to_string()));
     }
+
+    #[test]
+    fn test_get_bikes_with_locale() {
+        crate::database::test::setup();
+
+        bike::get_bikes.mock_safe(|_, preferences| {
+            assert_eq!(preferences.tags[0], "en-GB".parse::<LanguageTag>().unwrap());
+            MockResult::Return(Ok(vec![]))
+        });
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::get_bikes]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let response = client.get("/bikes?locale=en-GB")
+            .header(Header::new("Accept-Language", "de-DE"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let response = client.get("/bikes")
+            .header(Header::new("Accept-Language", "en-GB, de-DE;q=0.5"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let response = client.get("/bikes?locale=xxde-DEyy").dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    fn bike_with_attributes(bike_id: i32, electric: bool, capacity_kg: f64) -> BikeWithDetails {
+        let attribute = |key: &str, value| BikeAttributeWithValue { key: key.to_string(), label: None, unit: None, value, value_label: None };
+
+        BikeWithDetails {
+            bike: BikeTranslatable { id: bike_id,
+                bike_id,
+                locale: "de-DE".to_string(),
+                title: format!("Bike {}", bike_id),
+                description: None,
+                url: None
+            },
+            status: "active".to_string(),
+            buffer_before_minutes: 0,
+            buffer_after_minutes: 0,
+            booking_granularity: "free".to_string(),
+            requires_approval: false,
+            attributes: vec![
+                attribute("electric", AttributeValue::Boolean(electric)),
+                attribute("capacity_kg", AttributeValue::Number(capacity_kg)),
+            ],
+            media: vec![],
+        }
+    }
+
+    #[test]
+    fn test_get_bikes_with_filters() {
+        crate::database::test::setup();
+
+        bike_attribute::get_bike_attributes.mock_safe(|_| MockResult::Return(Ok(vec![
+            BikeAttribute { id: 1, key: "electric".to_string(), value_type: "boolean".to_string(), unit: None, sort_priority: 0 },
+            BikeAttribute { id: 2, key: "capacity_kg".to_string(), value_type: "number".to_string(), unit: Some("kg".to_string()), sort_priority: 0 },
+        ])));
+        bike::get_bikes.mock_safe(|_, _| MockResult::Return(Ok(vec![
+            bike_with_attributes(1, true, 100.0),
+            bike_with_attributes(2, true, 60.0),
+            bike_with_attributes(3, false, 120.0),
+        ])));
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::get_bikes]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let ids = |uri: &str| {
+            let mut response = client.get(uri.to_string()).dispatch();
+            assert_eq!(response.status(), Status::Ok);
+
+            let body: serde_json::Value = serde_json::from_str(&response.body_string().unwrap()).unwrap();
+            body.as_array().unwrap().iter().map(|b| b["bike_id"].as_i64().unwrap()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids("/bikes?electric=true&min_capacity_kg=80"), vec![1]);
+        assert_eq!(ids("/bikes?max_capacity_kg=100&locale=en-GB"), vec![1, 2]);
+        assert_eq!(ids("/bikes?electric=false"), vec![3]);
+
+        assert_eq!(client.get("/bikes?electric=maybe").dispatch().status(), Status::BadRequest);
+        assert_eq!(client.get("/bikes?min_electric=1").dispatch().status(), Status::BadRequest);
+        assert_eq!(client.get("/bikes?min_capacity_kg=heavy").dispatch().status(), Status::BadRequest);
+        assert_eq!(client.get("/bikes?wheels=3").dispatch().status(), Status::BadRequest);
+        assert_eq!(client.get("/bikes?electric=false&utm_source=newsletter").dispatch().status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn test_get_bikes_available_in_window() {
+        crate::database::test::setup();
+
+        bike::get_bikes.mock_safe(|_, _| MockResult::Return(Ok(vec![
+            bike_with_attributes(1, true, 100.0),
+            bike_with_attributes(2, true, 60.0),
+        ])));
+        availability::get_available_bike_ids.mock_safe(|_, ids, from, to| {
+            assert_eq!(ids, &[1, 2]);
+            assert_eq!(from, NaiveDate::from_ymd(2021, 5, 1).and_hms(8, 0, 0));
+            assert_eq!(to, NaiveDate::from_ymd(2021, 5, 1).and_hms(18, 0, 0));
+            MockResult::Return(Ok(vec![2]))
+        });
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::get_bikes]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.get("/bikes?available_from=2021-05-01T10:00:00%2B02:00&available_to=2021-05-01T18:00:00").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let body: serde_json::Value = serde_json::from_str(&response.body_string().unwrap()).unwrap();
+        assert_eq!(body.as_array().unwrap().len(), 1);
+        assert_eq!(body[0]["bike_id"], 2);
+
+        assert_eq!(client.get("/bikes?available_from=2021-05-01T10:00:00").dispatch().status(), Status::BadRequest);
+        assert_eq!(client.get("/bikes?available_from=2021-05-02T10:00:00&available_to=2021-05-01T10:00:00").dispatch().status(), Status::BadRequest);
+        assert_eq!(client.get("/bikes?available_from=saturday&available_to=sunday").dispatch().status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn test_put_bike_status() {
+        crate::database::test::setup();
+        auth::test::setup();
+
+        bike::update_bike_status.mock_safe(|_, change| {
+            assert_eq!(change.status, "retired");
+            assert_eq!(change.changed_by, "admin");
+
+            MockResult::Return(Ok(BikeStatusUpdate {
+                change: BikeStatusChange {
+                    id: 1,
+                    bike_id: change.bike_id,
+                    status: change.status.clone(),
+                    comment: change.comment.clone(),
+                    changed_by: change.changed_by.clone(),
+                    changed_at: NaiveDate::from_ymd(2021, 4, 18).and_hms(0, 0, 0),
+                },
+                flagged_rents: vec![],
+            }))
+        });
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::put_bike_status]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.put("/admin/bikes/1/status")
+            .header(auth::test::authorization())
+            .body(r#"{"status":"retired","comment":"Stolen"}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("{\"change\":{\"id\":1,\"bike_id\":1,\"status\":\"retired\",\"comment\":\"Stolen\",\"changed_by\":\"admin\",\"changed_at\":\"2021-04-18T00:00:00+00:00\"},\"flagged_rents\":[]}".to_string()));
+
+        let response = client.put("/admin/bikes/1/status")
+            .header(auth::test::authorization())
+            .body(r#"{"status":"stolen","comment":null}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn test_get_bike_slots() {
+        crate::database::test::setup();
+
+        bike::get_bike.mock_safe(|_, id| MockResult::Return(Ok(Bike {
+            id,
+            status: "active".to_string(),
+            buffer_before_minutes: None,
+            buffer_after_minutes: None,
+            booking_granularity: if id == 1 { "half_day" } else { "free" }.to_string(),
+            requires_approval: false,
+        })));
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::get_bike_slots]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.get("/bikes/1/slots?date=2021-05-01").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("{\"bike_id\":1,\"booking_granularity\":\"half_day\",\"slots\":[{\"start_timestamp\":\"2021-05-01T00:00:00+00:00\",\"end_timestamp\":\"2021-05-01T12:00:00+00:00\"},{\"start_timestamp\":\"2021-05-01T12:00:00+00:00\",\"end_timestamp\":\"2021-05-02T00:00:00+00:00\"}]}".to_string()));
+
+        let mut response = client.get("/bikes/2/slots?date=2021-05-01").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("{\"bike_id\":2,\"booking_granularity\":\"free\",\"slots\":[]}".to_string()));
+
+        let response = client.get("/bikes/1/slots?date=tomorrow").dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn test_upload_bike_media() {
+        crate::database::test::setup();
+        auth::test::setup();
+        env::set_var("UPLOAD_DIRECTORY", env::temp_dir().join("cbs-uploads-test"));
+
+        bike::get_bike.mock_safe(|_, id| MockResult::Return(if id == 1 {
+            Ok(Bike {
+                id,
+                status: "active".to_string(),
+                buffer_before_minutes: None,
+                buffer_after_minutes: None,
+                booking_granularity: "free".to_string(),
+                requires_approval: false,
+            })
+        } else {
+            Err(diesel::result::Error::NotFound)
+        }));
+        bike::get_bike_media.mock_safe(|_, _| MockResult::Return(Ok(vec![media(1, 0), media(2, 4)])));
+        bike::insert_bike_media.mock_safe(|_, inserted| {
+            assert_eq!(inserted.bike_id, 1);
+            assert_eq!(inserted.position, 5);
+            assert_eq!((inserted.width, inserted.height), (640, 480));
+            assert_eq!((inserted.thumbnail_width, inserted.thumbnail_height), (320, 240));
+            assert!(inserted.thumbnail_url.ends_with("-thumbnail.png"));
+
+            MockResult::Return(Ok(media(3, inserted.position)))
+        });
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::upload_bike_media]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let response = client.post("/admin/bikes/1/media")
+            .header(auth::test::authorization())
+            .header(ContentType::PNG)
+            .body(crate::media::test::png(640, 480))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let response = client.post("/admin/bikes/9/media")
+            .header(auth::test::authorization())
+            .header(ContentType::PNG)
+            .body(crate::media::test::png(640, 480))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+
+        let response = client.post("/admin/bikes/1/media")
+            .body(crate::media::test::png(640, 480))
+            .dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+    }
+
+    #[test]
+    fn test_put_bike_media_translation() {
+        crate::database::test::setup();
+        auth::test::setup();
+
+        bike::get_bike_media.mock_safe(|_, _| MockResult::Return(Ok(vec![media(3, 0)])));
+        bike::upsert_bike_media_translation.mock_safe(|_, translation| MockResult::Return(Ok(BikeMediaTranslatable {
+            id: 1,
+            bike_media_id: translation.bike_media_id,
+            locale: translation.locale.clone(),
+            alt_text: translation.alt_text.clone(),
+        })));
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::put_bike_media_translation]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.put("/admin/bikes/1/media/3/translations/en-gb")
+            .header(auth::test::authorization())
+            .body(r#"{"alt_text":"Side view"}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("{\"id\":1,\"bike_media_id\":3,\"locale\":\"en-GB\",\"alt_text\":\"Side view\"}".to_string()));
+
+        let response = client.put("/admin/bikes/1/media/4/translations/en-GB")
+            .header(auth::test::authorization())
+            .body(r#"{"alt_text":"Side view"}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
 }
\ No newline at end of file
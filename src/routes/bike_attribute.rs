@@ -0,0 +1,234 @@
+use rocket::{delete,get,post,put};
+use rocket::http::RawStr;
+use rocket_contrib::json::Json;
+
+use crate::database::DbConn;
+use crate::database::models::{AttributeType,BikeAttribute,BikeAttributeOption,BikeAttributeOptionData,BikeAttributeOptionTranslatable,BikeAttributeTranslatable,BikeAttributeValue,BikeAttributeValueData,InsertBikeAttribute,InsertBikeAttributeOption,InsertBikeAttributeOptionTranslatable,InsertBikeAttributeTranslatable,InsertBikeAttributeValue,LabelTranslationData};
+use crate::database::daos::bike_attribute;
+
+use crate::routes::auth::Admin;
+use crate::routes::bike::parse_locale;
+use crate::routes::errors::BikeError;
+
+fn ensure_deleted(count: usize) -> Result<(),BikeError> {
+    if count == 0 {
+        return Err(BikeError::NotFound(String::from("Record not found")));
+    }
+
+    Ok(())
+}
+
+/// Keys are used as query parameters when filtering bikes, so they must not
+/// collide with the other parameters of `GET /bikes`.
+fn validate_attribute(attribute: &InsertBikeAttribute) -> Result<(),BikeError> {
+    attribute.value_type.parse::<AttributeType>().map_err(BikeError::Validation)?;
+
+    let valid_key = !attribute.key.is_empty()
+        && attribute.key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        && !["locale", "available_from", "available_to"].contains(&attribute.key.as_str())
+        && !attribute.key.starts_with("min_")
+        && !attribute.key.starts_with("max_");
+
+    if !valid_key {
+        return Err(BikeError::Validation(format!("'{}' is not a valid attribute key.", attribute.key)));
+    }
+
+    Ok(())
+}
+
+#[get("/admin/bike-attributes")]
+pub fn get_bike_attributes(db: DbConn, _admin: Admin) -> Result<Json<Vec<BikeAttribute>>,BikeError> {
+    Ok(Json(bike_attribute::get_bike_attributes(&db)?))
+}
+
+#[post("/admin/bike-attributes", data = "<attribute>")]
+pub fn create_bike_attribute(db: DbConn, _admin: Admin, attribute: Json<InsertBikeAttribute>) -> Result<Json<BikeAttribute>,BikeError> {
+    validate_attribute(&attribute)?;
+
+    Ok(Json(bike_attribute::insert_bike_attribute(&db, &attribute)?))
+}
+
+#[put("/admin/bike-attributes/<id>", data = "<attribute>")]
+pub fn update_bike_attribute(db: DbConn, _admin: Admin, id: i32, attribute: Json<InsertBikeAttribute>) -> Result<Json<BikeAttribute>,BikeError> {
+    validate_attribute(&attribute)?;
+
+    if bike_attribute::get_bike_attribute(&db, id)?.value_type != attribute.value_type {
+        return Err(BikeError::Validation(String::from("The type of an attribute cannot be changed.")));
+    }
+
+    Ok(Json(bike_attribute::update_bike_attribute(&db, id, &attribute)?))
+}
+
+#[delete("/admin/bike-attributes/<id>")]
+pub fn delete_bike_attribute(db: DbConn, _admin: Admin, id: i32) -> Result<(),BikeError> {
+    ensure_deleted(bike_attribute::delete_bike_attribute(&db, id)?)
+}
+
+#[put("/admin/bike-attributes/<id>/translations/<p_locale>", data = "<data>")]
+pub fn put_bike_attribute_translation(db: DbConn, _admin: Admin, id: i32, p_locale: &RawStr, data: Json<LabelTranslationData>) -> Result<Json<BikeAttributeTranslatable>,BikeError> {
+    let translation = InsertBikeAttributeTranslatable {
+        bike_attribute_id: id,
+        locale: parse_locale(p_locale)?,
+        label: data.into_inner().label,
+    };
+
+    Ok(Json(bike_attribute::upsert_bike_attribute_translation(&db, &translation)?))
+}
+
+#[get("/admin/bike-attributes/<id>/options")]
+pub fn get_bike_attribute_options(db: DbConn, _admin: Admin, id: i32) -> Result<Json<Vec<BikeAttributeOption>>,BikeError> {
+    Ok(Json(bike_attribute::get_bike_attribute_options(&db, id)?))
+}
+
+#[post("/admin/bike-attributes/<id>/options", data = "<data>")]
+pub fn create_bike_attribute_option(db: DbConn, _admin: Admin, id: i32, data: Json<BikeAttributeOptionData>) -> Result<Json<BikeAttributeOption>,BikeError> {
+    if bike_attribute::get_bike_attribute(&db, id)?.value_type.parse::<AttributeType>() != Ok(AttributeType::Enum) {
+        return Err(BikeError::Validation(String::from("Only enum attributes have options.")));
+    }
+
+    let option = InsertBikeAttributeOption {
+        bike_attribute_id: id,
+        key: data.into_inner().key,
+    };
+
+    Ok(Json(bike_attribute::insert_bike_attribute_option(&db, &option)?))
+}
+
+#[delete("/admin/bike-attributes/<id>/options/<option_id>")]
+pub fn delete_bike_attribute_option(db: DbConn, _admin: Admin, id: i32, option_id: i32) -> Result<(),BikeError> {
+    ensure_deleted(bike_attribute::delete_bike_attribute_option(&db, id, option_id)?)
+}
+
+#[put("/admin/bike-attributes/<id>/options/<option_id>/translations/<p_locale>", data = "<data>")]
+pub fn put_bike_attribute_option_translation(db: DbConn, _admin: Admin, id: i32, option_id: i32, p_locale: &RawStr, data: Json<LabelTranslationData>) -> Result<Json<BikeAttributeOptionTranslatable>,BikeError> {
+    let locale = parse_locale(p_locale)?;
+
+    if !bike_attribute::get_bike_attribute_options(&db, id)?.iter().any(|o| o.id == option_id) {
+        return Err(BikeError::NotFound(String::from("Option not found")));
+    }
+
+    let translation = InsertBikeAttributeOptionTranslatable {
+        bike_attribute_option_id: option_id,
+        locale,
+        label: data.into_inner().label,
+    };
+
+    Ok(Json(bike_attribute::upsert_bike_attribute_option_translation(&db, &translation)?))
+}
+
+/// Sets the value of an attribute for a bike. Exactly the field matching the
+/// type of the attribute has to be given.
+#[put("/admin/bikes/<bike_id>/attributes/<id>", data = "<data>")]
+pub fn put_bike_attribute_value(db: DbConn, _admin: Admin, bike_id: i32, id: i32, data: Json<BikeAttributeValueData>) -> Result<Json<BikeAttributeValue>,BikeError> {
+    let attribute = bike_attribute::get_bike_attribute(&db, id)?;
+    let attribute_type = attribute.value_type.parse::<AttributeType>().map_err(BikeError::Validation)?;
+    let data = data.into_inner();
+
+    let valid = match attribute_type {
+        AttributeType::Number => data.number_value.map_or(false, f64::is_finite) && data.boolean_value.is_none() && data.bike_attribute_option_id.is_none(),
+        AttributeType::Boolean => data.number_value.is_none() && data.boolean_value.is_some() && data.bike_attribute_option_id.is_none(),
+        AttributeType::Enum => data.number_value.is_none() && data.boolean_value.is_none() && match data.bike_attribute_option_id {
+            Some(option_id) => bike_attribute::get_bike_attribute_options(&db, id)?.iter().any(|o| o.id == option_id),
+            None => false,
+        },
+    };
+
+    if !valid {
+        return Err(BikeError::Validation(format!("Invalid value for attribute '{}'.", attribute.key)));
+    }
+
+    let value = InsertBikeAttributeValue {
+        bike_id,
+        bike_attribute_id: id,
+        number_value: data.number_value,
+        boolean_value: data.boolean_value,
+        bike_attribute_option_id: data.bike_attribute_option_id,
+    };
+
+    Ok(Json(bike_attribute::upsert_bike_attribute_value(&db, &value)?))
+}
+
+#[delete("/admin/bikes/<bike_id>/attributes/<id>")]
+pub fn delete_bike_attribute_value(db: DbConn, _admin: Admin, bike_id: i32, id: i32) -> Result<(),BikeError> {
+    ensure_deleted(bike_attribute::delete_bike_attribute_value(&db, bike_id, id)?)
+}
+
+#[cfg(test)]
+mod test {
+    use mocktopus::mocking::Mockable;
+    use mocktopus::mocking::MockResult;
+
+    use rocket;
+    use rocket::routes;
+    use rocket::local::Client;
+    use rocket::http::Status;
+
+    use crate::database::DbConn;
+    use crate::database::daos::bike_attribute;
+    use crate::database::models::{BikeAttribute,BikeAttributeValue};
+
+    use crate::routes::auth;
+
+    fn attribute(value_type: &str) -> BikeAttribute {
+        BikeAttribute { id: 2, key: "capacity_kg".to_string(), value_type: value_type.to_string(), unit: Some("kg".to_string()), sort_priority: 0 }
+    }
+
+    #[test]
+    fn test_create_bike_attribute_with_invalid_key() {
+        crate::database::test::setup();
+        auth::test::setup();
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::create_bike_attribute]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        for body in &[
+            r#"{"key":"min_wheels","value_type":"number","unit":null,"sort_priority":0}"#,
+            r#"{"key":"locale","value_type":"boolean","unit":null,"sort_priority":0}"#,
+            r#"{"key":"wheels.front","value_type":"number","unit":null,"sort_priority":0}"#,
+            r#"{"key":"Wheels","value_type":"number","unit":null,"sort_priority":0}"#,
+            r#"{"key":"wheels","value_type":"text","unit":null,"sort_priority":0}"#,
+        ] {
+            let response = client.post("/admin/bike-attributes")
+                .header(auth::test::authorization())
+                .body(body)
+                .dispatch();
+            assert_eq!(response.status(), Status::BadRequest);
+        }
+    }
+
+    #[test]
+    fn test_put_bike_attribute_value() {
+        crate::database::test::setup();
+        auth::test::setup();
+
+        bike_attribute::get_bike_attribute.mock_safe(|_, _| MockResult::Return(Ok(attribute("number"))));
+        bike_attribute::upsert_bike_attribute_value.mock_safe(|_, value| MockResult::Return(Ok(BikeAttributeValue {
+            id: 1,
+            bike_id: value.bike_id,
+            bike_attribute_id: value.bike_attribute_id,
+            number_value: value.number_value,
+            boolean_value: value.boolean_value,
+            bike_attribute_option_id: value.bike_attribute_option_id,
+        })));
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::put_bike_attribute_value]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.put("/admin/bikes/1/attributes/2")
+            .header(auth::test::authorization())
+            .body(r#"{"number_value":80.0,"boolean_value":null,"bike_attribute_option_id":null}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("{\"id\":1,\"bike_id\":1,\"bike_attribute_id\":2,\"number_value\":80.0,\"boolean_value\":null,\"bike_attribute_option_id\":null}".to_string()));
+
+        let response = client.put("/admin/bikes/1/attributes/2")
+            .header(auth::test::authorization())
+            .body(r#"{"number_value":null,"boolean_value":true,"bike_attribute_option_id":null}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+}
@@ -3,35 +3,60 @@ use rocket::http::RawStr;
 use rocket_contrib::json;
 use rocket_contrib::json::{Json,JsonValue};
 
+use crate::config;
+use crate::config::ChallengeType;
 use crate::database::DbConn;
-use crate::database::models::ChallengeResponse;
+use crate::database::models::{ChallengeResponse,ProofOfWorkChallenge,ProofOfWorkResponse};
 use crate::database::daos::challenge;
-
-use regex::Regex;
+use crate::locale::{LanguageTag,LocalePreferences};
+use crate::proof_of_work;
 
 use crate::routes::errors::ChallengeError;
 
-#[get("/challenges/<p_locale>/random")]
-pub fn get_random_challenge(db: DbConn, p_locale: &RawStr) -> Result<JsonValue,ChallengeError> {
-    lazy_static! {
-        static ref LOCALE_REGEX: Regex = Regex::new(r"\w{2}-\w{2}").unwrap();
+fn ensure_enabled(challenge_type: ChallengeType) -> Result<(),ChallengeError> {
+    if !config::is_challenge_type_enabled(challenge_type) {
+        return Err(ChallengeError::Disabled(String::from("This challenge type is not enabled.")));
     }
 
-    if !LOCALE_REGEX.is_match(p_locale) {
-        return Err(ChallengeError::Parse(String::from("No valid locale passed.")));
-    }
+    Ok(())
+}
 
-    let challenge = challenge::get_random_challenge(&db, &p_locale.to_string())?;
+#[get("/challenges/types")]
+pub fn get_challenge_types() -> Json<Vec<ChallengeType>> {
+    Json(config::enabled_challenge_types())
+}
+
+fn random_challenge(db: &DbConn, preferences: &LocalePreferences) -> Result<JsonValue,ChallengeError> {
+    ensure_enabled(ChallengeType::Question)?;
+
+    let challenge = challenge::get_random_challenge(db, preferences)?;
 
     Ok(json!({
         "token_challenge_id": challenge.token_challenge_id,
+        "locale": challenge.locale,
         "question": challenge.question,
         "url": challenge.url,
     }))
 }
 
+#[get("/challenges/<p_locale>/random")]
+pub fn get_random_challenge(db: DbConn, p_locale: &RawStr) -> Result<JsonValue,ChallengeError> {
+    let tag = p_locale.url_decode()
+        .map_err(|_| ChallengeError::Parse(String::from("No valid locale passed.")))?
+        .parse::<LanguageTag>()
+        .map_err(|_| ChallengeError::Parse(String::from("No valid locale passed.")))?;
+
+    random_challenge(&db, &LocalePreferences::new(vec![tag]))
+}
+
+#[get("/challenges/random")]
+pub fn get_random_challenge_for_locale(db: DbConn, locale: LocalePreferences) -> Result<JsonValue,ChallengeError> {
+    random_challenge(&db, &locale)
+}
+
 #[post("/challenges/test", data = "<challenge_response>")]
 pub fn test_challenge(db: DbConn, challenge_response: Json<ChallengeResponse>) -> Result<JsonValue,ChallengeError> {
+    ensure_enabled(ChallengeType::Question)?;
 
     let token = challenge::test_challenge(&db, &challenge_response)?;
 
@@ -40,6 +65,26 @@ pub fn test_challenge(db: DbConn, challenge_response: Json<ChallengeResponse>) -
     }))
 }
 
+#[get("/challenges/proof-of-work")]
+pub fn get_proof_of_work_challenge() -> Result<Json<ProofOfWorkChallenge>,ChallengeError> {
+    ensure_enabled(ChallengeType::ProofOfWork)?;
+
+    Ok(Json(proof_of_work::issue_challenge()?))
+}
+
+#[post("/challenges/proof-of-work/test", data = "<response>")]
+pub fn test_proof_of_work(db: DbConn, response: Json<ProofOfWorkResponse>) -> Result<JsonValue,ChallengeError> {
+    ensure_enabled(ChallengeType::ProofOfWork)?;
+
+    proof_of_work::verify_solution(&response)?;
+
+    let token = challenge::redeem_proof_of_work(&db, &response.challenge.nonce, response.challenge.expires_at)?;
+
+    Ok(json!({
+        "token": token.uuid
+    }))
+}
+
 #[cfg(test)]
 mod test {
     use mocktopus::mocking::Mockable;
@@ -53,16 +98,27 @@ mod test {
     use rocket::local::Client;
     use rocket::http::Status;
 
+    use std::env;
+
     use crate::database::DbConn;
     use crate::database::daos::challenge;
     use crate::database::models::{Token,TokenChallengeTranslatable};
+    use crate::proof_of_work;
+
+    fn setup() {
+        crate::database::test::setup();
+
+        env::set_var("CHALLENGE_TYPES", "question,proof_of_work");
+        env::set_var("PROOF_OF_WORK_SECRET", "test secret");
+        env::set_var("PROOF_OF_WORK_DIFFICULTY", "8");
+    }
 
     #[test]
     fn test_get_random_challenge() {
-        crate::database::test::setup();
+        setup();
 
-        challenge::get_random_challenge.mock_safe(|_, locale| {
-            assert_eq!(locale, "de-DE");
+        challenge::get_random_challenge.mock_safe(|_, preferences| {
+            assert_eq!(preferences.tags[0].to_string(), "de-DE");
             MockResult::Return(Ok(TokenChallengeTranslatable {
                 id: 1,
                 token_challenge_id: 1,
@@ -80,12 +136,25 @@ mod test {
 
         let mut response = client.get("/challenges/de-DE/random").dispatch();
         assert_eq!(response.status(), Status::Ok);
-        assert_eq!(response.body_string(), Some("{\"question\":\"The question\",\"token_challenge_id\":1,\"url\":null}".to_string()));
+        assert_eq!(response.body_string(), Some("{\"locale\":\"de-DE\",\"question\":\"The question\",\"token_challenge_id\":1,\"url\":null}".to_string()));
+    }
+
+    #[test]
+    fn test_get_random_challenge_with_invalid_locale() {
+        setup();
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::get_random_challenge]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let response = client.get("/challenges/xxde-DEyy/random").dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
     }
 
     #[test]
     fn test_test_challenge() {
-        crate::database::test::setup();
+        setup();
 
         let uuid = "00a791f1-68b8-457c-82d9-a060f48efbae";
         let uuid = Uuid::parse_str(uuid).unwrap();
@@ -95,6 +164,7 @@ mod test {
                 id: 1,
                 uuid: uuid,
                 created_at: NaiveDate::from_ymd(2021, 4, 18).and_hms(0, 0, 0),
+                rider_hash: None,
             }))
         });
 
@@ -109,4 +179,79 @@ mod test {
         assert_eq!(response.status(), Status::Ok);
         assert_eq!(response.body_string(), Some(format!("{{\"token\":\"{}\"}}", "00a791f1-68b8-457c-82d9-a060f48efbae")));
     }
+
+    #[test]
+    fn test_get_challenge_types() {
+        setup();
+
+        let rocket = rocket::ignite()
+            .mount("/", routes![super::get_challenge_types]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.get("/challenges/types").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("[\"question\",\"proof_of_work\"]".to_string()));
+    }
+
+    #[test]
+    fn test_test_proof_of_work() {
+        setup();
+
+        let uuid = "00a791f1-68b8-457c-82d9-a060f48efbae";
+        let uuid = Uuid::parse_str(uuid).unwrap();
+
+        challenge::redeem_proof_of_work.mock_safe(move |_, nonce, _| {
+            assert_eq!(nonce.len(), 32);
+
+            MockResult::Return(Ok(Token {
+                id: 1,
+                uuid,
+                created_at: NaiveDate::from_ymd(2021, 4, 18).and_hms(0, 0, 0),
+                rider_hash: None,
+            }))
+        });
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::get_proof_of_work_challenge, super::test_proof_of_work]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.get("/challenges/proof-of-work").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let challenge: serde_json::Value = serde_json::from_str(&response.body_string().unwrap()).unwrap();
+        let nonce = challenge["nonce"].as_str().unwrap();
+        let solution = (0u64..).map(|i| i.to_string())
+            .find(|solution| proof_of_work::is_solution(nonce, solution, 8))
+            .unwrap();
+
+        let mut response = client.post("/challenges/proof-of-work/test")
+            .body(format!("{{\"challenge\":{},\"solution\":\"{}\"}}", challenge, solution))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some(format!("{{\"token\":\"{}\"}}", "00a791f1-68b8-457c-82d9-a060f48efbae")));
+    }
+
+    #[test]
+    fn test_test_proof_of_work_with_wrong_solution() {
+        setup();
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::get_proof_of_work_challenge, super::test_proof_of_work]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.get("/challenges/proof-of-work").dispatch();
+
+        let challenge: serde_json::Value = serde_json::from_str(&response.body_string().unwrap()).unwrap();
+        let nonce = challenge["nonce"].as_str().unwrap();
+        let solution = (0u64..).map(|i| i.to_string())
+            .find(|solution| !proof_of_work::is_solution(nonce, solution, 8))
+            .unwrap();
+
+        let response = client.post("/challenges/proof-of-work/test")
+            .body(format!("{{\"challenge\":{},\"solution\":\"{}\"}}", challenge, solution))
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
 }
\ No newline at end of file
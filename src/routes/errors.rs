@@ -3,6 +3,7 @@ use std::fmt;
 use rocket::Responder;
 
 use crate::mailer::errors::MailerError;
+use crate::media::errors::MediaError;
 
 // http://web.mit.edu/rust-lang_v1.25/arch/amd64_ubuntu1404/share/doc/rust/html/book/first-edition/error-handling.html#error-handling-with-a-custom-type
 
@@ -17,6 +18,10 @@ pub enum RentError {
     Validation(String),
     #[response(status = 400)]
     MailError(String),
+    #[response(status = 410)]
+    TokenExpired(String),
+    #[response(status = 409)]
+    TokenUsed(String),
 }
 
 impl fmt::Display for RentError {
@@ -26,6 +31,8 @@ impl fmt::Display for RentError {
             RentError::Database(ref err) => write!(f, "{}", err),
             RentError::Validation(ref err) => write!(f, "{}", err),
             RentError::MailError(ref err) => write!(f, "{}", err),
+            RentError::TokenExpired(ref err) => write!(f, "{}", err),
+            RentError::TokenUsed(ref err) => write!(f, "{}", err),
         }
     }
 }
@@ -63,6 +70,8 @@ pub enum ChallengeError {
     Database(String),
     #[response(status = 400)]
     Validation(String),
+    #[response(status = 404)]
+    Disabled(String),
 }
 
 impl fmt::Display for ChallengeError {
@@ -71,6 +80,7 @@ impl fmt::Display for ChallengeError {
             ChallengeError::Parse(ref err) => write!(f, "{}", err),
             ChallengeError::Database(ref err) => write!(f, "{}", err),
             ChallengeError::Validation(ref err) => write!(f, "{}", err),
+            ChallengeError::Disabled(ref err) => write!(f, "{}", err),
         }
     }
 }
@@ -79,4 +89,292 @@ impl From<diesel::result::Error> for ChallengeError {
     fn from(err: diesel::result::Error) -> ChallengeError {
         ChallengeError::Database(err.to_string())
     }
-}
\ No newline at end of file
+}
+
+#[derive(Responder)]
+#[derive(Debug)]
+pub enum InviteError {
+    #[response(status = 400)]
+    Database(String),
+    #[response(status = 400)]
+    Validation(String),
+}
+
+impl fmt::Display for InviteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            InviteError::Database(ref err) => write!(f, "{}", err),
+            InviteError::Validation(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<diesel::result::Error> for InviteError {
+    fn from(err: diesel::result::Error) -> InviteError {
+        InviteError::Database(err.to_string())
+    }
+}
+
+#[derive(Responder)]
+#[derive(Debug)]
+pub enum SupporterError {
+    #[response(status = 400)]
+    Database(String),
+    #[response(status = 400)]
+    Validation(String),
+    #[response(status = 404)]
+    NotFound(String),
+    Media(MediaError),
+}
+
+impl fmt::Display for SupporterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SupporterError::Database(ref err) => write!(f, "{}", err),
+            SupporterError::Validation(ref err) => write!(f, "{}", err),
+            SupporterError::NotFound(ref err) => write!(f, "{}", err),
+            SupporterError::Media(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<diesel::result::Error> for SupporterError {
+    fn from(err: diesel::result::Error) -> SupporterError {
+        match err {
+            diesel::result::Error::NotFound => SupporterError::NotFound(err.to_string()),
+            _ => SupporterError::Database(err.to_string()),
+        }
+    }
+}
+
+impl From<MediaError> for SupporterError {
+    fn from(err: MediaError) -> SupporterError {
+        SupporterError::Media(err)
+    }
+}
+
+#[derive(Responder)]
+#[derive(Debug)]
+pub enum BikeError {
+    #[response(status = 400)]
+    Database(String),
+    #[response(status = 400)]
+    Validation(String),
+    #[response(status = 404)]
+    NotFound(String),
+    Media(MediaError),
+}
+
+impl fmt::Display for BikeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BikeError::Database(ref err) => write!(f, "{}", err),
+            BikeError::Validation(ref err) => write!(f, "{}", err),
+            BikeError::NotFound(ref err) => write!(f, "{}", err),
+            BikeError::Media(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<diesel::result::Error> for BikeError {
+    fn from(err: diesel::result::Error) -> BikeError {
+        match err {
+            diesel::result::Error::NotFound => BikeError::NotFound(err.to_string()),
+            _ => BikeError::Database(err.to_string()),
+        }
+    }
+}
+
+impl From<MediaError> for BikeError {
+    fn from(err: MediaError) -> BikeError {
+        BikeError::Media(err)
+    }
+}
+
+#[derive(Responder)]
+#[derive(Debug)]
+pub enum IncidentError {
+    #[response(status = 400)]
+    Parse(String),
+    #[response(status = 400)]
+    Database(String),
+    #[response(status = 400)]
+    Validation(String),
+    #[response(status = 404)]
+    NotFound(String),
+    Media(MediaError),
+}
+
+impl fmt::Display for IncidentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            IncidentError::Parse(ref err) => write!(f, "{}", err),
+            IncidentError::Database(ref err) => write!(f, "{}", err),
+            IncidentError::Validation(ref err) => write!(f, "{}", err),
+            IncidentError::NotFound(ref err) => write!(f, "{}", err),
+            IncidentError::Media(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<uuid::ParseError> for IncidentError {
+    fn from(err: uuid::ParseError) -> IncidentError {
+        IncidentError::Parse(err.to_string())
+    }
+}
+
+impl From<diesel::result::Error> for IncidentError {
+    fn from(err: diesel::result::Error) -> IncidentError {
+        match err {
+            diesel::result::Error::NotFound => IncidentError::NotFound(err.to_string()),
+            _ => IncidentError::Database(err.to_string()),
+        }
+    }
+}
+
+impl From<MediaError> for IncidentError {
+    fn from(err: MediaError) -> IncidentError {
+        IncidentError::Media(err)
+    }
+}
+
+#[derive(Responder)]
+#[derive(Debug)]
+pub enum FeedbackError {
+    #[response(status = 400)]
+    Parse(String),
+    #[response(status = 400)]
+    Database(String),
+    #[response(status = 400)]
+    Validation(String),
+    #[response(status = 404)]
+    NotFound(String),
+    #[response(status = 409)]
+    AlreadySubmitted(String),
+}
+
+impl fmt::Display for FeedbackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FeedbackError::Parse(ref err) => write!(f, "{}", err),
+            FeedbackError::Database(ref err) => write!(f, "{}", err),
+            FeedbackError::Validation(ref err) => write!(f, "{}", err),
+            FeedbackError::NotFound(ref err) => write!(f, "{}", err),
+            FeedbackError::AlreadySubmitted(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<uuid::ParseError> for FeedbackError {
+    fn from(err: uuid::ParseError) -> FeedbackError {
+        FeedbackError::Parse(err.to_string())
+    }
+}
+
+impl From<diesel::result::Error> for FeedbackError {
+    fn from(err: diesel::result::Error) -> FeedbackError {
+        match err {
+            diesel::result::Error::NotFound => FeedbackError::NotFound(err.to_string()),
+            diesel::result::Error::DatabaseError(diesel::result::DatabaseErrorKind::UniqueViolation, _) =>
+                FeedbackError::AlreadySubmitted(String::from("Feedback for this rent has already been submitted.")),
+            _ => FeedbackError::Database(err.to_string()),
+        }
+    }
+}
+
+#[derive(Responder)]
+#[derive(Debug)]
+pub enum AccessoryError {
+    #[response(status = 400)]
+    Database(String),
+    #[response(status = 400)]
+    Validation(String),
+    #[response(status = 404)]
+    NotFound(String),
+}
+
+impl fmt::Display for AccessoryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AccessoryError::Database(ref err) => write!(f, "{}", err),
+            AccessoryError::Validation(ref err) => write!(f, "{}", err),
+            AccessoryError::NotFound(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<diesel::result::Error> for AccessoryError {
+    fn from(err: diesel::result::Error) -> AccessoryError {
+        match err {
+            diesel::result::Error::NotFound => AccessoryError::NotFound(err.to_string()),
+            _ => AccessoryError::Database(err.to_string()),
+        }
+    }
+}
+
+#[derive(Responder)]
+#[derive(Debug)]
+pub enum WaitlistError {
+    #[response(status = 400)]
+    Parse(String),
+    #[response(status = 400)]
+    Database(String),
+    #[response(status = 400)]
+    Validation(String),
+    #[response(status = 404)]
+    NotFound(String),
+    #[response(status = 400)]
+    MailError(String),
+    #[response(status = 410)]
+    TokenExpired(String),
+    #[response(status = 409)]
+    TokenUsed(String),
+}
+
+impl fmt::Display for WaitlistError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            WaitlistError::Parse(ref err) => write!(f, "{}", err),
+            WaitlistError::Database(ref err) => write!(f, "{}", err),
+            WaitlistError::Validation(ref err) => write!(f, "{}", err),
+            WaitlistError::NotFound(ref err) => write!(f, "{}", err),
+            WaitlistError::MailError(ref err) => write!(f, "{}", err),
+            WaitlistError::TokenExpired(ref err) => write!(f, "{}", err),
+            WaitlistError::TokenUsed(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<uuid::ParseError> for WaitlistError {
+    fn from(err: uuid::ParseError) -> WaitlistError {
+        WaitlistError::Parse(err.to_string())
+    }
+}
+
+impl From<diesel::result::Error> for WaitlistError {
+    fn from(err: diesel::result::Error) -> WaitlistError {
+        match err {
+            diesel::result::Error::NotFound => WaitlistError::NotFound(err.to_string()),
+            _ => WaitlistError::Database(err.to_string()),
+        }
+    }
+}
+
+impl From<MailerError> for WaitlistError {
+    fn from(err: MailerError) -> WaitlistError {
+        WaitlistError::MailError(err.to_string())
+    }
+}
+
+impl From<RentError> for WaitlistError {
+    fn from(err: RentError) -> WaitlistError {
+        match err {
+            RentError::Parse(err) => WaitlistError::Parse(err),
+            RentError::Database(err) => WaitlistError::Database(err),
+            RentError::Validation(err) => WaitlistError::Validation(err),
+            RentError::MailError(err) => WaitlistError::MailError(err),
+            RentError::TokenExpired(err) => WaitlistError::TokenExpired(err),
+            RentError::TokenUsed(err) => WaitlistError::TokenUsed(err),
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use chrono::Utc;
+
+use rocket::{get,post};
+use rocket::http::RawStr;
+use rocket_contrib::json::Json;
+
+use crate::database::DbConn;
+use crate::database::models::{BikeFeedbackSummary,InsertRentFeedback,Rent,RentFeedback,RentFeedbackData};
+use crate::database::daos::{feedback,rent};
+
+use crate::routes::auth::Admin;
+use crate::routes::errors::FeedbackError;
+
+/// The rent of the token for the bike. A series books the bike once per
+/// occurrence, its feedback is given per occurrence instead.
+fn rent_of_token(db: &DbConn, token: &RawStr, bike_id: Option<i32>) -> Result<Rent,FeedbackError> {
+    let bike_id = bike_id.ok_or_else(|| FeedbackError::Validation(String::from("The bike of the booking has to be given.")))?;
+    let mut rents = rent::get_rents_by_token(db, &::uuid::Uuid::parse_str(token)?)?
+        .into_iter()
+        .filter(|rent| rent.bike_id == bike_id)
+        .collect::<Vec<Rent>>();
+
+    match rents.len() {
+        0 => Err(FeedbackError::NotFound(String::from("Rent not found"))),
+        1 => Ok(rents.remove(0)),
+        _ => Err(FeedbackError::Validation(String::from("The booking is a series, feedback is given per occurrence."))),
+    }
+}
+
+fn give_feedback(db: &DbConn, rent: &Rent, data: RentFeedbackData) -> Result<RentFeedback,FeedbackError> {
+    if rent.revocation_timestamp.is_some() || rent.end_timestamp > Utc::now().naive_utc() {
+        return Err(FeedbackError::Validation(String::from("Feedback can only be given after the rent has ended.")));
+    }
+
+    if data.rating < 1 || data.rating > 5 {
+        return Err(FeedbackError::Validation(String::from("The rating has to be between 1 and 5.")));
+    }
+
+    if data.distance_km.map_or(false, |distance| !distance.is_finite() || distance < 0.0) {
+        return Err(FeedbackError::Validation(String::from("The distance cannot be negative.")));
+    }
+
+    let feedback = InsertRentFeedback {
+        rent_id: rent.id,
+        rating: data.rating,
+        comment: data.comment.filter(|comment| !comment.trim().is_empty()),
+        distance_km: data.distance_km,
+    };
+
+    Ok(feedback::insert_feedback(db, &feedback)?)
+}
+
+/// Feedback can be given once per rent after it has ended, i.e. once for
+/// each bike of the booking.
+#[post("/rents/<token>/feedback", data = "<data>")]
+pub fn submit_feedback(db: DbConn, token: &RawStr, data: Json<RentFeedbackData>) -> Result<Json<RentFeedback>,FeedbackError> {
+    let rent = rent_of_token(&db, token, data.bike_id)?;
+
+    Ok(Json(give_feedback(&db, &rent, data.into_inner())?))
+}
+
+/// Feedback for one occurrence of a series.
+#[post("/rents/<token>/occurrences/<rent_id>/feedback", data = "<data>")]
+pub fn submit_occurrence_feedback(db: DbConn, token: &RawStr, rent_id: i32, data: Json<RentFeedbackData>) -> Result<Json<RentFeedback>,FeedbackError> {
+    let rent = rent::get_rent_by_token(&db, &::uuid::Uuid::parse_str(token)?, rent_id)?;
+
+    Ok(Json(give_feedback(&db, &rent, data.into_inner())?))
+}
+
+#[get("/admin/feedback")]
+pub fn get_feedback_summaries(db: DbConn, _admin: Admin) -> Result<Json<Vec<BikeFeedbackSummary>>,FeedbackError> {
+    Ok(Json(feedback::get_feedback_summaries(&db)?))
+}
+
+#[get("/admin/bikes/<id>/feedback")]
+pub fn get_bike_feedback(db: DbConn, _admin: Admin, id: i32) -> Result<Json<Vec<RentFeedback>>,FeedbackError> {
+    Ok(Json(feedback::get_feedback_by_bike(&db, id)?))
+}
+
+#[cfg(test)]
+mod test {
+    use mocktopus::mocking::Mockable;
+    use mocktopus::mocking::MockResult;
+
+    use chrono::{Duration,NaiveDate,Utc};
+
+    use diesel::result::{DatabaseErrorKind,Error};
+
+    use rocket;
+    use rocket::routes;
+    use rocket::local::Client;
+    use rocket::http::Status;
+
+    use crate::database::DbConn;
+    use crate::database::daos::{feedback,rent};
+    use crate::database::models::{Rent,RentFeedback};
+
+    fn rent(end_in_days: i64) -> Rent {
+        let end = Utc::now().naive_utc() + Duration::days(end_in_days);
+
+        Rent {
+            id: 7,
+            token_id: 1,
+            bike_id: 3,
+            created_at: end,
+            start_timestamp: end - Duration::days(1),
+            end_timestamp: end,
+            revocation_timestamp: None,
+            flagged_at: None,
+            flag_reason: None,
+        }
+    }
+
+    #[test]
+    fn test_submit_feedback() {
+        crate::database::test::setup();
+
+        rent::get_rents_by_token.mock_safe(|_, _| MockResult::Return(Ok(vec![rent(-1)])));
+        feedback::insert_feedback.mock_safe(|_, feedback| MockResult::Return(Ok(RentFeedback {
+            id: 1,
+            rent_id: feedback.rent_id,
+            rating: feedback.rating,
+            comment: feedback.comment.clone(),
+            distance_km: feedback.distance_km,
+            created_at: NaiveDate::from_ymd(2021, 4, 18).and_hms(0, 0, 0),
+        })));
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::submit_feedback]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.post("/rents/00a791f1-68b8-457c-82d9-a060f48efbae/feedback")
+            .body(r#"{"bike_id":3,"rating":5,"comment":"Great bike!","distance_km":12.5}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("{\"id\":1,\"rent_id\":7,\"rating\":5,\"comment\":\"Great bike!\",\"distance_km\":12.5,\"created_at\":\"2021-04-18T00:00:00+00:00\"}".to_string()));
+
+        for body in &[
+            r#"{"bike_id":3,"rating":0,"comment":null,"distance_km":null}"#,
+            r#"{"bike_id":3,"rating":6,"comment":null,"distance_km":null}"#,
+            r#"{"bike_id":3,"rating":3,"comment":null,"distance_km":-1.0}"#,
+        ] {
+            let response = client.post("/rents/00a791f1-68b8-457c-82d9-a060f48efbae/feedback")
+                .body(body)
+                .dispatch();
+            assert_eq!(response.status(), Status::BadRequest);
+        }
+
+        let response = client.post("/rents/00a791f1-68b8-457c-82d9-a060f48efbae/feedback")
+            .body(r#"{"bike_id":4,"rating":5,"comment":null,"distance_km":null}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+
+        let response = client.post("/rents/00a791f1-68b8-457c-82d9-a060f48efbae/feedback")
+            .body(r#"{"rating":5,"comment":null,"distance_km":null}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn test_submit_feedback_for_series() {
+        crate::database::test::setup();
+
+        rent::get_rents_by_token.mock_safe(|_, _| MockResult::Return(Ok(vec![rent(-8), Rent { id: 8, ..rent(-1) }])));
+        rent::get_rent_by_token.mock_safe(|_, _, rent_id| MockResult::Return(match rent_id {
+            7 => Ok(rent(-8)),
+            8 => Ok(Rent { id: 8, ..rent(-1) }),
+            _ => Err(Error::NotFound),
+        }));
+        feedback::insert_feedback.mock_safe(|_, feedback| MockResult::Return(Ok(RentFeedback {
+            id: 1,
+            rent_id: feedback.rent_id,
+            rating: feedback.rating,
+            comment: None,
+            distance_km: None,
+            created_at: NaiveDate::from_ymd(2021, 4, 18).and_hms(0, 0, 0),
+        })));
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::submit_feedback, super::submit_occurrence_feedback]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let response = client.post("/rents/00a791f1-68b8-457c-82d9-a060f48efbae/feedback")
+            .body(r#"{"bike_id":3,"rating":4,"comment":null,"distance_km":null}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+
+        let mut response = client.post("/rents/00a791f1-68b8-457c-82d9-a060f48efbae/occurrences/8/feedback")
+            .body(r#"{"rating":4,"comment":null,"distance_km":null}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("{\"id\":1,\"rent_id\":8,\"rating\":4,\"comment\":null,\"distance_km\":null,\"created_at\":\"2021-04-18T00:00:00+00:00\"}".to_string()));
+
+        let response = client.post("/rents/00a791f1-68b8-457c-82d9-a060f48efbae/occurrences/9/feedback")
+            .body(r#"{"rating":4,"comment":null,"distance_km":null}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_submit_feedback_twice() {
+        crate::database::test::setup();
+
+        rent::get_rents_by_token.mock_safe(|_, _| MockResult::Return(Ok(vec![rent(-1)])));
+        feedback::insert_feedback.mock_safe(|_, _| MockResult::Return(Err(Error::DatabaseError(DatabaseErrorKind::UniqueViolation, Box::new(String::from("duplicate key"))))));
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::submit_feedback]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let response = client.post("/rents/00a791f1-68b8-457c-82d9-a060f48efbae/feedback")
+            .body(r#"{"bike_id":3,"rating":4,"comment":null,"distance_km":null}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Conflict);
+    }
+
+    #[test]
+    fn test_submit_feedback_before_end() {
+        crate::database::test::setup();
+
+        rent::get_rents_by_token.mock_safe(|_, _| MockResult::Return(Ok(vec![rent(1)])));
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::submit_feedback]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let response = client.post("/rents/00a791f1-68b8-457c-82d9-a060f48efbae/feedback")
+            .body(r#"{"bike_id":3,"rating":4,"comment":null,"distance_km":null}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+}
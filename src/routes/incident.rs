@@ -0,0 +1,321 @@
+use chrono::{Duration,Utc};
+
+use log::error;
+
+use rocket::{get,post};
+use rocket::Data;
+use rocket::http::RawStr;
+use rocket_contrib::json::Json;
+
+use crate::config;
+use crate::database::DbConn;
+use crate::database::models::{BikeIncident,BikeIncidentData,BikeIncidentPhoto,BikeIncidentWithPhotos,IncidentCategory,InsertBikeIncident,InsertBikeIncidentPhoto,Rent};
+use crate::database::daos::{incident,rent};
+use crate::mailer;
+use crate::media;
+use crate::media::ImageVariant;
+use crate::media::storage::FileStorage;
+
+use crate::routes::auth::Admin;
+use crate::routes::errors::IncidentError;
+
+/// Stores the incident and tells the operators about it. A failing mail does
+/// not fail the report, the incident is listed for the admins either way.
+fn file_incident(db: &DbConn, rent: &Rent, data: BikeIncidentData, reported_by: Option<String>) -> Result<BikeIncident,IncidentError> {
+    let category = data.category.parse::<IncidentCategory>().map_err(IncidentError::Validation)?;
+
+    if data.description.trim().is_empty() {
+        return Err(IncidentError::Validation(String::from("An incident needs a description.")));
+    }
+
+    let inserted = incident::insert_incident(db, &InsertBikeIncident {
+        rent_id: rent.id,
+        bike_id: rent.bike_id,
+        category: category.as_str().to_string(),
+        description: data.description,
+        reported_by,
+    })?;
+
+    if mailer::is_mail_config_available() {
+        if let Err(err) = mailer::send_incident_mail(&inserted) {
+            error!("Sending the mail for incident {} failed: {}", inserted.id, err);
+        }
+    }
+
+    Ok(inserted)
+}
+
+fn store_photo(db: &DbConn, incident_id: i32, data: Data) -> Result<BikeIncidentPhoto,IncidentError> {
+    let storage = FileStorage::from_config();
+    let upload = media::read_upload(data)?;
+    let image = media::store_image(&storage, "incidents", &upload, &[
+        ImageVariant { name: "web", max_width: config::bike_image_max_width(), max_height: config::bike_image_max_height() },
+        ImageVariant { name: "thumbnail", max_width: config::thumbnail_max_width(), max_height: config::thumbnail_max_height() },
+    ])?;
+    let (web, thumbnail) = (&image.variants[0], &image.variants[1]);
+
+    let photo = incident::add_incident_photo(db, &InsertBikeIncidentPhoto {
+        bike_incident_id: incident_id,
+        url: web.url.clone(),
+        width: web.width as i32,
+        height: web.height as i32,
+        thumbnail_url: thumbnail.url.clone(),
+        thumbnail_width: thumbnail.width as i32,
+        thumbnail_height: thumbnail.height as i32,
+    }, config::incident_photo_limit());
+
+    if photo.is_err() {
+        media::discard_image(&storage, &image);
+    }
+
+    photo
+}
+
+/// Incidents can be reported as long as the rent has started, did not end
+/// too long ago and was not revoked.
+fn check_reportable(rent: &Rent) -> Result<(),IncidentError> {
+    if rent.revocation_timestamp.is_some() || rent.start_timestamp > Utc::now().naive_utc() {
+        return Err(IncidentError::Validation(String::from("Incidents can only be reported for running or past rents.")));
+    }
+
+    if rent.end_timestamp + Duration::days(config::incident_report_days()) < Utc::now().naive_utc() {
+        return Err(IncidentError::Validation(format!("Incidents can only be reported up to {} days after the rent.", config::incident_report_days())));
+    }
+
+    Ok(())
+}
+
+/// The rent of the token for the bike, without a bike the first rent of the
+/// booking is used. A series books a bike once per occurrence, its incidents
+/// are reported per occurrence instead.
+fn rent_of_token(db: &DbConn, token: &RawStr, bike_id: Option<i32>) -> Result<Rent,IncidentError> {
+    let rents = rent::get_rents_by_token(db, &::uuid::Uuid::parse_str(token)?)?
+        .into_iter()
+        .filter(|rent| bike_id.map_or(true, |bike_id| rent.bike_id == bike_id))
+        .collect::<Vec<Rent>>();
+
+    let mut bike_ids = rents.iter().map(|rent| rent.bike_id).collect::<Vec<i32>>();
+    bike_ids.sort_unstable();
+    bike_ids.dedup();
+
+    if bike_ids.len() < rents.len() {
+        return Err(IncidentError::Validation(String::from("The booking is a series, incidents are reported per occurrence.")));
+    }
+
+    let rent = rents.into_iter()
+        .next()
+        .ok_or_else(|| IncidentError::NotFound(String::from("Rent not found")))?;
+    check_reportable(&rent)?;
+
+    Ok(rent)
+}
+
+#[post("/rents/<token>/incidents", data = "<data>")]
+pub fn report_incident(db: DbConn, token: &RawStr, data: Json<BikeIncidentData>) -> Result<Json<BikeIncident>,IncidentError> {
+    let rent = rent_of_token(&db, token, data.bike_id)?;
+
+    Ok(Json(file_incident(&db, &rent, data.into_inner(), None)?))
+}
+
+/// Reports an incident for one occurrence of a series.
+#[post("/rents/<token>/occurrences/<rent_id>/incidents", data = "<data>")]
+pub fn report_occurrence_incident(db: DbConn, token: &RawStr, rent_id: i32, data: Json<BikeIncidentData>) -> Result<Json<BikeIncident>,IncidentError> {
+    let rent = rent::get_rent_by_token(&db, &::uuid::Uuid::parse_str(token)?, rent_id)?;
+    check_reportable(&rent)?;
+
+    Ok(Json(file_incident(&db, &rent, data.into_inner(), None)?))
+}
+
+#[post("/rents/<token>/incidents/<incident_id>/photos", data = "<data>")]
+pub fn upload_incident_photo(db: DbConn, token: &RawStr, incident_id: i32, data: Data) -> Result<Json<BikeIncidentPhoto>,IncidentError> {
+    let incident = incident::get_incident(&db, incident_id)?;
+    let rent = rent::get_rent_by_token(&db, &::uuid::Uuid::parse_str(token)?, incident.rent_id)?;
+    check_reportable(&rent)?;
+
+    Ok(Json(store_photo(&db, incident_id, data)?))
+}
+
+#[get("/admin/incidents")]
+pub fn get_incidents(db: DbConn, _admin: Admin) -> Result<Json<Vec<BikeIncidentWithPhotos>>,IncidentError> {
+    Ok(Json(incident::get_incidents(&db)?))
+}
+
+/// Incidents filed by staff, e.g. when a bike is handed back damaged.
+#[post("/admin/rents/<id>/incidents", data = "<data>")]
+pub fn create_incident(db: DbConn, admin: Admin, id: i32, data: Json<BikeIncidentData>) -> Result<Json<BikeIncident>,IncidentError> {
+    let rent = rent::get_rent(&db, id)?;
+
+    Ok(Json(file_incident(&db, &rent, data.into_inner(), Some(admin.name))?))
+}
+
+#[post("/admin/incidents/<incident_id>/photos", data = "<data>")]
+pub fn upload_admin_incident_photo(db: DbConn, _admin: Admin, incident_id: i32, data: Data) -> Result<Json<BikeIncidentPhoto>,IncidentError> {
+    incident::get_incident(&db, incident_id)?;
+
+    Ok(Json(store_photo(&db, incident_id, data)?))
+}
+
+#[cfg(test)]
+mod test {
+    use mocktopus::mocking::Mockable;
+    use mocktopus::mocking::MockResult;
+
+    use chrono::{Duration,NaiveDate,Utc};
+
+    use diesel::result::Error;
+
+    use rocket;
+    use rocket::routes;
+    use rocket::local::Client;
+    use rocket::http::Status;
+
+    use crate::database::DbConn;
+    use crate::database::daos::{incident,rent};
+    use crate::database::models::{BikeIncident,Rent};
+    use crate::mailer;
+
+    fn rent(start_in_days: i64, revoked: bool) -> Rent {
+        let start = Utc::now().naive_utc() + Duration::days(start_in_days);
+
+        Rent {
+            id: 7,
+            token_id: 1,
+            bike_id: 3,
+            created_at: start,
+            start_timestamp: start,
+            end_timestamp: start + Duration::days(1),
+            revocation_timestamp: if revoked { Some(start) } else { None },
+            flagged_at: None,
+            flag_reason: None,
+        }
+    }
+
+    #[test]
+    fn test_report_incident() {
+        crate::database::test::setup();
+
+        rent::get_rents_by_token.mock_safe(|_, _| MockResult::Return(Ok(vec![rent(-1, false)])));
+        mailer::is_mail_config_available.mock_safe(|| MockResult::Return(false));
+        incident::insert_incident.mock_safe(|_, incident| {
+            assert_eq!(incident.rent_id, 7);
+            assert_eq!(incident.bike_id, 3);
+            assert_eq!(incident.reported_by, None);
+
+            MockResult::Return(Ok(BikeIncident {
+                id: 1,
+                rent_id: incident.rent_id,
+                bike_id: incident.bike_id,
+                category: incident.category.clone(),
+                description: incident.description.clone(),
+                reported_by: None,
+                created_at: NaiveDate::from_ymd(2021, 4, 18).and_hms(0, 0, 0),
+            }))
+        });
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::report_incident]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.post("/rents/00a791f1-68b8-457c-82d9-a060f48efbae/incidents")
+            .body(r#"{"category":"flat_tyre","description":"Rear tyre is flat."}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("{\"id\":1,\"rent_id\":7,\"bike_id\":3,\"category\":\"flat_tyre\",\"description\":\"Rear tyre is flat.\",\"reported_by\":null,\"created_at\":\"2021-04-18T00:00:00+00:00\"}".to_string()));
+
+        let response = client.post("/rents/00a791f1-68b8-457c-82d9-a060f48efbae/incidents")
+            .body(r#"{"category":"alien_abduction","description":"Gone."}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+
+        let response = client.post("/rents/00a791f1-68b8-457c-82d9-a060f48efbae/incidents")
+            .body(r#"{"category":"crash","description":"Crashed.","bike_id":4}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+
+        let response = client.post("/rents/not-a-token/incidents")
+            .body(r#"{"category":"crash","description":"Crashed."}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn test_report_incident_for_series() {
+        crate::database::test::setup();
+
+        rent::get_rents_by_token.mock_safe(|_, _| MockResult::Return(Ok(vec![rent(-8, false), Rent { id: 8, ..rent(-1, false) }])));
+        rent::get_rent_by_token.mock_safe(|_, _, rent_id| MockResult::Return(match rent_id {
+            7 => Ok(rent(-8, false)),
+            8 => Ok(Rent { id: 8, ..rent(-1, false) }),
+            _ => Err(Error::NotFound),
+        }));
+        mailer::is_mail_config_available.mock_safe(|| MockResult::Return(false));
+        incident::insert_incident.mock_safe(|_, incident| {
+            assert_eq!(incident.rent_id, 8);
+
+            MockResult::Return(Ok(BikeIncident {
+                id: 1,
+                rent_id: incident.rent_id,
+                bike_id: incident.bike_id,
+                category: incident.category.clone(),
+                description: incident.description.clone(),
+                reported_by: None,
+                created_at: NaiveDate::from_ymd(2021, 4, 18).and_hms(0, 0, 0),
+            }))
+        });
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::report_incident, super::report_occurrence_incident]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let response = client.post("/rents/00a791f1-68b8-457c-82d9-a060f48efbae/incidents")
+            .body(r#"{"category":"crash","description":"Crashed.","bike_id":3}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+
+        let response = client.post("/rents/00a791f1-68b8-457c-82d9-a060f48efbae/occurrences/8/incidents")
+            .body(r#"{"category":"crash","description":"Crashed."}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let response = client.post("/rents/00a791f1-68b8-457c-82d9-a060f48efbae/occurrences/9/incidents")
+            .body(r#"{"category":"crash","description":"Crashed."}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_report_incident_for_future_rent() {
+        crate::database::test::setup();
+
+        rent::get_rents_by_token.mock_safe(|_, _| MockResult::Return(Ok(vec![rent(2, false)])));
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::report_incident]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let response = client.post("/rents/00a791f1-68b8-457c-82d9-a060f48efbae/incidents")
+            .body(r#"{"category":"crash","description":"Crashed."}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn test_report_incident_long_after_rent() {
+        crate::database::test::setup();
+
+        rent::get_rents_by_token.mock_safe(|_, _| MockResult::Return(Ok(vec![rent(-30, false)])));
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::report_incident]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let response = client.post("/rents/00a791f1-68b8-457c-82d9-a060f48efbae/incidents")
+            .body(r#"{"category":"crash","description":"Crashed."}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+}
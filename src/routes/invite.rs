@@ -0,0 +1,161 @@
+use rocket::{get,post};
+use rocket_contrib::json;
+use rocket_contrib::json::{Json,JsonValue};
+
+use crate::database::DbConn;
+use crate::database::models::{InviteCode,InviteCodeRedemptionRequest,InviteCodeRedemptionWithRent,InviteCodeRequest,InviteCodeWithUsage};
+use crate::database::daos::invite;
+
+use crate::routes::auth::Admin;
+use crate::routes::errors::InviteError;
+
+#[post("/admin/invite-codes", data = "<request>")]
+pub fn create_invite_codes(db: DbConn, admin: Admin, request: Json<InviteCodeRequest>) -> Result<Json<Vec<InviteCode>>,InviteError> {
+    let data = invite::insert_invite_codes(&db, &request, &admin.name)?;
+
+    Ok(Json(data))
+}
+
+#[get("/admin/invite-codes")]
+pub fn get_invite_codes(db: DbConn, _admin: Admin) -> Result<Json<Vec<InviteCodeWithUsage>>,InviteError> {
+    let data = invite::get_invite_codes(&db)?;
+
+    Ok(Json(data))
+}
+
+#[get("/admin/invite-codes/<id>/redemptions")]
+pub fn get_redemptions(db: DbConn, _admin: Admin, id: i32) -> Result<Json<Vec<InviteCodeRedemptionWithRent>>,InviteError> {
+    let data = invite::get_redemptions(&db, id)?;
+
+    Ok(Json(data))
+}
+
+#[post("/invite-codes/redeem", data = "<request>")]
+pub fn redeem_invite_code(db: DbConn, request: Json<InviteCodeRedemptionRequest>) -> Result<JsonValue,InviteError> {
+    let token = invite::redeem_invite_code(&db, &request.code)?;
+
+    Ok(json!({
+        "token": token.uuid
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use mocktopus::mocking::Mockable;
+    use mocktopus::mocking::MockResult;
+
+    use chrono::NaiveDate;
+    use uuid::Uuid;
+
+    use rocket;
+    use rocket::routes;
+    use rocket::local::Client;
+    use rocket::http::Status;
+
+    use crate::database::DbConn;
+    use crate::database::daos::invite;
+    use crate::database::models::{InviteCode,Token};
+
+    use crate::routes::auth;
+    use crate::routes::errors::InviteError;
+
+    #[test]
+    fn test_create_invite_codes() {
+        crate::database::test::setup();
+        auth::test::setup();
+
+        invite::insert_invite_codes.mock_safe(|_, request, author| {
+            assert_eq!(request.group_name, "Kindergarten");
+            assert_eq!(request.count, Some(1));
+            assert_eq!(author, "admin");
+
+            MockResult::Return(Ok(vec![InviteCode {
+                id: 1,
+                code: "ABCDEFGHJK".to_string(),
+                group_name: request.group_name.clone(),
+                usage_limit: request.usage_limit,
+                valid_from: None,
+                valid_until: None,
+                created_by: author.to_string(),
+                created_at: NaiveDate::from_ymd(2021, 7, 1).and_hms(0, 0, 0),
+            }]))
+        });
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::create_invite_codes]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.post("/admin/invite-codes")
+            .header(auth::test::authorization())
+            .body(r#"{"group_name":"Kindergarten","usage_limit":5,"valid_from":null,"valid_until":null,"count":1}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("[{\"id\":1,\"code\":\"ABCDEFGHJK\",\"group_name\":\"Kindergarten\",\"usage_limit\":5,\"valid_from\":null,\"valid_until\":null,\"created_by\":\"admin\",\"created_at\":\"2021-07-01T00:00:00+00:00\"}]".to_string()));
+    }
+
+    #[test]
+    fn test_create_invite_codes_without_authorization() {
+        crate::database::test::setup();
+        auth::test::setup();
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::create_invite_codes]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let response = client.post("/admin/invite-codes")
+            .body(r#"{"group_name":"Kindergarten","usage_limit":5,"valid_from":null,"valid_until":null,"count":1}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Unauthorized);
+    }
+
+    #[test]
+    fn test_redeem_invite_code() {
+        crate::database::test::setup();
+
+        let uuid = Uuid::parse_str("00a791f1-68b8-457c-82d9-a060f48efbae").unwrap();
+
+        invite::redeem_invite_code.mock_safe(move |_, code| {
+            assert_eq!(code, "ABCDEFGHJK");
+
+            MockResult::Return(Ok(Token {
+                id: 1,
+                uuid,
+                created_at: NaiveDate::from_ymd(2021, 7, 1).and_hms(0, 0, 0),
+                rider_hash: None,
+            }))
+        });
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::redeem_invite_code]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.post("/invite-codes/redeem")
+            .body(r#"{"code":"ABCDEFGHJK"}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some(format!("{{\"token\":\"{}\"}}", uuid)));
+    }
+
+    #[test]
+    fn test_redeem_invite_code_used_up() {
+        crate::database::test::setup();
+
+        invite::redeem_invite_code.mock_safe(|_, _| {
+            MockResult::Return(Err(InviteError::Validation("The invite code has been used up.".to_string())))
+        });
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::redeem_invite_code]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.post("/invite-codes/redeem")
+            .body(r#"{"code":"ABCDEFGHJK"}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+        assert_eq!(response.body_string(), Some("The invite code has been used up.".to_string()));
+    }
+}
@@ -0,0 +1,266 @@
+use std::cmp::Reverse;
+
+use chrono::Utc;
+
+use rocket::{delete,get,post,put};
+use rocket_contrib::json::Json;
+
+use crate::database::DbConn;
+use crate::database::models::{BikeServiceInterval,BikeServiceIntervalData,BikeWorkOrder,BikeWorkOrderData,InsertBikeServiceInterval,InsertBikeWorkOrder,MaintenanceHistoryEntry,ServiceDue};
+use crate::database::daos::{incident,maintenance};
+
+use crate::routes::auth::Admin;
+use crate::routes::errors::BikeError;
+
+fn ensure_deleted(count: usize) -> Result<(),BikeError> {
+    if count == 0 {
+        return Err(BikeError::NotFound(String::from("Record not found")));
+    }
+
+    Ok(())
+}
+
+fn service_interval(bike_id: i32, data: BikeServiceIntervalData) -> Result<InsertBikeServiceInterval,BikeError> {
+    let positive = |value: Option<i32>| value.map_or(true, |value| value > 0);
+
+    if data.interval_days.is_none() && data.interval_rents.is_none() {
+        return Err(BikeError::Validation(String::from("A service interval needs a number of days or rents.")));
+    }
+
+    if !positive(data.interval_days) || !positive(data.interval_rents) {
+        return Err(BikeError::Validation(String::from("Service intervals have to be positive.")));
+    }
+
+    Ok(InsertBikeServiceInterval {
+        bike_id,
+        title: data.title,
+        interval_days: data.interval_days,
+        interval_rents: data.interval_rents,
+        blocks_bookings: data.blocks_bookings,
+    })
+}
+
+/// Services which are due for any bike.
+#[get("/admin/maintenance/due")]
+pub fn get_due_services(db: DbConn, _admin: Admin) -> Result<Json<Vec<ServiceDue>>,BikeError> {
+    Ok(Json(maintenance::get_due_services(&db, Utc::now().naive_utc())?))
+}
+
+#[get("/admin/bikes/<id>/service-intervals")]
+pub fn get_service_intervals(db: DbConn, _admin: Admin, id: i32) -> Result<Json<Vec<BikeServiceInterval>>,BikeError> {
+    Ok(Json(maintenance::get_service_intervals(&db, id)?))
+}
+
+#[post("/admin/bikes/<id>/service-intervals", data = "<data>")]
+pub fn create_service_interval(db: DbConn, _admin: Admin, id: i32, data: Json<BikeServiceIntervalData>) -> Result<Json<BikeServiceInterval>,BikeError> {
+    let interval = service_interval(id, data.into_inner())?;
+
+    Ok(Json(maintenance::insert_service_interval(&db, &interval)?))
+}
+
+#[put("/admin/bikes/<id>/service-intervals/<interval_id>", data = "<data>")]
+pub fn update_service_interval(db: DbConn, _admin: Admin, id: i32, interval_id: i32, data: Json<BikeServiceIntervalData>) -> Result<Json<BikeServiceInterval>,BikeError> {
+    let interval = service_interval(id, data.into_inner())?;
+
+    Ok(Json(maintenance::update_service_interval(&db, interval_id, &interval)?))
+}
+
+#[delete("/admin/bikes/<id>/service-intervals/<interval_id>")]
+pub fn delete_service_interval(db: DbConn, _admin: Admin, id: i32, interval_id: i32) -> Result<(),BikeError> {
+    ensure_deleted(maintenance::delete_service_interval(&db, id, interval_id)?)
+}
+
+#[get("/admin/bikes/<id>/work-orders")]
+pub fn get_work_orders(db: DbConn, _admin: Admin, id: i32) -> Result<Json<Vec<BikeWorkOrder>>,BikeError> {
+    Ok(Json(maintenance::get_work_orders(&db, id)?))
+}
+
+#[post("/admin/bikes/<id>/work-orders", data = "<data>")]
+pub fn create_work_order(db: DbConn, admin: Admin, id: i32, data: Json<BikeWorkOrderData>) -> Result<Json<BikeWorkOrder>,BikeError> {
+    let data = data.into_inner();
+
+    if data.description.trim().is_empty() {
+        return Err(BikeError::Validation(String::from("A work order needs a description.")));
+    }
+
+    if data.cost_cents.map_or(false, |cost| cost < 0) {
+        return Err(BikeError::Validation(String::from("The cost cannot be negative.")));
+    }
+
+    if let Some(interval_id) = data.bike_service_interval_id {
+        if !maintenance::get_service_intervals(&db, id)?.iter().any(|i| i.id == interval_id) {
+            return Err(BikeError::NotFound(String::from("Service interval not found")));
+        }
+    }
+
+    let work_order = InsertBikeWorkOrder {
+        bike_id: id,
+        bike_service_interval_id: data.bike_service_interval_id,
+        description: data.description,
+        parts: data.parts,
+        cost_cents: data.cost_cents,
+        performed_by: data.performed_by.unwrap_or(admin.name),
+        performed_at: data.performed_at.unwrap_or_else(|| Utc::now().naive_utc()),
+    };
+
+    Ok(Json(maintenance::insert_work_order(&db, &work_order)?))
+}
+
+#[delete("/admin/bikes/<id>/work-orders/<work_order_id>")]
+pub fn delete_work_order(db: DbConn, _admin: Admin, id: i32, work_order_id: i32) -> Result<(),BikeError> {
+    ensure_deleted(maintenance::delete_work_order(&db, id, work_order_id)?)
+}
+
+/// Work orders and reported incidents of the bike, newest first.
+#[get("/admin/bikes/<id>/maintenance-history")]
+pub fn get_maintenance_history(db: DbConn, _admin: Admin, id: i32) -> Result<Json<Vec<MaintenanceHistoryEntry>>,BikeError> {
+    let mut history = maintenance::get_work_orders(&db, id)?.into_iter()
+        .map(MaintenanceHistoryEntry::WorkOrder)
+        .chain(incident::get_incidents_by_bike(&db, id)?.into_iter().map(MaintenanceHistoryEntry::Incident))
+        .collect::<Vec<_>>();
+
+    history.sort_by_key(|entry| Reverse(entry.timestamp()));
+
+    Ok(Json(history))
+}
+
+#[cfg(test)]
+mod test {
+    use mocktopus::mocking::Mockable;
+    use mocktopus::mocking::MockResult;
+
+    use chrono::NaiveDate;
+
+    use rocket;
+    use rocket::routes;
+    use rocket::local::Client;
+    use rocket::http::Status;
+
+    use crate::database::DbConn;
+    use crate::database::daos::{incident,maintenance};
+    use crate::database::models::{BikeIncident,BikeIncidentWithPhotos,BikeServiceInterval,BikeWorkOrder};
+
+    use crate::routes::auth;
+
+    #[test]
+    fn test_create_service_interval() {
+        crate::database::test::setup();
+        auth::test::setup();
+
+        maintenance::insert_service_interval.mock_safe(|_, interval| MockResult::Return(Ok(BikeServiceInterval {
+            id: 1,
+            bike_id: interval.bike_id,
+            title: interval.title.clone(),
+            interval_days: interval.interval_days,
+            interval_rents: interval.interval_rents,
+            blocks_bookings: interval.blocks_bookings,
+            created_at: NaiveDate::from_ymd(2021, 4, 18).and_hms(0, 0, 0),
+            notified_at: None,
+        })));
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::create_service_interval]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.post("/admin/bikes/1/service-intervals")
+            .header(auth::test::authorization())
+            .body(r#"{"title":"Brakes","interval_days":null,"interval_rents":20,"blocks_bookings":true}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("{\"id\":1,\"bike_id\":1,\"title\":\"Brakes\",\"interval_days\":null,\"interval_rents\":20,\"blocks_bookings\":true,\"created_at\":\"2021-04-18T00:00:00+00:00\",\"notified_at\":null}".to_string()));
+
+        for body in &[
+            r#"{"title":"Brakes","interval_days":null,"interval_rents":null}"#,
+            r#"{"title":"Brakes","interval_days":0,"interval_rents":null}"#,
+        ] {
+            let response = client.post("/admin/bikes/1/service-intervals")
+                .header(auth::test::authorization())
+                .body(body)
+                .dispatch();
+            assert_eq!(response.status(), Status::BadRequest);
+        }
+    }
+
+    #[test]
+    fn test_create_work_order() {
+        crate::database::test::setup();
+        auth::test::setup();
+
+        maintenance::get_service_intervals.mock_safe(|_, _| MockResult::Return(Ok(vec![])));
+        maintenance::insert_work_order.mock_safe(|_, work_order| {
+            assert_eq!(work_order.performed_by, "admin");
+
+            MockResult::Return(Ok(BikeWorkOrder {
+                id: 1,
+                bike_id: work_order.bike_id,
+                bike_service_interval_id: work_order.bike_service_interval_id,
+                description: work_order.description.clone(),
+                parts: work_order.parts.clone(),
+                cost_cents: work_order.cost_cents,
+                performed_by: work_order.performed_by.clone(),
+                performed_at: work_order.performed_at,
+                created_at: work_order.performed_at,
+            }))
+        });
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::create_work_order]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.post("/admin/bikes/1/work-orders")
+            .header(auth::test::authorization())
+            .body(r#"{"bike_service_interval_id":null,"description":"Replaced chain","parts":"Chain","cost_cents":2500,"performed_by":null,"performed_at":"2021-04-18T10:00:00"}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("{\"id\":1,\"bike_id\":1,\"bike_service_interval_id\":null,\"description\":\"Replaced chain\",\"parts\":\"Chain\",\"cost_cents\":2500,\"performed_by\":\"admin\",\"performed_at\":\"2021-04-18T10:00:00+00:00\",\"created_at\":\"2021-04-18T10:00:00+00:00\"}".to_string()));
+
+        let response = client.post("/admin/bikes/1/work-orders")
+            .header(auth::test::authorization())
+            .body(r#"{"bike_service_interval_id":3,"description":"Brakes","parts":null,"cost_cents":null,"performed_by":"Kim","performed_at":null}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_get_maintenance_history() {
+        crate::database::test::setup();
+        auth::test::setup();
+
+        maintenance::get_work_orders.mock_safe(|_, bike_id| MockResult::Return(Ok(vec![BikeWorkOrder {
+            id: 2,
+            bike_id,
+            bike_service_interval_id: None,
+            description: "Patched tyre".to_string(),
+            parts: None,
+            cost_cents: None,
+            performed_by: "Kim".to_string(),
+            performed_at: NaiveDate::from_ymd(2021, 4, 20).and_hms(0, 0, 0),
+            created_at: NaiveDate::from_ymd(2021, 4, 20).and_hms(0, 0, 0),
+        }])));
+        incident::get_incidents_by_bike.mock_safe(|_, bike_id| MockResult::Return(Ok(vec![BikeIncidentWithPhotos {
+            incident: BikeIncident {
+                id: 5,
+                rent_id: 7,
+                bike_id,
+                category: "flat_tyre".to_string(),
+                description: "Flat".to_string(),
+                reported_by: None,
+                created_at: NaiveDate::from_ymd(2021, 4, 19).and_hms(0, 0, 0),
+            },
+            photos: vec![],
+        }])));
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::get_maintenance_history]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.get("/admin/bikes/1/maintenance-history")
+            .header(auth::test::authorization())
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("[{\"type\":\"work_order\",\"id\":2,\"bike_id\":1,\"bike_service_interval_id\":null,\"description\":\"Patched tyre\",\"parts\":null,\"cost_cents\":null,\"performed_by\":\"Kim\",\"performed_at\":\"2021-04-20T00:00:00+00:00\",\"created_at\":\"2021-04-20T00:00:00+00:00\"},{\"type\":\"incident\",\"id\":5,\"rent_id\":7,\"bike_id\":1,\"category\":\"flat_tyre\",\"description\":\"Flat\",\"reported_by\":null,\"created_at\":\"2021-04-19T00:00:00+00:00\",\"photos\":[]}]".to_string()));
+    }
+}
@@ -0,0 +1,72 @@
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use rocket::get;
+use rocket::http::ContentType;
+use rocket::request::Request;
+use rocket::response::{self,Responder,Response};
+
+use crate::media::errors::MediaError;
+use crate::media::storage::{FileStorage,Storage};
+
+/// A stored file. Names of stored files are derived from their content, so
+/// clients and proxies may cache them indefinitely.
+pub struct CachedFile {
+    content_type: ContentType,
+    data: Vec<u8>,
+}
+
+impl<'r> Responder<'r> for CachedFile {
+    fn respond_to(self, _: &Request) -> response::Result<'r> {
+        Response::build()
+            .header(self.content_type)
+            .raw_header("Cache-Control", "public, max-age=31536000, immutable")
+            .sized_body(Cursor::new(self.data))
+            .ok()
+    }
+}
+
+#[get("/uploads/<path..>")]
+pub fn get_upload(path: PathBuf) -> Result<CachedFile, MediaError> {
+    let key = path.to_str()
+        .ok_or_else(|| MediaError::NotFound(String::from("File not found")))?;
+    let content_type = path.extension()
+        .and_then(|extension| extension.to_str())
+        .and_then(ContentType::from_extension)
+        .unwrap_or(ContentType::Binary);
+
+    Ok(CachedFile {
+        content_type,
+        data: FileStorage::from_config().load(key)?,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+
+    use rocket;
+    use rocket::routes;
+    use rocket::local::Client;
+    use rocket::http::{ContentType,Status};
+
+    use crate::media::storage::{FileStorage,Storage};
+
+    #[test]
+    fn test_get_upload() {
+        env::set_var("UPLOAD_DIRECTORY", env::temp_dir().join("cbs-uploads-test"));
+        FileStorage::from_config().store("logos/served.png", b"png").unwrap();
+
+        let rocket = rocket::ignite().mount("/", routes![super::get_upload]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.get("/uploads/logos/served.png").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.content_type(), Some(ContentType::PNG));
+        assert_eq!(response.headers().get_one("Cache-Control"), Some("public, max-age=31536000, immutable"));
+        assert_eq!(response.body_bytes(), Some(b"png".to_vec()));
+
+        let response = client.get("/uploads/logos/missing.png").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+}
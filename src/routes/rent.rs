@@ -1,22 +1,21 @@
-use rocket::{get,post};
+use rocket::{delete,get,post};
 use rocket::http::RawStr;
 use rocket_contrib::json;
 use rocket_contrib::json::{Json,JsonValue};
 
-use chrono::prelude::DateTime;
-
 use crate::database::DbConn;
 use crate::database::models::*;
-use crate::database::daos::rent;
+use crate::database::daos::{hold,rent,rent_series};
 use crate::mailer;
+use crate::timezone;
 
 use crate::routes::errors::RentError;
+use crate::routes::waitlist;
 
 #[get("/rents?<as_of>")]
 pub fn get_rents(db: DbConn, as_of: Option<String>) -> Result<Json<Vec<Rent>>,RentError> {
     let as_of = as_of.unwrap_or("1970-01-01T00:00:00.000Z".to_string());
-    let as_of = DateTime::parse_from_rfc3339(&as_of)?;
-    let as_of = as_of.naive_utc();
+    let as_of = timezone::parse_timestamp(&as_of).map_err(RentError::Validation)?;
 
     let data = rent::get_rents(&db, &as_of)?;
 
@@ -29,11 +28,18 @@ pub fn book(db: DbConn, booking: Json<Booking>) -> Result<JsonValue,RentError> {
 
     let result = rent::insert_booking(&db, booking);
 
-    if result.is_ok() {
-        mailer::send_rent_mail(booking)?;
+    if let Ok(receipt) = result {
+        mailer::send_rent_mail(booking, receipt.approval_status)?;
+
+        if let (Some(email), Some(verification)) = (booking.email.as_ref(), receipt.verification.as_ref()) {
+            mailer::send_verification_mail(email, verification)?;
+        }
 
         Ok(json!({
-            "token": booking.token
+            "token": booking.token,
+            "approval_status": receipt.approval_status,
+            "email_verification": receipt.verification.map(|_| VerificationStatus::Pending),
+            "quota": receipt.quota
         }))
     } else {
         Err(result.err().unwrap())
@@ -44,7 +50,61 @@ pub fn book(db: DbConn, booking: Json<Booking>) -> Result<JsonValue,RentError> {
 pub fn revoke_booking(db: DbConn, token: &RawStr) -> Result<(),RentError> {
     let parsed_token = ::uuid::Uuid::parse_str(token)?;
 
-    rent::revoke_booking(&db, &parsed_token)
+    rent::revoke_booking(&db, &parsed_token)?;
+    waitlist::notify_waitlist(&db);
+
+    Ok(())
+}
+
+/// Keeps the slot free for a few minutes, the booking takes it with `hold_id`.
+#[post("/holds", data = "<request>")]
+pub fn create_hold(db: DbConn, request: Json<HoldRequest>) -> Result<Json<RentHold>,RentError> {
+    Ok(Json(hold::create_hold(&db, &request)?))
+}
+
+#[delete("/holds/<hold_id>")]
+pub fn release_hold(db: DbConn, hold_id: &RawStr) -> Result<(),RentError> {
+    let parsed_hold_id = ::uuid::Uuid::parse_str(hold_id)?;
+
+    if hold::delete_hold(&db, &parsed_hold_id)? == 0 {
+        return Err(RentError::Validation(String::from("The hold is not known.")));
+    }
+
+    Ok(())
+}
+
+/// Books the free occurrences of a recurring booking and reports the others.
+#[post("/rent-series", data = "<booking>")]
+pub fn book_series(db: DbConn, booking: Json<RecurringBooking>) -> Result<Json<RentSeriesBooking>,RentError> {
+    let result = rent_series::insert_recurring_booking(&db, &booking)?;
+
+    if mailer::is_mail_config_available() {
+        mailer::send_series_mail(&booking, &result)?;
+
+        if let (Some(email), Some(verification)) = (booking.email.as_ref(), result.verification.as_ref()) {
+            mailer::send_verification_mail(email, verification)?;
+        }
+    }
+
+    Ok(Json(result))
+}
+
+#[get("/rent-series/<token>")]
+pub fn get_series(db: DbConn, token: &RawStr) -> Result<Json<RentSeriesWithRents>,RentError> {
+    let parsed_token = ::uuid::Uuid::parse_str(token)?;
+
+    Ok(Json(rent_series::get_series_by_token(&db, &parsed_token)?))
+}
+
+/// Revokes one occurrence of a series, `/rents/<token>/revoke` revokes all.
+#[post("/rents/<token>/occurrences/<rent_id>/revoke")]
+pub fn revoke_occurrence(db: DbConn, token: &RawStr, rent_id: i32) -> Result<(),RentError> {
+    let parsed_token = ::uuid::Uuid::parse_str(token)?;
+
+    rent_series::revoke_occurrence(&db, &parsed_token, rent_id)?;
+    waitlist::notify_waitlist(&db);
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -60,13 +120,30 @@ mod test {
     use rocket::local::Client;
     use rocket::http::Status;
 
+    use chrono::NaiveDate;
+
     use crate::database::DbConn;
-    use crate::database::daos::rent;
+    use crate::database::daos::{hold,rent,rent_series};
+    use crate::database::models::{ApprovalStatus,BookingReceipt,EmailVerification,RentHold,RentSeries,RentSeriesBooking,RentSeriesWithRents,VerificationStatus};
 
     use crate::mailer;
 
     use crate::routes::errors::RentError;
 
+    fn email_verification(status: VerificationStatus) -> EmailVerification {
+        EmailVerification {
+            id: 1,
+            token_id: 2,
+            uuid: ::uuid::Uuid::parse_str("5d1a2f76-0c6e-4b8e-9f1c-2b7e3c4d5a6b").unwrap(),
+            encrypted_email: "".to_string(),
+            short_token: Some("ABC123".to_string()),
+            status: status.as_str().to_string(),
+            expires_at: NaiveDate::from_ymd(2021, 4, 19).and_hms(1, 0, 0),
+            verified_at: None,
+            created_at: NaiveDate::from_ymd(2021, 4, 19).and_hms(0, 0, 0),
+        }
+    }
+
     #[test]
     fn test_get_rents_without_timestamp() {
         crate::database::test::setup();
@@ -120,10 +197,14 @@ mod test {
         let uuid = "00a791f1-68b8-457c-82d9-a060f48efbae";
 
         rent::insert_booking.mock_safe(|_, _| {
-            MockResult::Return(Ok(()))
+            MockResult::Return(Ok(BookingReceipt {
+                approval_status: ApprovalStatus::Approved,
+                quota: None,
+                verification: None,
+            }))
         });
 
-        mailer::send_rent_mail.mock_safe(|_| {
+        mailer::send_rent_mail.mock_safe(|_, _| {
             MockResult::Return(Ok(Response::new(
                 Code {
                     category: Category::Information,
@@ -143,7 +224,7 @@ mod test {
             .body(format!(r#"{{"token":"{}","bike_id": 1,"start_timestamp": "2021-04-19T00:00:00.000","end_timestamp": "2021-04-19T00:00:00.000","encrypted_details": "","short_token": "","email": null}}"#, uuid))
             .dispatch();
         assert_eq!(response.status(), Status::Ok);
-        assert_eq!(response.body_string(), Some(format!("{{\"token\":\"{}\"}}", uuid).to_string()));
+        assert_eq!(response.body_string(), Some(format!("{{\"approval_status\":\"approved\",\"email_verification\":null,\"quota\":null,\"token\":\"{}\"}}", uuid).to_string()));
     }
 
     #[test]
@@ -153,11 +234,26 @@ mod test {
         let uuid = "00a791f1-68b8-457c-82d9-a060f48efbae";
 
         rent::insert_booking.mock_safe(|_, _| {
-            MockResult::Return(Ok(()))
+            MockResult::Return(Ok(BookingReceipt {
+                approval_status: ApprovalStatus::Approved,
+                quota: None,
+                verification: Some(email_verification(VerificationStatus::Pending)),
+            }))
         });
 
-        mailer::send_rent_mail.mock_safe(|b| {
-            assert_eq!(b.email.as_ref().unwrap(), "someone@somewhere.near");
+        mailer::send_rent_mail.mock_safe(|_, _| {
+            MockResult::Return(Ok(Response::new(
+                Code {
+                    category: Category::Information,
+                    detail: Detail::Zero,
+                    severity: Severity::PositiveCompletion,
+                },
+                vec![]
+            )))
+        });
+
+        mailer::send_verification_mail.mock_safe(|email, _| {
+            assert_eq!(email, "someone@somewhere.near");
             MockResult::Return(Ok(Response::new(
                 Code {
                     category: Category::Information,
@@ -177,7 +273,7 @@ mod test {
             .body(format!(r#"{{"token":"{}","bike_id": 1,"start_timestamp": "2021-04-19T00:00:00.000","end_timestamp": "2021-04-19T00:00:00.000","encrypted_details": "","short_token": "","email": "someone@somewhere.near"}}"#, uuid))
             .dispatch();
         assert_eq!(response.status(), Status::Ok);
-        assert_eq!(response.body_string(), Some(format!("{{\"token\":\"{}\"}}", uuid).to_string()));
+        assert_eq!(response.body_string(), Some(format!("{{\"approval_status\":\"approved\",\"email_verification\":\"pending\",\"quota\":null,\"token\":\"{}\"}}", uuid).to_string()));
     }
 
     #[test]
@@ -208,6 +304,7 @@ mod test {
 
         let uuid = "00a791f1-68b8-457c-82d9-a060f48efbae";
 
+        mailer::is_mail_config_available.mock_safe(|| MockResult::Return(false));
         rent::revoke_booking.mock_safe(move |_, token| {
             assert_eq!(uuid, token.to_string());
             MockResult::Return(Ok(()))
@@ -221,4 +318,101 @@ mod test {
         let response = client.post(format!("/rents/{}/revoke", uuid)).dispatch();
         assert_eq!(response.status(), Status::Ok);
     }
+
+    #[test]
+    fn test_book_series() {
+        crate::database::test::setup();
+
+        let uuid = "00a791f1-68b8-457c-82d9-a060f48efbae";
+
+        rent_series::insert_recurring_booking.mock_safe(|_, booking| {
+            assert_eq!(booking.frequency, "weekly");
+            assert_eq!(booking.occurrence_count, Some(4));
+
+            MockResult::Return(Ok(RentSeriesBooking {
+                series: RentSeriesWithRents {
+                    series: RentSeries {
+                        id: 1,
+                        token_id: 2,
+                        bike_id: booking.bike_id,
+                        frequency: booking.frequency.clone(),
+                        start_timestamp: booking.start_timestamp,
+                        end_timestamp: booking.end_timestamp,
+                        until_date: booking.until_date,
+                        occurrence_count: booking.occurrence_count,
+                        created_at: NaiveDate::from_ymd(2021, 4, 18).and_hms(0, 0, 0),
+                    },
+                    rents: vec![],
+                },
+                conflicts: vec![],
+                approval_status: ApprovalStatus::Pending,
+                quota: None,
+                verification: None,
+            }))
+        });
+
+        mailer::is_mail_config_available.mock_safe(|| MockResult::Return(false));
+        mailer::send_series_mail.mock_safe(|_, _| panic!("No mail can be sent without a configuration"));
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::book_series]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.post("/rent-series")
+            .body(format!(r#"{{"token":"{}","bike_id": 1,"start_timestamp": "2021-04-20T14:00:00","end_timestamp": "2021-04-20T18:00:00","encrypted_details": "","short_token": "","email": null,"frequency": "weekly","until_date": null,"occurrence_count": 4,"hold_id": null}}"#, uuid))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("{\"id\":1,\"token_id\":2,\"bike_id\":1,\"frequency\":\"weekly\",\"start_timestamp\":\"2021-04-20T14:00:00+00:00\",\"end_timestamp\":\"2021-04-20T18:00:00+00:00\",\"until_date\":null,\"occurrence_count\":4,\"created_at\":\"2021-04-18T00:00:00+00:00\",\"rents\":[],\"conflicts\":[],\"approval_status\":\"pending\",\"quota\":null}".to_string()));
+    }
+
+    #[test]
+    fn test_revoke_occurrence() {
+        crate::database::test::setup();
+
+        let uuid = "00a791f1-68b8-457c-82d9-a060f48efbae";
+
+        mailer::is_mail_config_available.mock_safe(|| MockResult::Return(false));
+        rent_series::revoke_occurrence.mock_safe(move |_, token, rent_id| {
+            assert_eq!(uuid, token.to_string());
+            assert_eq!(rent_id, 5);
+            MockResult::Return(Ok(()))
+        });
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::revoke_occurrence]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let response = client.post(format!("/rents/{}/occurrences/5/revoke", uuid)).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn test_create_hold() {
+        crate::database::test::setup();
+
+        hold::create_hold.mock_safe(|_, request| MockResult::Return(Ok(RentHold {
+            id: 1,
+            uuid: ::uuid::Uuid::parse_str("00a791f1-68b8-457c-82d9-a060f48efbae").unwrap(),
+            bike_id: request.bike_id,
+            start_timestamp: request.start_timestamp,
+            end_timestamp: request.end_timestamp,
+            expires_at: NaiveDate::from_ymd(2021, 4, 18).and_hms(0, 15, 0),
+            waitlist_entry_id: None,
+            token_id: Some(2),
+            created_at: NaiveDate::from_ymd(2021, 4, 18).and_hms(0, 0, 0),
+        })));
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::create_hold]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.post("/holds")
+            .body(r#"{"token":"20a791f1-68b8-457c-82d9-a060f48efbae","bike_id":1,"start_timestamp":"2021-04-20T08:00:00","end_timestamp":"2021-04-20T18:00:00"}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("{\"id\":1,\"uuid\":\"00a791f1-68b8-457c-82d9-a060f48efbae\",\"bike_id\":1,\"start_timestamp\":\"2021-04-20T08:00:00+00:00\",\"end_timestamp\":\"2021-04-20T18:00:00+00:00\",\"expires_at\":\"2021-04-18T00:15:00+00:00\",\"waitlist_entry_id\":null,\"token_id\":2,\"created_at\":\"2021-04-18T00:00:00+00:00\"}".to_string()));
+    }
 }
\ No newline at end of file
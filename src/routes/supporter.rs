@@ -1,38 +1,210 @@
-use rocket::get;
+use std::convert::TryFrom;
+
+use rocket::{delete,get,post,put,FromForm};
+use rocket::Data;
+use rocket::http::RawStr;
+use rocket::request::LenientForm;
 use rocket_contrib::json::Json;
 
+use crate::config;
 use crate::database::DbConn;
-use crate::database::models::SupporterWithTypeAndTranslatable;
+use crate::database::models::{InsertSupporter,InsertSupporterTranslatable,InsertSupporterType,MissingTranslations,Supporter,SupporterTranslatable,SupporterTranslationData,SupporterType,SupporterWithTypeAndTranslatable};
 use crate::database::daos::supporter;
+use crate::locale;
+use crate::locale::LocalePreferences;
+use crate::media;
+use crate::media::{ImageVariant,StoredVariant};
+use crate::media::storage::FileStorage;
+
+use crate::routes::auth::Admin;
+use crate::routes::errors::SupporterError;
+
+#[derive(FromForm)]
+pub struct SupporterFilter {
+    #[form(field = "type")]
+    pub supporter_type: Option<String>,
+}
+
+fn ensure_deleted(count: usize) -> Result<(),SupporterError> {
+    if count == 0 {
+        return Err(SupporterError::NotFound(String::from("Record not found")));
+    }
+
+    Ok(())
+}
+
+fn parse_locale(p_locale: &RawStr) -> Result<String,SupporterError> {
+    let locale = p_locale.url_decode()
+        .map_err(|_| SupporterError::Validation(String::from("No valid locale passed.")))?;
 
-use crate::routes::errors::RentError;
+    locale::parse_stored_locale(&locale).map_err(SupporterError::Validation)
+}
 
-#[get("/supporters")]
-pub fn get_supporters(db: DbConn) -> Result<Json<Vec<SupporterWithTypeAndTranslatable>>,RentError> {
-    let data = supporter::get_supporters(&db)?;
+#[get("/supporters?<filter..>")]
+pub fn get_supporters(db: DbConn, locale: LocalePreferences, filter: LenientForm<SupporterFilter>) -> Result<Json<Vec<SupporterWithTypeAndTranslatable>>,SupporterError> {
+    let data = supporter::get_supporters(&db, &locale, filter.supporter_type.as_deref())?;
 
     Ok(Json(data))
 }
 
+#[get("/admin/supporter-types")]
+pub fn get_supporter_types(db: DbConn, _admin: Admin) -> Result<Json<Vec<SupporterType>>,SupporterError> {
+    Ok(Json(supporter::get_supporter_types(&db)?))
+}
+
+#[post("/admin/supporter-types", data = "<supporter_type>")]
+pub fn create_supporter_type(db: DbConn, _admin: Admin, supporter_type: Json<InsertSupporterType>) -> Result<Json<SupporterType>,SupporterError> {
+    Ok(Json(supporter::insert_supporter_type(&db, &supporter_type)?))
+}
+
+#[put("/admin/supporter-types/<id>", data = "<supporter_type>")]
+pub fn update_supporter_type(db: DbConn, _admin: Admin, id: i32, supporter_type: Json<InsertSupporterType>) -> Result<Json<SupporterType>,SupporterError> {
+    Ok(Json(supporter::update_supporter_type(&db, id, &supporter_type)?))
+}
+
+#[delete("/admin/supporter-types/<id>")]
+pub fn delete_supporter_type(db: DbConn, _admin: Admin, id: i32) -> Result<(),SupporterError> {
+    ensure_deleted(supporter::delete_supporter_type(&db, id)?)
+}
+
+#[get("/admin/supporters")]
+pub fn get_all_supporters(db: DbConn, _admin: Admin) -> Result<Json<Vec<Supporter>>,SupporterError> {
+    Ok(Json(supporter::get_all_supporters(&db)?))
+}
+
+#[post("/admin/supporters", data = "<data>")]
+pub fn create_supporter(db: DbConn, _admin: Admin, data: Json<InsertSupporter>) -> Result<Json<Supporter>,SupporterError> {
+    validate_period(&data)?;
+
+    Ok(Json(supporter::insert_supporter(&db, &data)?))
+}
+
+#[put("/admin/supporters/<id>", data = "<data>")]
+pub fn update_supporter(db: DbConn, _admin: Admin, id: i32, data: Json<InsertSupporter>) -> Result<Json<Supporter>,SupporterError> {
+    validate_period(&data)?;
+
+    Ok(Json(supporter::update_supporter(&db, id, &data)?))
+}
+
+#[delete("/admin/supporters/<id>")]
+pub fn delete_supporter(db: DbConn, _admin: Admin, id: i32) -> Result<(),SupporterError> {
+    ensure_deleted(supporter::delete_supporter(&db, id)?)
+}
+
+#[get("/admin/supporters/<id>/translations")]
+pub fn get_supporter_translations(db: DbConn, _admin: Admin, id: i32) -> Result<Json<Vec<SupporterTranslatable>>,SupporterError> {
+    Ok(Json(supporter::get_supporter_translations(&db, id)?))
+}
+
+#[put("/admin/supporters/<id>/translations/<p_locale>", data = "<data>")]
+pub fn put_supporter_translation(db: DbConn, _admin: Admin, id: i32, p_locale: &RawStr, data: Json<SupporterTranslationData>) -> Result<Json<SupporterTranslatable>,SupporterError> {
+    let data = data.into_inner();
+    let translation = InsertSupporterTranslatable {
+        supporter_id: id,
+        locale: parse_locale(p_locale)?,
+        title: data.title,
+        description: data.description,
+        url: data.url,
+        logo_url: data.logo_url,
+        logo_width: data.logo_width,
+        logo_height: data.logo_height,
+        logo_alt_text: data.logo_alt_text,
+    };
+
+    Ok(Json(supporter::upsert_supporter_translation(&db, &translation)?))
+}
+
+#[delete("/admin/supporters/<id>/translations/<p_locale>")]
+pub fn delete_supporter_translation(db: DbConn, _admin: Admin, id: i32, p_locale: &RawStr) -> Result<(),SupporterError> {
+    ensure_deleted(supporter::delete_supporter_translation(&db, id, &parse_locale(p_locale)?)?)
+}
+
+fn logo_size(logo: &StoredVariant) -> Result<(i16, i16),SupporterError> {
+    let width = i16::try_from(logo.width).map_err(|_| SupporterError::Validation(String::from("The logo is too wide.")))?;
+    let height = i16::try_from(logo.height).map_err(|_| SupporterError::Validation(String::from("The logo is too high.")))?;
+
+    Ok((width, height))
+}
+
+/// Stores the uploaded logo and fills in the logo URL and dimensions of the
+/// translation. The translation has to exist already.
+#[post("/admin/supporters/<id>/translations/<p_locale>/logo", data = "<data>")]
+pub fn upload_supporter_logo(db: DbConn, _admin: Admin, id: i32, p_locale: &RawStr, data: Data) -> Result<Json<SupporterTranslatable>,SupporterError> {
+    let locale = parse_locale(p_locale)?;
+
+    if !supporter::get_supporter_translations(&db, id)?.iter().any(|t| t.locale == locale) {
+        return Err(SupporterError::NotFound(String::from("Translation not found")));
+    }
+
+    let storage = FileStorage::from_config();
+    let upload = media::read_upload(data)?;
+    let variant = ImageVariant { name: "web", max_width: config::logo_max_width(), max_height: config::logo_max_height() };
+    let image = media::store_image(&storage, "logos", &upload, &[variant])?;
+    let web = &image.variants[0];
+
+    let updated = logo_size(web)
+        .and_then(|(width, height)| Ok(supporter::update_supporter_logo(&db, id, &locale, &web.url, width, height)?));
+
+    if updated.is_err() {
+        media::discard_image(&storage, &image);
+    }
+
+    Ok(Json(updated?))
+}
+
+#[get("/admin/supporters/translations/missing")]
+pub fn get_missing_supporter_translations(db: DbConn, _admin: Admin) -> Result<Json<Vec<MissingTranslations>>,SupporterError> {
+    Ok(Json(supporter::get_missing_supporter_translations(&db)?))
+}
+
+fn validate_period(data: &InsertSupporter) -> Result<(),SupporterError> {
+    if let (Some(start), Some(end)) = (data.start_date, data.end_date) {
+        if start > end {
+            return Err(SupporterError::Validation(String::from("The sponsorship ends before it starts.")));
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
+    use std::env;
+
     use mocktopus::mocking::Mockable;
     use mocktopus::mocking::MockResult;
 
     use rocket;
     use rocket::routes;
     use rocket::local::Client;
-    use rocket::http::Status;
+    use rocket::http::{ContentType,Status};
 
     use crate::database::DbConn;
     use crate::database::daos::supporter;
-    use crate::database::models::SupporterWithTypeAndTranslatable;
+    use crate::database::models::{SupporterTranslatable,SupporterWithTypeAndTranslatable};
+
+    use crate::routes::auth;
+
+    fn translatable(supporter_id: i32, locale: &str) -> SupporterTranslatable {
+        SupporterTranslatable {
+            id: 1,
+            supporter_id,
+            locale: locale.to_string(),
+            title: "Sponsor".to_string(),
+            description: None,
+            url: None,
+            logo_url: None,
+            logo_width: None,
+            logo_height: None,
+            logo_alt_text: None,
+        }
+    }
 
     #[test]
     fn test_get_bikes() {
         crate::database::test::setup();
 
-        supporter::get_supporters.mock_safe(|_| MockResult::Return(Ok(vec![
+        supporter::get_supporters.mock_safe(|_, _, _| MockResult::Return(Ok(vec![
             SupporterWithTypeAndTranslatable { id: 1,
                 supporter_type_title: "Hardware".to_string(),
                 locale: "de-DE".to_string(),
@@ -71,7 +243,7 @@ mod test {
     fn test_get_bikes_with_empty_result() {
         crate::database::test::setup();
 
-        supporter::get_supporters.mock_safe(|_| MockResult::Return(Ok(vec![])));
+        supporter::get_supporters.mock_safe(|_, _, _| MockResult::Return(Ok(vec![])));
 
         let rocket = rocket::ignite()
             .attach(DbConn::fairing())
@@ -82,4 +254,120 @@ mod test {
         assert_eq!(response.status(), Status::Ok);
         assert_eq!(response.body_string(), Some("[]".to_string()));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_get_supporters_with_filters() {
+        crate::database::test::setup();
+
+        supporter::get_supporters.mock_safe(|_, preferences, supporter_type| {
+            assert_eq!(preferences.tags[0].to_string(), "en-GB");
+            assert_eq!(supporter_type, Some("Hardware"));
+            MockResult::Return(Ok(vec![]))
+        });
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::get_supporters]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let response = client.get("/supporters?type=Hardware&locale=en-GB").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn test_create_supporter_with_invalid_period() {
+        crate::database::test::setup();
+        auth::test::setup();
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::create_supporter]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let response = client.post("/admin/supporters")
+            .header(auth::test::authorization())
+            .body(r#"{"supporter_type_id":1,"sort_priority":10,"start_date":"2021-08-01","end_date":"2021-07-01"}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn test_put_supporter_translation() {
+        crate::database::test::setup();
+        auth::test::setup();
+
+        supporter::upsert_supporter_translation.mock_safe(|_, translation| {
+            assert_eq!(translation.supporter_id, 3);
+            assert_eq!(translation.locale, "en-GB");
+
+            MockResult::Return(Ok(translatable(translation.supporter_id, &translation.locale)))
+        });
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::put_supporter_translation]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.put("/admin/supporters/3/translations/en-gb")
+            .header(auth::test::authorization())
+            .body(r#"{"title":"Sponsor","description":null,"url":null,"logo_url":null,"logo_width":null,"logo_height":null,"logo_alt_text":null}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("{\"id\":1,\"supporter_id\":3,\"locale\":\"en-GB\",\"title\":\"Sponsor\",\"description\":null,\"url\":null,\"logo_url\":null,\"logo_width\":null,\"logo_height\":null,\"logo_alt_text\":null}".to_string()));
+
+        let response = client.put("/admin/supporters/3/translations/zh-Hant-TW")
+            .header(auth::test::authorization())
+            .body(r#"{"title":"Sponsor","description":null,"url":null,"logo_url":null,"logo_width":null,"logo_height":null,"logo_alt_text":null}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn test_upload_supporter_logo() {
+        crate::database::test::setup();
+        auth::test::setup();
+        env::set_var("UPLOAD_DIRECTORY", env::temp_dir().join("cbs-uploads-test"));
+        env::set_var("LOGO_MAX_WIDTH", "400");
+        env::set_var("LOGO_MAX_HEIGHT", "200");
+
+        supporter::get_supporter_translations.mock_safe(|_, supporter_id| MockResult::Return(Ok(vec![translatable(supporter_id, "en-GB")])));
+        supporter::update_supporter_logo.mock_safe(|_, supporter_id, locale, logo_url, logo_width, logo_height| {
+            assert_eq!(supporter_id, 3);
+            assert_eq!(locale, "en-GB");
+            assert!(logo_url.starts_with("/uploads/logos/"));
+            assert_eq!((logo_width, logo_height), (400, 100));
+
+            let mut translation = translatable(supporter_id, locale);
+            translation.logo_url = Some(logo_url.to_string());
+            translation.logo_width = Some(logo_width);
+            translation.logo_height = Some(logo_height);
+
+            MockResult::Return(Ok(translation))
+        });
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::upload_supporter_logo]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let response = client.post("/admin/supporters/3/translations/en-GB/logo")
+            .header(auth::test::authorization())
+            .header(ContentType::PNG)
+            .body(crate::media::test::png(1000, 250))
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let response = client.post("/admin/supporters/3/translations/en-GB/logo")
+            .header(auth::test::authorization())
+            .body("not an image")
+            .dispatch();
+        assert_eq!(response.status(), Status::UnsupportedMediaType);
+
+        let response = client.post("/admin/supporters/3/translations/de-DE/logo")
+            .header(auth::test::authorization())
+            .header(ContentType::PNG)
+            .body(crate::media::test::png(1000, 250))
+            .dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+}
@@ -0,0 +1,102 @@
+use chrono::Utc;
+
+use log::{error,info};
+
+use rocket::get;
+use rocket::http::RawStr;
+
+use crate::database::DbConn;
+use crate::database::daos::verification;
+use crate::email_address;
+use crate::mailer;
+
+use crate::routes::errors::RentError;
+use crate::routes::waitlist;
+
+/// Revokes the bookings whose address was not confirmed in time and offers
+/// their slots to the waitlist right away.
+pub fn lapse_unverified_bookings(db: &DbConn) {
+    match verification::lapse_unverified_bookings(db, Utc::now().naive_utc()) {
+        Ok(count) => {
+            if count > 0 {
+                info!("Lapsed {} unverified bookings", count);
+                waitlist::notify_waitlist(db);
+            }
+        },
+        Err(err) => error!("Lapsing unverified bookings failed: {}", err),
+    }
+}
+
+/// The link of the verification mail. Confirms the booking or waitlist entry
+/// and sends the booking details to the now trusted address. A booking whose
+/// link ran out lapses here already, not only with the next periodic job.
+#[get("/email-verifications/<code>")]
+pub fn verify_email(db: DbConn, code: &RawStr) -> Result<String,RentError> {
+    let parsed_code = ::uuid::Uuid::parse_str(code)?;
+
+    lapse_unverified_bookings(&db);
+
+    let (verification, rents) = verification::verify_email(&db, &parsed_code)?;
+
+    if mailer::is_mail_config_available() && !rents.is_empty() {
+        if let Some(email) = email_address::open(&verification.encrypted_email) {
+            mailer::send_confirmation_mail(&email, &verification, &rents)?;
+        }
+    }
+
+    Ok(String::from("Thank you, your email address is confirmed."))
+}
+
+#[cfg(test)]
+mod test {
+    use mocktopus::mocking::Mockable;
+    use mocktopus::mocking::MockResult;
+
+    use chrono::NaiveDate;
+
+    use rocket;
+    use rocket::routes;
+    use rocket::local::Client;
+    use rocket::http::Status;
+
+    use crate::database::DbConn;
+    use crate::database::daos::verification;
+    use crate::database::models::{EmailVerification,VerificationStatus};
+    use crate::mailer;
+    use crate::routes::errors::RentError;
+
+    #[test]
+    fn test_verify_email() {
+        crate::database::test::setup();
+
+        mailer::is_mail_config_available.mock_safe(|| MockResult::Return(false));
+        verification::lapse_unverified_bookings.mock_safe(|_, _| MockResult::Return(Ok(0)));
+        verification::verify_email.mock_safe(|_, code| MockResult::Return(if code.to_string() == "5d1a2f76-0c6e-4b8e-9f1c-2b7e3c4d5a6b" {
+            Ok((EmailVerification {
+                id: 1,
+                token_id: 2,
+                uuid: *code,
+                encrypted_email: "".to_string(),
+                short_token: Some("ABC123".to_string()),
+                status: VerificationStatus::Verified.as_str().to_string(),
+                expires_at: NaiveDate::from_ymd(2021, 4, 19).and_hms(1, 0, 0),
+                verified_at: Some(NaiveDate::from_ymd(2021, 4, 19).and_hms(0, 30, 0)),
+                created_at: NaiveDate::from_ymd(2021, 4, 19).and_hms(0, 0, 0),
+            }, vec![]))
+        } else {
+            Err(RentError::Validation(String::from("The link has expired, the booking has lapsed.")))
+        }));
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::verify_email]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.get("/email-verifications/5d1a2f76-0c6e-4b8e-9f1c-2b7e3c4d5a6b").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("Thank you, your email address is confirmed.".to_string()));
+
+        let response = client.get("/email-verifications/00a791f1-68b8-457c-82d9-a060f48efbae").dispatch();
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+}
@@ -0,0 +1,191 @@
+use chrono::Utc;
+
+use log::{error,info};
+
+use rocket::{delete,get,post};
+use rocket::http::RawStr;
+use rocket_contrib::json::Json;
+
+use crate::database::DbConn;
+use crate::database::models::{WaitlistEntry,WaitlistRequest};
+use crate::database::daos::{availability,verification,waitlist};
+use crate::mailer;
+
+use crate::routes::auth::Admin;
+use crate::routes::errors::WaitlistError;
+
+/// Offers freed slots to the waiting riders. Runs after revocations and as a
+/// periodic job, which passes on slots of holds that ran out. Without a mail
+/// configuration nobody could be told, so the riders keep waiting.
+pub fn notify_waitlist(db: &DbConn) {
+    if !mailer::is_mail_config_available() {
+        return;
+    }
+
+    match waitlist::offer_free_slots(db, Utc::now().naive_utc()) {
+        Ok(offers) => {
+            for (entry, hold) in &offers {
+                let email = match verification::get_verified_email(db, entry.token_id) {
+                    Ok(Some(email)) => email,
+                    Ok(None) => {
+                        error!("No confirmed address for waitlist entry {}", entry.id);
+                        continue;
+                    },
+                    Err(err) => {
+                        error!("Looking up the address for waitlist entry {} failed: {}", entry.id, err);
+                        continue;
+                    },
+                };
+
+                if let Err(err) = mailer::send_waitlist_mail(&email, entry, hold) {
+                    error!("Sending the mail for waitlist entry {} failed: {}", entry.id, err);
+                }
+            }
+
+            if !offers.is_empty() {
+                info!("Offered {} slots to waitlisted riders", offers.len());
+            }
+        },
+        Err(err) => error!("Offering slots to waitlisted riders failed: {}", err),
+    }
+}
+
+/// Puts the rider on the waitlist for a window in which the bike is taken.
+/// This spends a token like a booking, and slots are only offered once the
+/// rider confirmed the address.
+#[post("/waitlist", data = "<data>")]
+pub fn create_waitlist_entry(db: DbConn, data: Json<WaitlistRequest>) -> Result<Json<WaitlistEntry>,WaitlistError> {
+    if !mailer::is_mail_config_available() {
+        return Err(WaitlistError::Validation(String::from("The waitlist is not available at the moment.")));
+    }
+
+    if data.start_timestamp >= data.end_timestamp {
+        return Err(WaitlistError::Validation(String::from("The rent has to end after it starts.")));
+    }
+
+    if data.start_timestamp <= Utc::now().naive_utc() {
+        return Err(WaitlistError::Validation(String::from("Only future rents can be waited for.")));
+    }
+
+    if availability::is_bike_available(&db, data.bike_id, data.start_timestamp, data.end_timestamp)? {
+        return Err(WaitlistError::Validation(String::from("The bike is available, it can be booked right away.")));
+    }
+
+    let (entry, verification) = waitlist::insert_waitlist_entry(&db, &data)?;
+    mailer::send_verification_mail(&data.email, &verification)?;
+
+    Ok(Json(entry))
+}
+
+#[delete("/waitlist/<uuid>")]
+pub fn delete_waitlist_entry(db: DbConn, uuid: &RawStr) -> Result<(),WaitlistError> {
+    if waitlist::delete_waitlist_entry(&db, &::uuid::Uuid::parse_str(uuid)?)? == 0 {
+        return Err(WaitlistError::NotFound(String::from("Waitlist entry not found")));
+    }
+
+    Ok(())
+}
+
+#[get("/admin/waitlist")]
+pub fn get_waitlist_entries(db: DbConn, _admin: Admin) -> Result<Json<Vec<WaitlistEntry>>,WaitlistError> {
+    Ok(Json(waitlist::get_waitlist_entries(&db)?))
+}
+
+#[cfg(test)]
+mod test {
+    use mocktopus::mocking::Mockable;
+    use mocktopus::mocking::MockResult;
+
+    use chrono::NaiveDate;
+
+    use rocket;
+    use rocket::routes;
+    use rocket::local::Client;
+    use rocket::http::Status;
+
+    use uuid::Uuid;
+
+    use lettre::transport::smtp::response::{Category,Code,Detail,Response,Severity};
+
+    use crate::database::DbConn;
+    use crate::database::daos::{availability,waitlist};
+    use crate::database::models::{EmailVerification,VerificationStatus,WaitlistEntry};
+    use crate::mailer;
+
+    #[test]
+    fn test_create_waitlist_entry() {
+        crate::database::test::setup();
+
+        mailer::is_mail_config_available.mock_safe(|| MockResult::Return(true));
+        mailer::send_verification_mail.mock_safe(|_, _| {
+            MockResult::Return(Ok(Response::new(
+                Code {
+                    category: Category::Information,
+                    detail: Detail::Zero,
+                    severity: Severity::PositiveCompletion,
+                },
+                vec![]
+            )))
+        });
+        availability::is_bike_available.mock_safe(|_, bike_id, _, _| MockResult::Return(Ok(bike_id == 2)));
+        waitlist::insert_waitlist_entry.mock_safe(|_, request| MockResult::Return(Ok((WaitlistEntry {
+            id: 1,
+            uuid: Uuid::parse_str("00a791f1-68b8-457c-82d9-a060f48efbae").unwrap(),
+            token_id: 2,
+            bike_id: request.bike_id,
+            start_timestamp: request.start_timestamp,
+            end_timestamp: request.end_timestamp,
+            created_at: NaiveDate::from_ymd(2021, 4, 18).and_hms(0, 0, 0),
+            notified_at: None,
+        }, EmailVerification {
+            id: 1,
+            token_id: 2,
+            uuid: Uuid::parse_str("10a791f1-68b8-457c-82d9-a060f48efbae").unwrap(),
+            encrypted_email: "".to_string(),
+            short_token: None,
+            status: VerificationStatus::Pending.as_str().to_string(),
+            expires_at: NaiveDate::from_ymd(2021, 4, 18).and_hms(1, 0, 0),
+            verified_at: None,
+            created_at: NaiveDate::from_ymd(2021, 4, 18).and_hms(0, 0, 0),
+        }))));
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::create_waitlist_entry]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let mut response = client.post("/waitlist")
+            .body(r#"{"token":"20a791f1-68b8-457c-82d9-a060f48efbae","bike_id":1,"start_timestamp":"2999-04-20T08:00:00","end_timestamp":"2999-04-20T18:00:00","email":"someone@somewhere.near"}"#)
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.body_string(), Some("{\"id\":1,\"uuid\":\"00a791f1-68b8-457c-82d9-a060f48efbae\",\"bike_id\":1,\"start_timestamp\":\"2999-04-20T08:00:00+00:00\",\"end_timestamp\":\"2999-04-20T18:00:00+00:00\",\"token_id\":2,\"created_at\":\"2021-04-18T00:00:00+00:00\",\"notified_at\":null}".to_string()));
+
+        for body in &[
+            r#"{"token":"20a791f1-68b8-457c-82d9-a060f48efbae","bike_id":2,"start_timestamp":"2999-04-20T08:00:00","end_timestamp":"2999-04-20T18:00:00","email":"someone@somewhere.near"}"#,
+            r#"{"token":"20a791f1-68b8-457c-82d9-a060f48efbae","bike_id":1,"start_timestamp":"2020-04-20T08:00:00","end_timestamp":"2020-04-20T18:00:00","email":"someone@somewhere.near"}"#,
+        ] {
+            let response = client.post("/waitlist")
+                .body(body)
+                .dispatch();
+            assert_eq!(response.status(), Status::BadRequest);
+        }
+    }
+
+    #[test]
+    fn test_delete_waitlist_entry() {
+        crate::database::test::setup();
+
+        waitlist::delete_waitlist_entry.mock_safe(|_, uuid| MockResult::Return(Ok(if uuid.to_string() == "00a791f1-68b8-457c-82d9-a060f48efbae" { 1 } else { 0 })));
+
+        let rocket = rocket::ignite()
+            .attach(DbConn::fairing())
+            .mount("/", routes![super::delete_waitlist_entry]);
+        let client = Client::new(rocket).expect("valid rocket instance");
+
+        let response = client.delete("/waitlist/00a791f1-68b8-457c-82d9-a060f48efbae").dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let response = client.delete("/waitlist/10a791f1-68b8-457c-82d9-a060f48efbae").dispatch();
+        assert_eq!(response.status(), Status::NotFound);
+    }
+}
@@ -1,3 +1,162 @@
+table! {
+    accessories (id) {
+        id -> Int4,
+        quantity -> Int4,
+        sort_priority -> Int4,
+    }
+}
+
+table! {
+    accessory_bikes (id) {
+        id -> Int4,
+        accessory_id -> Int4,
+        bike_id -> Int4,
+    }
+}
+
+table! {
+    accessory_translatables (id) {
+        id -> Int4,
+        accessory_id -> Int4,
+        locale -> Varchar,
+        title -> Varchar,
+        description -> Nullable<Text>,
+    }
+}
+
+table! {
+    bike_attribute_option_translatables (id) {
+        id -> Int4,
+        bike_attribute_option_id -> Int4,
+        locale -> Varchar,
+        label -> Varchar,
+    }
+}
+
+table! {
+    bike_attribute_options (id) {
+        id -> Int4,
+        bike_attribute_id -> Int4,
+        key -> Varchar,
+    }
+}
+
+table! {
+    bike_attribute_translatables (id) {
+        id -> Int4,
+        bike_attribute_id -> Int4,
+        locale -> Varchar,
+        label -> Varchar,
+    }
+}
+
+table! {
+    bike_attribute_values (id) {
+        id -> Int4,
+        bike_id -> Int4,
+        bike_attribute_id -> Int4,
+        number_value -> Nullable<Float8>,
+        boolean_value -> Nullable<Bool>,
+        bike_attribute_option_id -> Nullable<Int4>,
+    }
+}
+
+table! {
+    bike_attributes (id) {
+        id -> Int4,
+        key -> Varchar,
+        value_type -> Varchar,
+        unit -> Nullable<Varchar>,
+        sort_priority -> Int4,
+    }
+}
+
+table! {
+    bike_blackouts (id) {
+        id -> Int4,
+        bike_id -> Nullable<Int4>,
+        start_timestamp -> Timestamptz,
+        end_timestamp -> Timestamptz,
+        reason -> Nullable<Varchar>,
+        created_by -> Varchar,
+        created_at -> Timestamptz,
+    }
+}
+
+table! {
+    bike_incident_photos (id) {
+        id -> Int4,
+        bike_incident_id -> Int4,
+        url -> Varchar,
+        width -> Int4,
+        height -> Int4,
+        thumbnail_url -> Varchar,
+        thumbnail_width -> Int4,
+        thumbnail_height -> Int4,
+        created_at -> Timestamptz,
+    }
+}
+
+table! {
+    bike_incidents (id) {
+        id -> Int4,
+        rent_id -> Int4,
+        bike_id -> Int4,
+        category -> Varchar,
+        description -> Text,
+        reported_by -> Nullable<Varchar>,
+        created_at -> Timestamptz,
+    }
+}
+
+table! {
+    bike_media (id) {
+        id -> Int4,
+        bike_id -> Int4,
+        position -> Int4,
+        url -> Varchar,
+        width -> Int4,
+        height -> Int4,
+        thumbnail_url -> Varchar,
+        thumbnail_width -> Int4,
+        thumbnail_height -> Int4,
+        created_at -> Timestamptz,
+    }
+}
+
+table! {
+    bike_media_translatables (id) {
+        id -> Int4,
+        bike_media_id -> Int4,
+        locale -> Varchar,
+        alt_text -> Varchar,
+    }
+}
+
+table! {
+    bike_service_intervals (id) {
+        id -> Int4,
+        bike_id -> Int4,
+        title -> Varchar,
+        interval_days -> Nullable<Int4>,
+        interval_rents -> Nullable<Int4>,
+        blocks_bookings -> Bool,
+        created_at -> Timestamptz,
+        notified_at -> Nullable<Timestamptz>,
+    }
+}
+
+table! {
+    bike_status_changes (id) {
+        id -> Int4,
+        bike_id -> Int4,
+        status -> Varchar,
+        comment -> Nullable<Varchar>,
+        changed_by -> Varchar,
+        changed_at -> Timestamptz,
+    }
+}
+
 table! {
     bike_translatables (id) {
         id -> Int4,
@@ -9,9 +168,93 @@ table! {
     }
 }
 
+table! {
+    bike_work_orders (id) {
+        id -> Int4,
+        bike_id -> Int4,
+        bike_service_interval_id -> Nullable<Int4>,
+        description -> Text,
+        parts -> Nullable<Text>,
+        cost_cents -> Nullable<Int4>,
+        performed_by -> Varchar,
+        performed_at -> Timestamptz,
+        created_at -> Timestamptz,
+    }
+}
+
 table! {
     bikes (id) {
         id -> Int4,
+        status -> Varchar,
+        buffer_before_minutes -> Nullable<Int4>,
+        buffer_after_minutes -> Nullable<Int4>,
+        booking_granularity -> Varchar,
+        requires_approval -> Bool,
+    }
+}
+
+table! {
+    email_verifications (id) {
+        id -> Int4,
+        token_id -> Int4,
+        uuid -> Uuid,
+        encrypted_email -> Text,
+        short_token -> Nullable<Varchar>,
+        status -> Varchar,
+        expires_at -> Timestamptz,
+        verified_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+    }
+}
+
+table! {
+    invite_code_redemptions (id) {
+        id -> Int4,
+        invite_code_id -> Int4,
+        token_id -> Nullable<Int4>,
+        redeemed_at -> Timestamptz,
+    }
+}
+
+table! {
+    invite_codes (id) {
+        id -> Int4,
+        code -> Varchar,
+        group_name -> Varchar,
+        usage_limit -> Int4,
+        valid_from -> Nullable<Timestamptz>,
+        valid_until -> Nullable<Timestamptz>,
+        created_by -> Varchar,
+        created_at -> Timestamptz,
+    }
+}
+
+table! {
+    proof_of_work_nonces (id) {
+        id -> Int4,
+        nonce -> Varchar,
+        expires_at -> Timestamptz,
+    }
+}
+
+table! {
+    rent_accessories (id) {
+        id -> Int4,
+        rent_id -> Int4,
+        accessory_id -> Int4,
+        quantity -> Int4,
+    }
+}
+
+table! {
+    rent_approvals (id) {
+        id -> Int4,
+        token_id -> Int4,
+        status -> Varchar,
+        expires_at -> Timestamptz,
+        decided_by -> Nullable<Varchar>,
+        decided_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
     }
 }
 
@@ -23,6 +266,54 @@ table! {
     }
 }
 
+table! {
+    rent_feedbacks (id) {
+        id -> Int4,
+        rent_id -> Int4,
+        rating -> Int2,
+        comment -> Nullable<Text>,
+        distance_km -> Nullable<Float8>,
+        created_at -> Timestamptz,
+    }
+}
+
+table! {
+    rent_hold_accessories (id) {
+        id -> Int4,
+        rent_hold_id -> Int4,
+        accessory_id -> Int4,
+        quantity -> Int4,
+    }
+}
+
+table! {
+    rent_holds (id) {
+        id -> Int4,
+        uuid -> Uuid,
+        bike_id -> Int4,
+        start_timestamp -> Timestamptz,
+        end_timestamp -> Timestamptz,
+        expires_at -> Timestamptz,
+        waitlist_entry_id -> Nullable<Int4>,
+        token_id -> Nullable<Int4>,
+        created_at -> Timestamptz,
+    }
+}
+
+table! {
+    rent_series (id) {
+        id -> Int4,
+        token_id -> Int4,
+        bike_id -> Int4,
+        frequency -> Varchar,
+        start_timestamp -> Timestamptz,
+        end_timestamp -> Timestamptz,
+        until_date -> Nullable<Date>,
+        occurrence_count -> Nullable<Int4>,
+        created_at -> Timestamptz,
+    }
+}
+
 table! {
     rents (id) {
         id -> Int4,
@@ -32,6 +323,8 @@ table! {
         start_timestamp -> Timestamptz,
         end_timestamp -> Timestamptz,
         revocation_timestamp -> Nullable<Timestamptz>,
+        flagged_at -> Nullable<Timestamptz>,
+        flag_reason -> Nullable<Varchar>,
     }
 }
 
@@ -54,6 +347,7 @@ table! {
     supporter_types (id) {
         id -> Int4,
         title -> Varchar,
+        sort_priority -> Int4,
     }
 }
 
@@ -61,6 +355,9 @@ table! {
     supporters (id) {
         id -> Int4,
         supporter_type_id -> Nullable<Int4>,
+        sort_priority -> Int4,
+        start_date -> Nullable<Date>,
+        end_date -> Nullable<Date>,
     }
 }
 
@@ -86,21 +383,96 @@ table! {
         id -> Int4,
         uuid -> Uuid,
         created_at -> Timestamptz,
+        rider_hash -> Nullable<Varchar>,
+    }
+}
+
+table! {
+    waitlist_entries (id) {
+        id -> Int4,
+        uuid -> Uuid,
+        bike_id -> Int4,
+        start_timestamp -> Timestamptz,
+        end_timestamp -> Timestamptz,
+        token_id -> Int4,
+        created_at -> Timestamptz,
+        notified_at -> Nullable<Timestamptz>,
     }
 }
 
+joinable!(accessory_bikes -> accessories (accessory_id));
+joinable!(accessory_bikes -> bikes (bike_id));
+joinable!(accessory_translatables -> accessories (accessory_id));
+joinable!(bike_attribute_option_translatables -> bike_attribute_options (bike_attribute_option_id));
+joinable!(bike_attribute_options -> bike_attributes (bike_attribute_id));
+joinable!(bike_attribute_translatables -> bike_attributes (bike_attribute_id));
+joinable!(bike_attribute_values -> bike_attribute_options (bike_attribute_option_id));
+joinable!(bike_attribute_values -> bike_attributes (bike_attribute_id));
+joinable!(bike_attribute_values -> bikes (bike_id));
+joinable!(bike_blackouts -> bikes (bike_id));
+joinable!(bike_incident_photos -> bike_incidents (bike_incident_id));
+joinable!(bike_incidents -> bikes (bike_id));
+joinable!(bike_incidents -> rents (rent_id));
+joinable!(bike_media -> bikes (bike_id));
+joinable!(bike_media_translatables -> bike_media (bike_media_id));
+joinable!(bike_service_intervals -> bikes (bike_id));
+joinable!(bike_status_changes -> bikes (bike_id));
 joinable!(bike_translatables -> bikes (bike_id));
+joinable!(bike_work_orders -> bike_service_intervals (bike_service_interval_id));
+joinable!(bike_work_orders -> bikes (bike_id));
+joinable!(email_verifications -> tokens (token_id));
+joinable!(invite_code_redemptions -> invite_codes (invite_code_id));
+joinable!(invite_code_redemptions -> tokens (token_id));
+joinable!(rent_accessories -> accessories (accessory_id));
+joinable!(rent_accessories -> rents (rent_id));
+joinable!(rent_approvals -> tokens (token_id));
 joinable!(rent_details -> rents (rent_id));
+joinable!(rent_feedbacks -> rents (rent_id));
+joinable!(rent_hold_accessories -> accessories (accessory_id));
+joinable!(rent_hold_accessories -> rent_holds (rent_hold_id));
+joinable!(rent_holds -> bikes (bike_id));
+joinable!(rent_holds -> tokens (token_id));
+joinable!(rent_holds -> waitlist_entries (waitlist_entry_id));
+joinable!(rent_series -> bikes (bike_id));
+joinable!(rent_series -> tokens (token_id));
 joinable!(rents -> bikes (bike_id));
 joinable!(rents -> tokens (token_id));
 joinable!(supporter_translatables -> supporters (supporter_id));
 joinable!(supporters -> supporter_types (supporter_type_id));
 joinable!(token_challenge_translatables -> token_challenges (token_challenge_id));
+joinable!(waitlist_entries -> bikes (bike_id));
+joinable!(waitlist_entries -> tokens (token_id));
 
 allow_tables_to_appear_in_same_query!(
+    accessories,
+    accessory_bikes,
+    accessory_translatables,
+    bike_attribute_option_translatables,
+    bike_attribute_options,
+    bike_attribute_translatables,
+    bike_attribute_values,
+    bike_attributes,
+    bike_blackouts,
+    bike_incident_photos,
+    bike_incidents,
+    bike_media,
+    bike_media_translatables,
+    bike_service_intervals,
+    bike_status_changes,
     bike_translatables,
+    bike_work_orders,
     bikes,
+    email_verifications,
+    invite_code_redemptions,
+    invite_codes,
+    proof_of_work_nonces,
+    rent_accessories,
+    rent_approvals,
     rent_details,
+    rent_feedbacks,
+    rent_hold_accessories,
+    rent_holds,
+    rent_series,
     rents,
     supporter_translatables,
     supporter_types,
@@ -108,4 +480,5 @@ allow_tables_to_appear_in_same_query!(
     token_challenge_translatables,
     token_challenges,
     tokens,
+    waitlist_entries,
 );
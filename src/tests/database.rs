@@ -1 +1,52 @@
-pub mod daos;
\ No newline at end of file
+pub mod daos;
+
+use chrono::{Duration,NaiveDateTime,Utc};
+
+use diesel::RunQueryDsl;
+use diesel::insert_into;
+
+use mocktopus::mocking::Mockable;
+use mocktopus::mocking::MockResult;
+
+use crate::config;
+use crate::database::DbConn;
+use crate::database::models::{Bike,Booking,Token};
+use crate::schema::{bikes,tokens};
+
+/// The given hour of tomorrow, so that bookings are always in the future.
+pub fn tomorrow(hour: i64) -> NaiveDateTime {
+    (Utc::now().naive_utc() + Duration::days(1)).date().and_hms(0, 0, 0) + Duration::hours(hour)
+}
+
+pub fn insert_bike(db: &DbConn) -> Bike {
+    insert_into(bikes::table).default_values().get_result::<Bike>(&**db).unwrap()
+}
+
+pub fn insert_token(db: &DbConn) -> Token {
+    insert_into(tokens::table).default_values().get_result::<Token>(&**db).unwrap()
+}
+
+/// A bike and two tokens, with the rider hash configured so that addresses
+/// can be given.
+pub fn setup_database(db: &DbConn) -> (Bike, Token, Token) {
+    config::rider_hash_secret.mock_safe(|| MockResult::Return(Some("test secret".to_string())));
+
+    (insert_bike(db), insert_token(db), insert_token(db))
+}
+
+/// A booking of a single bike without any extras. Tests set the other fields
+/// with the struct update syntax.
+pub fn booking(token: &Token, bike: &Bike, start_timestamp: NaiveDateTime, end_timestamp: NaiveDateTime, email: Option<&str>) -> Booking {
+    Booking {
+        token: token.uuid,
+        bike_id: bike.id,
+        start_timestamp,
+        end_timestamp,
+        encrypted_details: "".to_string(),
+        short_token: "".to_string(),
+        email: email.map(String::from),
+        additional_bike_ids: vec![],
+        accessories: vec![],
+        hold_id: None,
+    }
+}
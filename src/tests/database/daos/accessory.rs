@@ -0,0 +1,157 @@
+use chrono::NaiveDate;
+
+use diesel::{RunQueryDsl,Connection,ExpressionMethods,QueryDsl};
+
+use crate::database::DbConn;
+use crate::database::daos::{accessory,availability,hold,rent};
+use crate::database::models::{Bike,Booking,BookingAccessory,HoldRequest,InsertAccessory,InsertAccessoryTranslatable};
+use crate::locale::LocalePreferences;
+use crate::schema::{bikes,rent_accessories,rents};
+use crate::tests::database as fixtures;
+use crate::tests::database::{insert_bike,insert_token};
+
+fn booking(db: &DbConn, bike: &Bike, day: u32, accessories: Vec<BookingAccessory>) -> Booking {
+    let date = NaiveDate::from_ymd(2021, 5, day);
+
+    Booking { accessories, ..fixtures::booking(&insert_token(db), bike, date.and_hms(8, 0, 0), date.and_hms(18, 0, 0), None) }
+}
+
+#[test]
+pub fn test_book_accessories() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike1 = insert_bike(&db);
+        let bike2 = insert_bike(&db);
+        let seat = accessory::insert_accessory(&db, &InsertAccessory { quantity: 2, sort_priority: 0 }).unwrap();
+
+        accessory::upsert_accessory_translation(&db, &InsertAccessoryTranslatable {
+            accessory_id: seat.id,
+            locale: "de-DE".to_string(),
+            title: "Kindersitz".to_string(),
+            description: None,
+        }).unwrap();
+        assert_eq!(accessory::get_accessories(&db, &LocalePreferences::new(vec![])).unwrap()[0].accessory.title, "Kindersitz");
+
+        let two_seats = vec![BookingAccessory { accessory_id: seat.id, quantity: 2, bike_id: None }];
+        let one_seat = vec![BookingAccessory { accessory_id: seat.id, quantity: 1, bike_id: None }];
+
+        assert!(rent::insert_booking(&db, &booking(&db, &bike1, 1, two_seats)).is_ok());
+        assert!(rent::insert_booking(&db, &booking(&db, &bike2, 1, one_seat.clone())).is_err());
+        assert!(rent::insert_booking(&db, &booking(&db, &bike2, 2, one_seat)).is_ok());
+
+        let window = (NaiveDate::from_ymd(2021, 5, 2).and_hms(0, 0, 0), NaiveDate::from_ymd(2021, 5, 3).and_hms(0, 0, 0));
+        assert_eq!(availability::get_available_accessory_quantities(&db, window.0, window.1).unwrap(), vec![(seat.id, 1)]);
+
+        let duplicate = vec![BookingAccessory { accessory_id: seat.id, quantity: 1, bike_id: None }, BookingAccessory { accessory_id: seat.id, quantity: 1, bike_id: None }];
+        assert!(rent::insert_booking(&db, &booking(&db, &bike1, 3, duplicate)).is_err());
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_accessories_go_with_their_bike() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike1 = insert_bike(&db);
+        let bike2 = insert_bike(&db);
+        let bike3 = insert_bike(&db);
+        let seat = accessory::insert_accessory(&db, &InsertAccessory { quantity: 2, sort_priority: 0 }).unwrap();
+
+        let mut stray = booking(&db, &bike1, 1, vec![BookingAccessory { accessory_id: seat.id, quantity: 1, bike_id: Some(bike3.id) }]);
+        stray.additional_bike_ids = vec![bike2.id];
+        assert!(rent::insert_booking(&db, &stray).is_err());
+
+        let mut too_many = booking(&db, &bike1, 1, vec![
+            BookingAccessory { accessory_id: seat.id, quantity: 2, bike_id: None },
+            BookingAccessory { accessory_id: seat.id, quantity: 1, bike_id: Some(bike2.id) },
+        ]);
+        too_many.additional_bike_ids = vec![bike2.id];
+        assert!(rent::insert_booking(&db, &too_many).is_err());
+
+        let mut several = booking(&db, &bike1, 1, vec![
+            BookingAccessory { accessory_id: seat.id, quantity: 1, bike_id: None },
+            BookingAccessory { accessory_id: seat.id, quantity: 1, bike_id: Some(bike2.id) },
+        ]);
+        several.additional_bike_ids = vec![bike2.id];
+        assert!(rent::insert_booking(&db, &several).is_ok());
+
+        let reserved = rent_accessories::table
+            .inner_join(rents::table)
+            .select((rents::bike_id, rent_accessories::quantity))
+            .order_by(rents::bike_id)
+            .load::<(i32, i32)>(&*db)
+            .unwrap();
+        assert_eq!(reserved, vec![(bike1.id, 1), (bike2.id, 1)]);
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_accessory_peak_usage() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike1 = insert_bike(&db);
+        let bike2 = insert_bike(&db);
+        let bike3 = insert_bike(&db);
+        let seat = accessory::insert_accessory(&db, &InsertAccessory { quantity: 2, sort_priority: 0 }).unwrap();
+        let at = |day: u32, hour: u32| NaiveDate::from_ymd(2021, 5, day).and_hms(hour, 0, 0);
+
+        // Back to back rents never use more than one seat at a time.
+        for (bike, start, end) in &[(&bike1, 8, 12), (&bike2, 12, 16)] {
+            let mut single = booking(&db, bike, 4, vec![BookingAccessory { accessory_id: seat.id, quantity: 1, bike_id: None }]);
+            single.start_timestamp = at(4, *start);
+            single.end_timestamp = at(4, *end);
+            assert!(rent::insert_booking(&db, &single).is_ok());
+        }
+        assert_eq!(availability::get_available_accessory_quantities(&db, at(4, 8), at(4, 16)).unwrap(), vec![(seat.id, 1)]);
+
+        // The seats go out and come back with the bike.
+        diesel::update(bikes::table.find(bike1.id))
+            .set(bikes::buffer_after_minutes.eq(Some(60)))
+            .execute(&*db)
+            .unwrap();
+        assert!(rent::insert_booking(&db, &booking(&db, &bike1, 7, vec![BookingAccessory { accessory_id: seat.id, quantity: 2, bike_id: None }])).is_ok());
+        assert_eq!(availability::get_available_accessory_quantities(&db, at(7, 18), at(7, 19)).unwrap(), vec![(seat.id, 0)]);
+        assert_eq!(availability::get_available_accessory_quantities(&db, at(7, 19), at(7, 20)).unwrap(), vec![(seat.id, 2)]);
+
+        // Held seats are taken as well.
+        let token = insert_token(&db);
+        hold::create_hold(&db, &HoldRequest {
+            token: token.uuid,
+            bike_id: bike3.id,
+            start_timestamp: at(6, 8),
+            end_timestamp: at(6, 18),
+            accessories: vec![BookingAccessory { accessory_id: seat.id, quantity: 2, bike_id: None }],
+        }).unwrap();
+        assert_eq!(availability::get_available_accessory_quantities(&db, at(6, 10), at(6, 12)).unwrap(), vec![(seat.id, 0)]);
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_accessory_compatibility() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike1 = insert_bike(&db);
+        let bike2 = insert_bike(&db);
+        let seat = accessory::insert_accessory(&db, &InsertAccessory { quantity: 2, sort_priority: 0 }).unwrap();
+        let one_seat = vec![BookingAccessory { accessory_id: seat.id, quantity: 1, bike_id: None }];
+
+        assert_eq!(accessory::get_compatible_bike_ids(&db, seat.id).unwrap(), Vec::<i32>::new());
+        assert_eq!(accessory::set_compatible_bike_ids(&db, seat.id, &[bike1.id]).unwrap(), vec![bike1.id]);
+
+        assert!(rent::insert_booking(&db, &booking(&db, &bike2, 5, one_seat.clone())).is_err());
+        assert!(rent::insert_booking(&db, &booking(&db, &bike1, 5, one_seat)).is_ok());
+
+        assert_eq!(accessory::set_compatible_bike_ids(&db, seat.id, &[]).unwrap(), Vec::<i32>::new());
+
+        Ok(())
+    });
+}
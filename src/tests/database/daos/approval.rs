@@ -0,0 +1,81 @@
+use chrono::Utc;
+
+use diesel::Connection;
+
+use crate::database::daos::{approval,bike,rent};
+use crate::database::models::ApprovalStatus;
+use crate::tests::database::{booking,setup_database,tomorrow};
+
+#[test]
+pub fn test_pending_booking_reserves_slot_until_approved() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let (bike, token, other_token) = setup_database(&db);
+        let bike = bike::update_bike_approval(&db, bike.id, true).unwrap();
+
+        assert_eq!(rent::insert_booking(&db, &booking(&token, &bike, tomorrow(9), tomorrow(11), Some("someone@somewhere.near"))).unwrap().approval_status, ApprovalStatus::Pending);
+        assert!(rent::insert_booking(&db, &booking(&other_token, &bike, tomorrow(9), tomorrow(11), Some("someone@somewhere.near"))).is_err());
+
+        let pending = approval::get_pending_approvals(&db).unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].approval.expires_at, tomorrow(9));
+        assert_eq!(pending[0].rents.len(), 1);
+
+        let approved = approval::decide_approval(&db, pending[0].approval.id, true, "admin").unwrap();
+        assert_eq!(approved.approval.status, "approved");
+        assert_eq!(approved.approval.decided_by, Some("admin".to_string()));
+        assert!(approved.rents[0].revocation_timestamp.is_none());
+
+        assert!(approval::decide_approval(&db, pending[0].approval.id, false, "admin").is_err());
+        assert!(approval::get_pending_approvals(&db).unwrap().is_empty());
+        assert_eq!(approval::get_approval_by_token(&db, &token.uuid).unwrap().status, "approved");
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_declined_booking_frees_slot() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let (bike, token, other_token) = setup_database(&db);
+        let bike = bike::update_bike_approval(&db, bike.id, true).unwrap();
+
+        rent::insert_booking(&db, &booking(&token, &bike, tomorrow(9), tomorrow(11), Some("someone@somewhere.near"))).unwrap();
+        let pending = approval::get_approval_by_token(&db, &token.uuid).unwrap();
+
+        let declined = approval::decide_approval(&db, pending.id, false, "admin").unwrap();
+        assert_eq!(declined.approval.status, "declined");
+        assert!(declined.rents[0].revocation_timestamp.is_some());
+
+        assert_eq!(rent::insert_booking(&db, &booking(&other_token, &bike, tomorrow(9), tomorrow(11), Some("someone@somewhere.near"))).unwrap().approval_status, ApprovalStatus::Pending);
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_expire_pending_approvals() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let (bike, token, _) = setup_database(&db);
+        let bike = bike::update_bike_approval(&db, bike.id, true).unwrap();
+
+        rent::insert_booking(&db, &booking(&token, &bike, tomorrow(9), tomorrow(11), Some("someone@somewhere.near"))).unwrap();
+
+        assert!(approval::expire_pending_approvals(&db, Utc::now().naive_utc()).unwrap().is_empty());
+
+        let expired = approval::expire_pending_approvals(&db, tomorrow(10)).unwrap();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].approval.status, "expired");
+        assert!(expired[0].approval.decided_by.is_none());
+        assert!(expired[0].rents[0].revocation_timestamp.is_some());
+
+        assert!(approval::expire_pending_approvals(&db, tomorrow(10)).unwrap().is_empty());
+
+        Ok(())
+    });
+}
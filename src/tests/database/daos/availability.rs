@@ -0,0 +1,120 @@
+use chrono::{Duration,NaiveDate};
+
+use diesel::{RunQueryDsl,Connection};
+use diesel::insert_into;
+
+use crate::database::daos::{availability,bike};
+use crate::database::models::{Bike,BikeBufferData,BookingGranularity,Token,InsertRent,InsertBikeBlackout};
+use crate::schema::{bikes,rents,tokens};
+
+#[test]
+pub fn test_bike_availability() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike1 = insert_into(bikes::table).default_values().get_result::<Bike>(&*db).unwrap();
+        let bike2 = insert_into(bikes::table).default_values().get_result::<Bike>(&*db).unwrap();
+        let token = insert_into(tokens::table).default_values().get_result::<Token>(&*db).unwrap();
+
+        let day = |d, h| NaiveDate::from_ymd(2021, 5, d).and_hms(h, 0, 0);
+
+        insert_into(rents::table).values(InsertRent {
+            token_id: token.id,
+            bike_id: bike1.id,
+            start_timestamp: day(1, 8),
+            end_timestamp: day(1, 12),
+        }).execute(&*db).unwrap();
+
+        let ids = [bike1.id, bike2.id];
+
+        assert!(!availability::is_bike_available(&db, bike1.id, day(1, 10), day(1, 14)).unwrap());
+        assert!(availability::is_bike_available(&db, bike2.id, day(1, 10), day(1, 14)).unwrap());
+        assert!(availability::is_bike_available(&db, bike1.id, day(1, 12), day(1, 14)).unwrap());
+        assert_eq!(availability::get_available_bike_ids(&db, &ids, day(1, 10), day(1, 14)).unwrap(), vec![bike2.id]);
+
+        availability::insert_blackout(&db, &InsertBikeBlackout {
+            bike_id: Some(bike2.id),
+            start_timestamp: day(2, 0),
+            end_timestamp: day(3, 0),
+            reason: None,
+            created_by: String::from("admin"),
+        }).unwrap();
+
+        assert_eq!(availability::get_available_bike_ids(&db, &ids, day(2, 10), day(2, 14)).unwrap(), vec![bike1.id]);
+
+        availability::insert_blackout(&db, &InsertBikeBlackout {
+            bike_id: None,
+            start_timestamp: day(4, 0),
+            end_timestamp: day(5, 0),
+            reason: Some(String::from("Closed")),
+            created_by: String::from("admin"),
+        }).unwrap();
+
+        assert!(availability::get_available_bike_ids(&db, &ids, day(4, 10), day(4, 14)).unwrap().is_empty());
+        assert!(!availability::is_bike_available(&db, bike1.id, day(3, 20), day(4, 2)).unwrap());
+        assert_eq!(availability::get_available_bike_ids(&db, &ids, day(5, 0), day(5, 4)).unwrap(), ids.to_vec());
+
+        assert_eq!(availability::get_blackouts(&db, day(3, 0)).unwrap().len(), 1);
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_bike_availability_with_buffers() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike1 = insert_into(bikes::table).default_values().get_result::<Bike>(&*db).unwrap();
+        let token = insert_into(tokens::table).default_values().get_result::<Token>(&*db).unwrap();
+
+        let day = |d, h| NaiveDate::from_ymd(2021, 5, d).and_hms(h, 0, 0);
+
+        insert_into(rents::table).values(InsertRent {
+            token_id: token.id,
+            bike_id: bike1.id,
+            start_timestamp: day(1, 8),
+            end_timestamp: day(1, 12),
+        }).execute(&*db).unwrap();
+
+        let bike1 = bike::update_bike_buffers(&db, bike1.id, &BikeBufferData {
+            buffer_before_minutes: Some(30),
+            buffer_after_minutes: Some(60),
+        }).unwrap();
+
+        assert_eq!(availability::buffer_minutes(&bike1), (30, 60));
+        assert!(!availability::is_bike_available(&db, bike1.id, day(1, 12), day(1, 14)).unwrap());
+        assert!(availability::is_bike_available(&db, bike1.id, day(1, 12) + Duration::minutes(90), day(1, 16)).unwrap());
+        assert!(!availability::is_bike_available(&db, bike1.id, day(1, 4), day(1, 7)).unwrap());
+        assert!(availability::is_bike_available(&db, bike1.id, day(1, 4), day(1, 8) - Duration::minutes(90)).unwrap());
+        assert!(availability::get_available_bike_ids(&db, &[bike1.id], day(1, 12), day(1, 13)).unwrap().is_empty());
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_align_window() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike1 = insert_into(bikes::table).default_values().get_result::<Bike>(&*db).unwrap();
+        let bike2 = insert_into(bikes::table).default_values().get_result::<Bike>(&*db).unwrap();
+
+        let day = |d, h, m| NaiveDate::from_ymd(2021, 5, d).and_hms(h, m, 0);
+
+        assert_eq!(availability::align_window(&db, &[bike1.id], day(1, 13, 37), day(1, 15, 0)).unwrap(), (day(1, 13, 37), day(1, 15, 0)));
+
+        bike::update_bike_granularity(&db, bike1.id, BookingGranularity::Hourly).unwrap();
+        assert_eq!(availability::align_window(&db, &[bike1.id], day(1, 13, 37), day(1, 15, 0)).unwrap(), (day(1, 13, 0), day(1, 15, 0)));
+
+        bike::update_bike_granularity(&db, bike2.id, BookingGranularity::HalfDay).unwrap();
+        assert_eq!(availability::align_window(&db, &[bike1.id, bike2.id], day(1, 9, 0), day(1, 13, 0)).unwrap(), (day(1, 0, 0), day(2, 0, 0)));
+
+        bike::update_bike_granularity(&db, bike2.id, BookingGranularity::Day).unwrap();
+        assert_eq!(availability::align_window(&db, &[bike2.id], day(1, 0, 0), day(3, 0, 0)).unwrap(), (day(1, 0, 0), day(3, 0, 0)));
+        assert_eq!(availability::align_window(&db, &[bike2.id], day(1, 10, 0), day(2, 10, 0)).unwrap(), (day(1, 0, 0), day(3, 0, 0)));
+
+        Ok(())
+    });
+}
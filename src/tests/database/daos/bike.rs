@@ -1,7 +1,11 @@
+use chrono::{Duration,Utc};
+
 use diesel::{RunQueryDsl,Connection};
 use diesel::{insert_into};
 
-use crate::database::models::{Bike,InsertBikeTranslatable};
+use crate::database::daos::{availability,bike};
+use crate::database::models::{Bike,InsertBikeMedia,InsertBikeMediaTranslatable,InsertBikeStatusChange,InsertBikeTranslatable,InsertRent,Token};
+use crate::locale::LocalePreferences;
 use crate::schema::bikes::dsl::*;
 use crate::schema::bike_translatables::dsl::*;
 
@@ -11,7 +15,7 @@ pub fn test_get_bikes_without_bikes_in_database() {
 
     (&*db).test_transaction::<_, diesel::result::Error, _>(|| {
         let expected: usize = 0;
-        let actual: usize = crate::database::daos::bike::get_bikes(&db).unwrap().len();
+        let actual: usize = crate::database::daos::bike::get_bikes(&db, &LocalePreferences::new(vec![])).unwrap().len();
 
         assert_eq!(actual, expected);
 
@@ -45,11 +49,137 @@ pub fn test_get_bikes_with_bikes_in_database() {
         insert_into(bike_translatables).values(&values).execute(&*db).expect("Could not insert bike translatables.");
 
         let expected: usize = values.len();
-        let actual: usize = crate::database::daos::bike::get_bikes(&db).unwrap().len();
+        let actual: usize = crate::database::daos::bike::get_bikes(&db, &LocalePreferences::new(vec![])).unwrap().len();
 
         println!("{} - {}", expected, actual);
         assert_eq!(actual, expected);
 
         Ok(())
     });
-}
\ No newline at end of file
+}
+
+#[test]
+pub fn test_get_bikes_picks_best_translation_per_bike() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike1 = insert_into(bikes).default_values().get_result::<Bike>(&*db).unwrap();
+        let values = vec![
+            InsertBikeTranslatable {
+                bike_id: bike1.id,
+                locale: "de-DE".to_string(),
+                title: "Lastenrad".to_string(),
+                description: None,
+                url: None,
+            },
+            InsertBikeTranslatable {
+                bike_id: bike1.id,
+                locale: "en-GB".to_string(),
+                title: "Cargo bike".to_string(),
+                description: None,
+                url: None,
+            },
+        ];
+
+        insert_into(bike_translatables).values(&values).execute(&*db).expect("Could not insert bike translatables.");
+
+        let actual = crate::database::daos::bike::get_bikes(&db, &LocalePreferences::parse("en-US").unwrap()).unwrap();
+
+        assert_eq!(actual.len(), 1);
+        assert_eq!(actual[0].bike.title, "Cargo bike");
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_get_bikes_includes_ordered_media() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike1 = insert_into(bikes).default_values().get_result::<Bike>(&*db).unwrap();
+        let bike2 = insert_into(bikes).default_values().get_result::<Bike>(&*db).unwrap();
+
+        for bike in &[&bike1, &bike2] {
+            insert_into(bike_translatables).values(&InsertBikeTranslatable {
+                bike_id: bike.id,
+                locale: "de-DE".to_string(),
+                title: "Lastenrad".to_string(),
+                description: None,
+                url: None,
+            }).execute(&*db).unwrap();
+        }
+
+        let media = |p_position: i32, name: &str| InsertBikeMedia {
+            bike_id: bike1.id,
+            position: p_position,
+            url: format!("/uploads/bikes/{}-web.png", name),
+            width: 1600,
+            height: 1200,
+            thumbnail_url: format!("/uploads/bikes/{}-thumbnail.png", name),
+            thumbnail_width: 320,
+            thumbnail_height: 240,
+        };
+        let back = bike::insert_bike_media(&db, &media(2, "back")).unwrap();
+        let front = bike::insert_bike_media(&db, &media(1, "front")).unwrap();
+
+        bike::upsert_bike_media_translation(&db, &InsertBikeMediaTranslatable { bike_media_id: front.id, locale: "de-DE".to_string(), alt_text: "Vorne".to_string() }).unwrap();
+        bike::upsert_bike_media_translation(&db, &InsertBikeMediaTranslatable { bike_media_id: front.id, locale: "en-GB".to_string(), alt_text: "Front".to_string() }).unwrap();
+
+        let actual = bike::get_bikes(&db, &LocalePreferences::parse("en-GB").unwrap()).unwrap();
+        let gallery = actual.iter().find(|b| b.bike.bike_id == bike1.id).unwrap();
+
+        assert_eq!(gallery.media.iter().map(|m| m.id).collect::<Vec<_>>(), vec![front.id, back.id]);
+        assert_eq!(gallery.media[0].alt_text, Some("Front".to_string()));
+        assert_eq!(gallery.media[1].alt_text, None);
+        assert!(actual.iter().find(|b| b.bike.bike_id == bike2.id).unwrap().media.is_empty());
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_retire_bike() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike1 = insert_into(bikes).default_values().get_result::<Bike>(&*db).unwrap();
+        let token1 = insert_into(crate::schema::tokens::table).default_values().get_result::<Token>(&*db).unwrap();
+        let token2 = insert_into(crate::schema::tokens::table).default_values().get_result::<Token>(&*db).unwrap();
+        let now = Utc::now().naive_utc();
+
+        insert_into(bike_translatables).values(InsertBikeTranslatable {
+            bike_id: bike1.id,
+            locale: "de-DE".to_string(),
+            title: "Bike1".to_string(),
+            description: None,
+            url: None,
+        }).execute(&*db).unwrap();
+
+        insert_into(crate::schema::rents::table).values(vec![
+            InsertRent { token_id: token1.id, bike_id: bike1.id, start_timestamp: now - Duration::days(3), end_timestamp: now - Duration::days(2) },
+            InsertRent { token_id: token2.id, bike_id: bike1.id, start_timestamp: now + Duration::days(2), end_timestamp: now + Duration::days(3) },
+        ]).execute(&*db).unwrap();
+
+        assert_eq!(bike1.status, "active");
+        assert!(availability::is_bike_available(&db, bike1.id, now + Duration::days(5), now + Duration::days(6)).unwrap());
+
+        let update = bike::update_bike_status(&db, &InsertBikeStatusChange {
+            bike_id: bike1.id,
+            status: "retired".to_string(),
+            comment: Some("Stolen".to_string()),
+            changed_by: "admin".to_string(),
+        }).unwrap();
+
+        assert_eq!(update.change.status, "retired");
+        assert_eq!(update.flagged_rents.len(), 1);
+        assert_eq!(update.flagged_rents[0].token_id, token2.id);
+        assert!(update.flagged_rents[0].flag_reason.is_some());
+
+        assert!(bike::get_bikes(&db, &LocalePreferences::new(vec![])).unwrap().is_empty());
+        assert!(!availability::is_bike_available(&db, bike1.id, now + Duration::days(5), now + Duration::days(6)).unwrap());
+        assert_eq!(bike::get_bike_status_history(&db, bike1.id).unwrap().len(), 1);
+
+        Ok(())
+    });
+}
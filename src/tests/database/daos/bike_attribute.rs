@@ -0,0 +1,52 @@
+use diesel::{RunQueryDsl,Connection};
+use diesel::{insert_into};
+
+use crate::database::daos::bike_attribute;
+use crate::database::models::{AttributeValue,Bike,InsertBikeAttribute,InsertBikeAttributeOption,InsertBikeAttributeOptionTranslatable,InsertBikeAttributeTranslatable,InsertBikeAttributeValue};
+use crate::locale::LocalePreferences;
+use crate::schema::bikes::dsl::*;
+
+fn value(p_bike_id: i32, p_bike_attribute_id: i32) -> InsertBikeAttributeValue {
+    InsertBikeAttributeValue {
+        bike_id: p_bike_id,
+        bike_attribute_id: p_bike_attribute_id,
+        number_value: None,
+        boolean_value: None,
+        bike_attribute_option_id: None,
+    }
+}
+
+#[test]
+pub fn test_get_attributes_by_bike() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike = insert_into(bikes).default_values().get_result::<Bike>(&*db).unwrap();
+        let electric = bike_attribute::insert_bike_attribute(&db, &InsertBikeAttribute { key: "electric".to_string(), value_type: "boolean".to_string(), unit: None, sort_priority: 10 }).unwrap();
+        let capacity = bike_attribute::insert_bike_attribute(&db, &InsertBikeAttribute { key: "capacity_kg".to_string(), value_type: "number".to_string(), unit: Some("kg".to_string()), sort_priority: 5 }).unwrap();
+        let lock = bike_attribute::insert_bike_attribute(&db, &InsertBikeAttribute { key: "lock_type".to_string(), value_type: "enum".to_string(), unit: None, sort_priority: 0 }).unwrap();
+        let chain = bike_attribute::insert_bike_attribute_option(&db, &InsertBikeAttributeOption { bike_attribute_id: lock.id, key: "chain".to_string() }).unwrap();
+
+        bike_attribute::upsert_bike_attribute_translation(&db, &InsertBikeAttributeTranslatable { bike_attribute_id: capacity.id, locale: "en-GB".to_string(), label: "Load capacity".to_string() }).unwrap();
+        bike_attribute::upsert_bike_attribute_option_translation(&db, &InsertBikeAttributeOptionTranslatable { bike_attribute_option_id: chain.id, locale: "de-DE".to_string(), label: "Kettenschloss".to_string() }).unwrap();
+
+        bike_attribute::upsert_bike_attribute_value(&db, &InsertBikeAttributeValue { number_value: Some(80.0), ..value(bike.id, capacity.id) }).unwrap();
+        bike_attribute::upsert_bike_attribute_value(&db, &InsertBikeAttributeValue { number_value: Some(100.0), ..value(bike.id, capacity.id) }).unwrap();
+        bike_attribute::upsert_bike_attribute_value(&db, &InsertBikeAttributeValue { boolean_value: Some(true), ..value(bike.id, electric.id) }).unwrap();
+        bike_attribute::upsert_bike_attribute_value(&db, &InsertBikeAttributeValue { bike_attribute_option_id: Some(chain.id), ..value(bike.id, lock.id) }).unwrap();
+
+        let actual = bike_attribute::get_attributes_by_bike(&db, &LocalePreferences::parse("en-GB").unwrap()).unwrap()
+            .into_iter()
+            .filter(|(attribute_bike_id, _)| *attribute_bike_id == bike.id)
+            .map(|(_, attribute)| (attribute.key, attribute.label, attribute.value, attribute.value_label))
+            .collect::<Vec<_>>();
+
+        assert_eq!(actual, vec![
+            ("electric".to_string(), None, AttributeValue::Boolean(true), None),
+            ("capacity_kg".to_string(), Some("Load capacity".to_string()), AttributeValue::Number(100.0), None),
+            ("lock_type".to_string(), None, AttributeValue::Option("chain".to_string()), Some("Kettenschloss".to_string())),
+        ]);
+
+        Ok(())
+    });
+}
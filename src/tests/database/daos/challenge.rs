@@ -1,7 +1,10 @@
+use chrono::{Duration,Utc};
+
 use diesel::{RunQueryDsl,Connection};
 use diesel::{insert_into};
 
 use crate::database::models::{TokenChallenge,InsertTokenChallengeTranslatable,ChallengeResponse};
+use crate::locale::LocalePreferences;
 use crate::schema::token_challenges::dsl::*;
 use crate::schema::token_challenge_translatables::dsl::*;
 
@@ -67,6 +70,55 @@ pub fn test_test_challenge_fails() {
 
         assert_eq!(actual.is_ok(), false);
 
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_get_random_challenge_prefers_best_locale() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        for challenge_locale in &["de-DE", "sv-SE"] {
+            let token_challenge = insert_into(token_challenges).default_values().get_result::<TokenChallenge>(&*db).unwrap();
+
+            insert_into(token_challenge_translatables)
+                .values(InsertTokenChallengeTranslatable {
+                    token_challenge_id: token_challenge.id,
+                    locale: challenge_locale.to_string(),
+                    question: "".to_string(),
+                    answer_hash: "123".to_string(),
+                    url: None,
+                })
+                .execute(&*db)
+                .expect("Could not insert token challenge translatables.");
+        }
+
+        let preferences = LocalePreferences::parse("sv-SE").unwrap();
+
+        for _ in 0..5 {
+            let challenge = crate::database::daos::challenge::get_random_challenge(&db, &preferences).unwrap();
+
+            assert_eq!(challenge.locale, "sv-SE");
+        }
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_redeem_proof_of_work_once() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let expires_at = Utc::now().naive_utc() + Duration::minutes(5);
+
+        assert!(crate::database::daos::challenge::redeem_proof_of_work(&db, "00112233445566778899aabbccddeeff", expires_at).is_ok());
+        assert!(crate::database::daos::challenge::redeem_proof_of_work(&db, "00112233445566778899aabbccddeeff", expires_at).is_err());
+
+        assert_eq!(crate::database::daos::challenge::delete_expired_proof_of_work_nonces(&db, expires_at).unwrap(), 0);
+        assert_eq!(crate::database::daos::challenge::delete_expired_proof_of_work_nonces(&db, expires_at + Duration::seconds(1)).unwrap(), 1);
+
         Ok(())
     });
 }
\ No newline at end of file
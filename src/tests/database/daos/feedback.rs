@@ -0,0 +1,70 @@
+use chrono::NaiveDate;
+
+use diesel::{RunQueryDsl,Connection};
+use diesel::insert_into;
+
+use crate::database::daos::feedback;
+use crate::database::models::{Bike,BikeFeedbackSummary,InsertRent,InsertRentFeedback,Rent,Token};
+use crate::schema::{bikes,rents,tokens};
+
+#[test]
+pub fn test_feedback_per_bike() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike1 = insert_into(bikes::table).default_values().get_result::<Bike>(&*db).unwrap();
+        let bike2 = insert_into(bikes::table).default_values().get_result::<Bike>(&*db).unwrap();
+
+        let mut rent_ids = vec![];
+        for (day, bike_id) in &[(1, bike1.id), (2, bike1.id), (3, bike2.id)] {
+            let token = insert_into(tokens::table).default_values().get_result::<Token>(&*db).unwrap();
+            let rent = insert_into(rents::table).values(InsertRent {
+                token_id: token.id,
+                bike_id: *bike_id,
+                start_timestamp: NaiveDate::from_ymd(2021, 5, *day).and_hms(8, 0, 0),
+                end_timestamp: NaiveDate::from_ymd(2021, 5, *day).and_hms(18, 0, 0),
+            }).get_result::<Rent>(&*db).unwrap();
+            rent_ids.push(rent.id);
+        }
+
+        for (rent_id, rating, distance_km) in &[(rent_ids[0], 5, Some(10.0)), (rent_ids[1], 2, None), (rent_ids[2], 4, Some(3.5))] {
+            feedback::insert_feedback(&db, &InsertRentFeedback {
+                rent_id: *rent_id,
+                rating: *rating,
+                comment: None,
+                distance_km: *distance_km,
+            }).unwrap();
+        }
+
+        assert_eq!(feedback::get_feedback_by_bike(&db, bike1.id).unwrap().len(), 2);
+        assert_eq!(feedback::get_feedback_summaries(&db).unwrap(), vec![
+            BikeFeedbackSummary { bike_id: bike1.id, feedback_count: 2, average_rating: 3.5, total_distance_km: 10.0 },
+            BikeFeedbackSummary { bike_id: bike2.id, feedback_count: 1, average_rating: 4.0, total_distance_km: 3.5 },
+        ]);
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_feedback_once_per_rent() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike1 = insert_into(bikes::table).default_values().get_result::<Bike>(&*db).unwrap();
+        let token = insert_into(tokens::table).default_values().get_result::<Token>(&*db).unwrap();
+        let rent = insert_into(rents::table).values(InsertRent {
+            token_id: token.id,
+            bike_id: bike1.id,
+            start_timestamp: NaiveDate::from_ymd(2021, 5, 1).and_hms(8, 0, 0),
+            end_timestamp: NaiveDate::from_ymd(2021, 5, 1).and_hms(18, 0, 0),
+        }).get_result::<Rent>(&*db).unwrap();
+
+        let data = InsertRentFeedback { rent_id: rent.id, rating: 3, comment: None, distance_km: None };
+
+        assert!(feedback::insert_feedback(&db, &data).is_ok());
+        assert!(feedback::insert_feedback(&db, &data).is_err());
+
+        Ok(())
+    });
+}
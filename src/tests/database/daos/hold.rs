@@ -0,0 +1,91 @@
+use chrono::{Duration,Utc};
+
+use diesel::{RunQueryDsl,Connection,ExpressionMethods};
+use diesel::update;
+
+use crate::database::daos::{hold,rent};
+use crate::database::models::{Booking,HoldRequest};
+use crate::schema::rent_holds;
+use crate::tests::database::{booking,insert_bike,insert_token,tomorrow};
+
+#[test]
+pub fn test_hold_blocks_other_bookings() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike = insert_bike(&db);
+        let token = insert_token(&db);
+        let other_token = insert_token(&db);
+        let request = HoldRequest {
+            token: token.uuid,
+            bike_id: bike.id,
+            start_timestamp: tomorrow(8),
+            end_timestamp: tomorrow(12),
+            accessories: vec![],
+        };
+
+        let held = hold::create_hold(&db, &request).unwrap();
+
+        assert!(hold::create_hold(&db, &HoldRequest { token: other_token.uuid, ..request.clone() }).is_err());
+        assert!(rent::insert_booking(&db, &booking(&token, &bike, tomorrow(9), tomorrow(11), None)).is_err());
+        assert!(rent::insert_booking(&db, &Booking { hold_id: Some(held.uuid), ..booking(&other_token, &bike, tomorrow(9), tomorrow(11), None) }).is_err());
+        assert!(rent::insert_booking(&db, &Booking { hold_id: Some(held.uuid), ..booking(&token, &bike, tomorrow(9), tomorrow(11), None) }).is_ok());
+        assert_eq!(hold::delete_hold(&db, &held.uuid).unwrap(), 0);
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_expired_hold() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike = insert_bike(&db);
+        let token = insert_token(&db);
+
+        let held = hold::create_hold(&db, &HoldRequest {
+            token: token.uuid,
+            bike_id: bike.id,
+            start_timestamp: tomorrow(8),
+            end_timestamp: tomorrow(12),
+            accessories: vec![],
+        }).unwrap();
+
+        update(rent_holds::table)
+            .set(rent_holds::expires_at.eq(Utc::now().naive_utc() - Duration::minutes(1)))
+            .execute(&*db)
+            .unwrap();
+
+        assert!(rent::insert_booking(&db, &Booking { hold_id: Some(held.uuid), ..booking(&token, &bike, tomorrow(9), tomorrow(11), None) }).is_err());
+        assert_eq!(hold::delete_expired_holds(&db, Utc::now().naive_utc()).unwrap(), 1);
+        assert!(rent::insert_booking(&db, &booking(&token, &bike, tomorrow(9), tomorrow(11), None)).is_ok());
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_hold_limits() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike = insert_bike(&db);
+        let other_bike = insert_bike(&db);
+        let token = insert_token(&db);
+        let request = HoldRequest {
+            token: token.uuid,
+            bike_id: bike.id,
+            start_timestamp: tomorrow(8),
+            end_timestamp: tomorrow(8) + Duration::days(8),
+            accessories: vec![],
+        };
+
+        assert!(hold::create_hold(&db, &request).is_err());
+        assert!(hold::create_hold(&db, &HoldRequest { token: uuid::Uuid::parse_str("00a791f1-68b8-457c-82d9-a060f48efbae").unwrap(), end_timestamp: tomorrow(12), ..request.clone() }).is_err());
+        assert!(hold::create_hold(&db, &HoldRequest { end_timestamp: tomorrow(12), ..request.clone() }).is_ok());
+        assert!(hold::create_hold(&db, &HoldRequest { bike_id: other_bike.id, end_timestamp: tomorrow(12), ..request.clone() }).is_err());
+
+        Ok(())
+    });
+}
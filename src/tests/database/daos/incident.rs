@@ -0,0 +1,65 @@
+use chrono::NaiveDate;
+
+use diesel::{RunQueryDsl,Connection};
+use diesel::insert_into;
+
+use crate::database::daos::{incident,rent};
+use crate::database::models::{Bike,InsertBikeIncident,InsertBikeIncidentPhoto,InsertRent,Rent,Token};
+use crate::schema::{bikes,rents,tokens};
+
+#[test]
+pub fn test_incidents_of_rent() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike1 = insert_into(bikes::table).default_values().get_result::<Bike>(&*db).unwrap();
+        let bike2 = insert_into(bikes::table).default_values().get_result::<Bike>(&*db).unwrap();
+        let token = insert_into(tokens::table).default_values().get_result::<Token>(&*db).unwrap();
+        let rent1 = insert_into(rents::table).values(InsertRent {
+            token_id: token.id,
+            bike_id: bike1.id,
+            start_timestamp: NaiveDate::from_ymd(2021, 5, 1).and_hms(8, 0, 0),
+            end_timestamp: NaiveDate::from_ymd(2021, 5, 1).and_hms(18, 0, 0),
+        }).get_result::<Rent>(&*db).unwrap();
+
+        let other_token = insert_into(tokens::table).default_values().get_result::<Token>(&*db).unwrap();
+        let rent2 = insert_into(rents::table).values(InsertRent {
+            token_id: other_token.id,
+            bike_id: bike2.id,
+            start_timestamp: NaiveDate::from_ymd(2021, 5, 1).and_hms(8, 0, 0),
+            end_timestamp: NaiveDate::from_ymd(2021, 5, 1).and_hms(18, 0, 0),
+        }).get_result::<Rent>(&*db).unwrap();
+
+        assert_eq!(rent::get_rent_by_token(&db, &token.uuid, rent1.id).unwrap().id, rent1.id);
+        assert!(rent::get_rent_by_token(&db, &token.uuid, rent2.id).is_err());
+
+        let incident1 = incident::insert_incident(&db, &InsertBikeIncident {
+            rent_id: rent1.id,
+            bike_id: rent1.bike_id,
+            category: "crash".to_string(),
+            description: "Hit a curb.".to_string(),
+            reported_by: None,
+        }).unwrap();
+
+        let photo = InsertBikeIncidentPhoto {
+            bike_incident_id: incident1.id,
+            url: "/uploads/incidents/a-web.png".to_string(),
+            width: 800,
+            height: 600,
+            thumbnail_url: "/uploads/incidents/a-thumbnail.png".to_string(),
+            thumbnail_width: 320,
+            thumbnail_height: 240,
+        };
+
+        incident::add_incident_photo(&db, &photo, 1).unwrap();
+        assert!(incident::add_incident_photo(&db, &photo, 1).is_err());
+
+        let incidents = incident::get_incidents_by_bike(&db, bike1.id).unwrap();
+        assert_eq!(incidents.len(), 1);
+        assert_eq!(incidents[0].photos.len(), 1);
+        assert_eq!(incident::count_incident_photos(&db, incident1.id).unwrap(), 1);
+        assert!(incident::get_incidents_by_bike(&db, bike2.id).unwrap().is_empty());
+
+        Ok(())
+    });
+}
@@ -0,0 +1,75 @@
+use chrono::{Duration,Utc};
+
+use diesel::Connection;
+
+use crate::database::models::InviteCodeRequest;
+use crate::routes::errors::InviteError;
+
+#[test]
+pub fn test_redeem_invite_code_until_used_up() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let request = InviteCodeRequest {
+            group_name: "Kindergarten".to_string(),
+            usage_limit: 2,
+            valid_from: None,
+            valid_until: None,
+            count: Some(1),
+        };
+        let invite_codes = crate::database::daos::invite::insert_invite_codes(&db, &request, "admin").unwrap();
+
+        assert_eq!(invite_codes.len(), 1);
+
+        let code = invite_codes[0].code.to_lowercase();
+
+        assert_eq!(crate::database::daos::invite::redeem_invite_code(&db, &code).is_ok(), true);
+        assert_eq!(crate::database::daos::invite::redeem_invite_code(&db, &code).is_ok(), true);
+
+        let actual = crate::database::daos::invite::redeem_invite_code(&db, &code);
+
+        assert!(matches!(actual, Err(InviteError::Validation(_))));
+
+        let usage = crate::database::daos::invite::get_invite_codes(&db).unwrap();
+
+        assert_eq!(usage[0].usage_count, 2);
+        assert_eq!(crate::database::daos::invite::get_redemptions(&db, invite_codes[0].id).unwrap().len(), 2);
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_redeem_invite_code_outside_validity_window() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let request = InviteCodeRequest {
+            group_name: "Association".to_string(),
+            usage_limit: 10,
+            valid_from: Some(Utc::now().naive_utc() + Duration::days(1)),
+            valid_until: Some(Utc::now().naive_utc() + Duration::days(2)),
+            count: Some(1),
+        };
+        let invite_codes = crate::database::daos::invite::insert_invite_codes(&db, &request, "admin").unwrap();
+
+        let actual = crate::database::daos::invite::redeem_invite_code(&db, &invite_codes[0].code);
+
+        assert!(matches!(actual, Err(InviteError::Validation(_))));
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_redeem_unknown_invite_code() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let actual = crate::database::daos::invite::redeem_invite_code(&db, "UNKNOWN");
+
+        assert!(matches!(actual, Err(InviteError::Validation(_))));
+
+        Ok(())
+    });
+}
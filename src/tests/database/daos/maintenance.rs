@@ -0,0 +1,106 @@
+use chrono::{Duration,Utc};
+
+use diesel::{RunQueryDsl,QueryDsl,Connection,ExpressionMethods};
+use diesel::{insert_into,update};
+
+use crate::database::daos::{availability,maintenance};
+use crate::database::models::{Bike,BikeServiceInterval,InsertBikeServiceInterval,InsertBikeWorkOrder,InsertRent,Token};
+use crate::schema::{bike_service_intervals,bikes,rents,tokens};
+
+#[test]
+pub fn test_service_due_after_rents() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike1 = insert_into(bikes::table).default_values().get_result::<Bike>(&*db).unwrap();
+        let now = Utc::now().naive_utc();
+
+        let interval = maintenance::insert_service_interval(&db, &InsertBikeServiceInterval {
+            bike_id: bike1.id,
+            title: "Brakes".to_string(),
+            interval_days: Some(90),
+            interval_rents: Some(2),
+            blocks_bookings: true,
+        }).unwrap();
+        let interval = update(bike_service_intervals::table.find(interval.id))
+            .set(bike_service_intervals::created_at.eq(now - Duration::days(10)))
+            .get_result::<BikeServiceInterval>(&*db)
+            .unwrap();
+
+        let window = (now + Duration::days(1), now + Duration::days(2));
+        assert!(maintenance::get_due_services(&db, now).unwrap().is_empty());
+        assert!(availability::is_bike_available(&db, bike1.id, window.0, window.1).unwrap());
+
+        for days in 1..3 {
+            let token = insert_into(tokens::table).default_values().get_result::<Token>(&*db).unwrap();
+            insert_into(rents::table).values(InsertRent {
+                token_id: token.id,
+                bike_id: bike1.id,
+                start_timestamp: interval.created_at + Duration::hours(days),
+                end_timestamp: interval.created_at + Duration::hours(days) + Duration::minutes(30),
+            }).execute(&*db).unwrap();
+        }
+
+        let as_of = interval.created_at + Duration::hours(4);
+        let due = maintenance::get_due_services(&db, as_of).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].rents_since, 2);
+        assert_eq!(due[0].days_since, 0);
+        assert!(maintenance::get_due_services(&db, now).unwrap()[0].blocks_bookings);
+        assert!(!availability::is_bike_available(&db, bike1.id, window.0, window.1).unwrap());
+        assert!(availability::get_available_bike_ids(&db, &[bike1.id], window.0, window.1).unwrap().is_empty());
+
+        maintenance::insert_work_order(&db, &InsertBikeWorkOrder {
+            bike_id: bike1.id,
+            bike_service_interval_id: Some(interval.id),
+            description: "Replaced brake pads".to_string(),
+            parts: Some("Brake pads".to_string()),
+            cost_cents: Some(3000),
+            performed_by: "Kim".to_string(),
+            performed_at: interval.created_at + Duration::hours(3),
+        }).unwrap();
+
+        assert!(maintenance::get_due_services(&db, as_of).unwrap().is_empty());
+        let due = maintenance::get_due_services(&db, interval.created_at + Duration::days(100)).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].last_service_at, interval.created_at + Duration::hours(3));
+        assert_eq!(due[0].days_since, 99);
+        assert_eq!(due[0].rents_since, 0);
+        assert!(availability::is_bike_available(&db, bike1.id, window.0, window.1).unwrap());
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_service_blocks_windows_once_due() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike1 = insert_into(bikes::table).default_values().get_result::<Bike>(&*db).unwrap();
+        let bike2 = insert_into(bikes::table).default_values().get_result::<Bike>(&*db).unwrap();
+        let now = Utc::now().naive_utc();
+
+        let interval = maintenance::insert_service_interval(&db, &InsertBikeServiceInterval {
+            bike_id: bike1.id,
+            title: "Inspection".to_string(),
+            interval_days: Some(30),
+            interval_rents: None,
+            blocks_bookings: true,
+        }).unwrap();
+        update(bike_service_intervals::table.find(interval.id))
+            .set(bike_service_intervals::created_at.eq(now - Duration::days(10)))
+            .execute(&*db)
+            .unwrap();
+
+        let soon = (now + Duration::days(1), now + Duration::days(2));
+        let later = (now + Duration::days(25), now + Duration::days(26));
+
+        assert!(availability::is_bike_available(&db, bike1.id, soon.0, soon.1).unwrap());
+        assert!(!availability::is_bike_available(&db, bike1.id, later.0, later.1).unwrap());
+        assert_eq!(availability::get_available_bike_ids(&db, &[bike1.id, bike2.id], later.0, later.1).unwrap(), vec![bike2.id]);
+        assert_eq!(maintenance::get_blocked_bike_ids(&db, &[bike2.id], later.0).unwrap(), Vec::<i32>::new());
+
+        Ok(())
+    });
+}
@@ -0,0 +1,104 @@
+use diesel::Connection;
+
+use mocktopus::mocking::Mockable;
+use mocktopus::mocking::MockResult;
+
+use crate::config;
+use crate::database::DbConn;
+use crate::database::daos::{rent,rent_series,verification};
+use crate::database::models::{BookingReceipt,RecurringBooking,RiderQuota};
+use crate::tests::database::{booking,insert_bike,insert_token,tomorrow};
+
+fn confirm(db: &DbConn, receipt: &BookingReceipt) {
+    verification::verify_email(db, &receipt.verification.as_ref().unwrap().uuid).unwrap();
+}
+
+fn setup(max_active_bookings: i64, max_booked_hours_per_month: i64) {
+    config::rider_hash_secret.mock_safe(|| MockResult::Return(Some("test secret".to_string())));
+    config::max_active_bookings.mock_safe(move || MockResult::Return(max_active_bookings));
+    config::max_booked_hours_per_month.mock_safe(move || MockResult::Return(max_booked_hours_per_month));
+}
+
+#[test]
+pub fn test_active_booking_limit() {
+    let db = crate::tests::get_database_connection();
+
+    setup(1, 0);
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike = insert_bike(&db);
+
+        let receipt = rent::insert_booking(&db, &booking(&insert_token(&db), &bike, tomorrow(9), tomorrow(11), Some("someone@somewhere.near"))).unwrap();
+        assert_eq!(receipt.quota, Some(RiderQuota { active_bookings_remaining: Some(0), booked_hours_remaining: None }));
+
+        // Unconfirmed bookings do not count, but cannot be confirmed beyond the limit.
+        let unconfirmed = rent::insert_booking(&db, &booking(&insert_token(&db), &bike, tomorrow(12), tomorrow(14), Some("someone+bikes@somewhere.near"))).unwrap();
+        confirm(&db, &receipt);
+        assert!(verification::verify_email(&db, &unconfirmed.verification.unwrap().uuid).is_err());
+
+        assert!(rent::insert_booking(&db, &booking(&insert_token(&db), &bike, tomorrow(15), tomorrow(16), Some("SOMEONE@somewhere.near"))).is_err());
+        assert!(rent::insert_booking(&db, &booking(&insert_token(&db), &bike, tomorrow(15), tomorrow(16), None)).is_err());
+        assert!(rent::insert_booking(&db, &booking(&insert_token(&db), &bike, tomorrow(15), tomorrow(16), Some("someone.else@somewhere.near"))).is_ok());
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_booked_hours_limit() {
+    let db = crate::tests::get_database_connection();
+
+    setup(0, 3);
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike = insert_bike(&db);
+
+        let receipt = rent::insert_booking(&db, &booking(&insert_token(&db), &bike, tomorrow(9), tomorrow(11), Some("someone@somewhere.near"))).unwrap();
+        assert_eq!(receipt.quota, Some(RiderQuota { active_bookings_remaining: None, booked_hours_remaining: Some(1.0) }));
+        confirm(&db, &receipt);
+
+        assert!(rent::insert_booking(&db, &booking(&insert_token(&db), &bike, tomorrow(12), tomorrow(14), Some("someone@somewhere.near"))).is_err());
+
+        let receipt = rent::insert_booking(&db, &booking(&insert_token(&db), &bike, tomorrow(12), tomorrow(13), Some("someone@somewhere.near"))).unwrap();
+        assert_eq!(receipt.quota, Some(RiderQuota { active_bookings_remaining: None, booked_hours_remaining: Some(0.0) }));
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_series_occurrences_count_as_bookings() {
+    let db = crate::tests::get_database_connection();
+
+    setup(3, 0);
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike = insert_bike(&db);
+        let token = insert_token(&db);
+
+        let mut series = RecurringBooking {
+            token: token.uuid,
+            bike_id: bike.id,
+            start_timestamp: tomorrow(9),
+            end_timestamp: tomorrow(11),
+            encrypted_details: "".to_string(),
+            short_token: "".to_string(),
+            email: Some("someone@somewhere.near".to_string()),
+            frequency: "weekly".to_string(),
+            until_date: None,
+            occurrence_count: Some(4),
+            hold_id: None,
+        };
+        assert!(rent_series::insert_recurring_booking(&db, &series).is_err());
+
+        series.occurrence_count = Some(2);
+        let booked = rent_series::insert_recurring_booking(&db, &series).unwrap();
+        assert_eq!(booked.quota, Some(RiderQuota { active_bookings_remaining: Some(1), booked_hours_remaining: None }));
+        verification::verify_email(&db, &booked.verification.unwrap().uuid).unwrap();
+
+        let receipt = rent::insert_booking(&db, &booking(&insert_token(&db), &bike, tomorrow(12), tomorrow(14), Some("someone@somewhere.near"))).unwrap();
+        assert_eq!(receipt.quota, Some(RiderQuota { active_bookings_remaining: Some(0), booked_hours_remaining: None }));
+
+        Ok(())
+    });
+}
@@ -1,9 +1,10 @@
 use chrono::prelude::*;
 
-use diesel::{RunQueryDsl,QueryDsl,Connection};
-use diesel::{insert_into};
+use diesel::{RunQueryDsl,QueryDsl,ExpressionMethods,Connection};
+use diesel::{insert_into,update};
 
 use crate::database::DbConn;
+use crate::routes::errors::RentError;
 
 use crate::database::models::{Bike,Token,InsertRent,Booking};
 use crate::schema::bikes::dsl::*;
@@ -93,6 +94,9 @@ pub fn test_insert_booking_succeeds() {
             encrypted_details: "".to_string(),
             short_token: "".to_string(),
             email: None,
+            additional_bike_ids: vec![],
+            accessories: vec![],
+            hold_id: None,
         };
 
         let actual = crate::database::daos::rent::insert_booking(&db, &booking);
@@ -126,6 +130,9 @@ pub fn test_insert_booking_fails() {
             encrypted_details: "".to_string(),
             short_token: "".to_string(),
             email: None,
+            additional_bike_ids: vec![],
+            accessories: vec![],
+            hold_id: None,
         };
 
         let actual = crate::database::daos::rent::insert_booking(&db, &booking);
@@ -166,4 +173,176 @@ pub fn test_revoke_booking_fails() {
 
         Ok(())
     });
-}
\ No newline at end of file
+}
+
+#[test]
+pub fn test_insert_booking_fails_for_used_token() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let test_tokens = setup_database(&db);
+
+        let bike1 = bikes.order_by(crate::schema::bikes::dsl::id)
+            .limit(1)
+            .get_result::<Bike>(&*db)
+            .unwrap();
+        let booking = Booking {
+            token: test_tokens[0].uuid,
+            bike_id: bike1.id,
+            start_timestamp: DateTime::parse_from_rfc3339(&"1970-01-05T00:00:00.000Z".to_string()).unwrap().naive_utc(),
+            end_timestamp: DateTime::parse_from_rfc3339(&"1970-01-06T00:00:00.000Z".to_string()).unwrap().naive_utc(),
+            encrypted_details: "".to_string(),
+            short_token: "".to_string(),
+            email: None,
+            additional_bike_ids: vec![],
+            accessories: vec![],
+            hold_id: None,
+        };
+
+        let actual = crate::database::daos::rent::insert_booking(&db, &booking);
+
+        assert!(matches!(actual, Err(RentError::TokenUsed(_))));
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_insert_booking_fails_for_token_booked_on_other_bike() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike1 = crate::tests::database::insert_bike(&db);
+        let bike2 = crate::tests::database::insert_bike(&db);
+        let token = crate::tests::database::insert_token(&db);
+        let start = crate::tests::database::tomorrow(10);
+        let end = crate::tests::database::tomorrow(12);
+
+        let first = crate::database::daos::rent::insert_booking(&db, &crate::tests::database::booking(&token, &bike1, start, end, None));
+        let second = crate::database::daos::rent::insert_booking(&db, &crate::tests::database::booking(&token, &bike2, start, end, None));
+
+        assert!(first.is_ok());
+        assert!(matches!(second, Err(RentError::TokenUsed(_))));
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_insert_booking_fails_for_expired_token() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        setup_database(&db);
+
+        let bike1 = bikes.order_by(crate::schema::bikes::dsl::id)
+            .limit(1)
+            .get_result::<Bike>(&*db)
+            .unwrap();
+        let token3 = insert_into(tokens)
+            .default_values()
+            .get_result::<Token>(&*db)
+            .unwrap();
+
+        update(tokens.filter(crate::schema::tokens::dsl::id.eq(token3.id)))
+            .set(crate::schema::tokens::dsl::created_at.eq(DateTime::parse_from_rfc3339(&"1970-01-01T00:00:00.000Z".to_string()).unwrap().naive_utc()))
+            .execute(&*db)
+            .unwrap();
+
+        let booking = Booking {
+            token: token3.uuid,
+            bike_id: bike1.id,
+            start_timestamp: DateTime::parse_from_rfc3339(&"1970-01-05T00:00:00.000Z".to_string()).unwrap().naive_utc(),
+            end_timestamp: DateTime::parse_from_rfc3339(&"1970-01-06T00:00:00.000Z".to_string()).unwrap().naive_utc(),
+            encrypted_details: "".to_string(),
+            short_token: "".to_string(),
+            email: None,
+            additional_bike_ids: vec![],
+            accessories: vec![],
+            hold_id: None,
+        };
+
+        let actual = crate::database::daos::rent::insert_booking(&db, &booking);
+
+        assert!(matches!(actual, Err(RentError::TokenExpired(_))));
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_insert_booking_with_several_bikes() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        setup_database(&db);
+
+        let bike1 = bikes.order_by(crate::schema::bikes::dsl::id)
+            .limit(1)
+            .get_result::<Bike>(&*db)
+            .unwrap();
+        let bike2 = insert_into(bikes).default_values().get_result::<Bike>(&*db).unwrap();
+        let token3 = insert_into(tokens)
+            .default_values()
+            .get_result::<Token>(&*db)
+            .unwrap();
+        let booking = Booking {
+            token: token3.uuid,
+            bike_id: bike1.id,
+            start_timestamp: DateTime::parse_from_rfc3339(&"1970-01-05T00:00:00.000Z".to_string()).unwrap().naive_utc(),
+            end_timestamp: DateTime::parse_from_rfc3339(&"1970-01-06T00:00:00.000Z".to_string()).unwrap().naive_utc(),
+            encrypted_details: "".to_string(),
+            short_token: "".to_string(),
+            email: None,
+            additional_bike_ids: vec![bike2.id],
+            accessories: vec![],
+            hold_id: None,
+        };
+
+        assert_eq!(crate::database::daos::rent::insert_booking(&db, &booking).is_ok(), true);
+        assert_eq!(crate::database::daos::rent::get_rents_by_token(&db, &token3.uuid).unwrap().len(), 2);
+
+        assert_eq!(crate::database::daos::rent::revoke_booking(&db, &token3.uuid).is_ok(), true);
+        assert_eq!(crate::database::daos::rent::get_rents_by_token(&db, &token3.uuid).unwrap()
+            .iter()
+            .all(|rent| rent.revocation_timestamp.is_some()), true);
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_insert_booking_with_several_bikes_fails_as_a_whole() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        setup_database(&db);
+
+        let bike1 = bikes.order_by(crate::schema::bikes::dsl::id)
+            .limit(1)
+            .get_result::<Bike>(&*db)
+            .unwrap();
+        let bike2 = insert_into(bikes).default_values().get_result::<Bike>(&*db).unwrap();
+        let token3 = insert_into(tokens)
+            .default_values()
+            .get_result::<Token>(&*db)
+            .unwrap();
+        let booking = Booking {
+            token: token3.uuid,
+            bike_id: bike2.id,
+            start_timestamp: DateTime::parse_from_rfc3339(&"1970-01-01T00:00:00.000Z".to_string()).unwrap().naive_utc(),
+            end_timestamp: DateTime::parse_from_rfc3339(&"1970-01-02T00:00:00.000Z".to_string()).unwrap().naive_utc(),
+            encrypted_details: "".to_string(),
+            short_token: "".to_string(),
+            email: None,
+            additional_bike_ids: vec![bike1.id],
+            accessories: vec![],
+            hold_id: None,
+        };
+
+        assert_eq!(crate::database::daos::rent::insert_booking(&db, &booking).is_err(), true);
+        assert_eq!(crate::database::daos::rent::get_rents_by_token(&db, &token3.uuid).unwrap().len(), 0);
+
+        Ok(())
+    });
+}
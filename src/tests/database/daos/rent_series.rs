@@ -0,0 +1,207 @@
+use chrono::{NaiveDate,NaiveDateTime,Utc};
+use chrono_tz::Tz;
+
+use mocktopus::mocking::Mockable;
+use mocktopus::mocking::MockResult;
+
+use diesel::{RunQueryDsl,Connection,ExpressionMethods};
+use diesel::insert_into;
+
+use crate::config;
+use crate::database::DbConn;
+use crate::database::daos::{hold,rent_series};
+use crate::database::models::{Bike,HoldRequest,InsertRent,RecurrenceFrequency,RecurringBooking,Token};
+use crate::schema::{bikes,rents,tokens};
+
+fn timestamp(day: u32, hour: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd(2021, 6, day).and_hms(hour, 0, 0)
+}
+
+fn recurring_booking(token: &Token, bike: &Bike) -> RecurringBooking {
+    RecurringBooking {
+        token: token.uuid,
+        bike_id: bike.id,
+        start_timestamp: timestamp(1, 14),
+        end_timestamp: timestamp(1, 18),
+        encrypted_details: "".to_string(),
+        short_token: "".to_string(),
+        email: None,
+        frequency: "weekly".to_string(),
+        until_date: None,
+        occurrence_count: Some(4),
+        hold_id: None,
+    }
+}
+
+fn setup_database(db: &DbConn) -> (Bike, Token) {
+    let bike = insert_into(bikes::table).default_values().get_result::<Bike>(&**db).unwrap();
+    let token = insert_into(tokens::table).default_values().get_result::<Token>(&**db).unwrap();
+
+    (bike, token)
+}
+
+#[test]
+pub fn test_expand_occurrences() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let (bike, token) = setup_database(&db);
+
+        let mut booking = recurring_booking(&token, &bike);
+        booking.occurrence_count = None;
+        booking.until_date = Some(NaiveDate::from_ymd(2021, 6, 29));
+
+        let weekly = rent_series::expand_occurrences(&booking, RecurrenceFrequency::Weekly).unwrap();
+        assert_eq!(weekly.iter().map(|o| o.start_timestamp).collect::<Vec<_>>(), vec![timestamp(1, 14), timestamp(8, 14), timestamp(15, 14), timestamp(22, 14), timestamp(29, 14)]);
+
+        let biweekly = rent_series::expand_occurrences(&booking, RecurrenceFrequency::Biweekly).unwrap();
+        assert_eq!(biweekly.iter().map(|o| o.end_timestamp).collect::<Vec<_>>(), vec![timestamp(1, 18), timestamp(15, 18), timestamp(29, 18)]);
+
+        booking.occurrence_count = Some(2);
+        assert!(rent_series::expand_occurrences(&booking, RecurrenceFrequency::Weekly).is_err());
+
+        booking.until_date = None;
+        booking.occurrence_count = Some(1000);
+        assert!(rent_series::expand_occurrences(&booking, RecurrenceFrequency::Weekly).is_err());
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_expand_occurrences_keeps_local_time() {
+    let db = crate::tests::get_database_connection();
+
+    config::timezone.mock_safe(|| MockResult::Return(Tz::Europe__Berlin));
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let (bike, token) = setup_database(&db);
+
+        let mut booking = recurring_booking(&token, &bike);
+        booking.start_timestamp = NaiveDate::from_ymd(2021, 3, 21).and_hms(9, 0, 0);
+        booking.end_timestamp = NaiveDate::from_ymd(2021, 3, 21).and_hms(11, 0, 0);
+        booking.occurrence_count = Some(2);
+
+        let occurrences = rent_series::expand_occurrences(&booking, RecurrenceFrequency::Weekly).unwrap();
+        assert_eq!(occurrences[1].start_timestamp, NaiveDate::from_ymd(2021, 3, 28).and_hms(8, 0, 0));
+        assert_eq!(occurrences[1].end_timestamp, NaiveDate::from_ymd(2021, 3, 28).and_hms(10, 0, 0));
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_insert_recurring_booking_reports_conflicts() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let (bike, token) = setup_database(&db);
+        let other_token = insert_into(tokens::table).default_values().get_result::<Token>(&*db).unwrap();
+
+        insert_into(rents::table)
+            .values(&InsertRent {
+                token_id: other_token.id,
+                bike_id: bike.id,
+                start_timestamp: timestamp(8, 16),
+                end_timestamp: timestamp(8, 20),
+            })
+            .execute(&*db)
+            .unwrap();
+
+        let result = rent_series::insert_recurring_booking(&db, &recurring_booking(&token, &bike)).unwrap();
+
+        assert_eq!(result.series.series.frequency, "weekly");
+        assert_eq!(result.series.rents.iter().map(|r| r.start_timestamp).collect::<Vec<_>>(), vec![timestamp(1, 14), timestamp(15, 14), timestamp(22, 14)]);
+        assert_eq!(result.conflicts.len(), 1);
+        assert_eq!(result.conflicts[0].start_timestamp, timestamp(8, 14));
+
+        assert_eq!(rent_series::get_series_by_token(&db, &token.uuid).unwrap().rents.len(), 3);
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_insert_recurring_booking_fails_without_free_occurrence() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let (bike, token) = setup_database(&db);
+        let other_token = insert_into(tokens::table).default_values().get_result::<Token>(&*db).unwrap();
+
+        insert_into(rents::table)
+            .values(&InsertRent {
+                token_id: other_token.id,
+                bike_id: bike.id,
+                start_timestamp: timestamp(1, 0),
+                end_timestamp: timestamp(30, 0),
+            })
+            .execute(&*db)
+            .unwrap();
+
+        assert!(rent_series::insert_recurring_booking(&db, &recurring_booking(&token, &bike)).is_err());
+        assert!(rent_series::get_series_by_token(&db, &token.uuid).is_err());
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_revoke_occurrence() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let (bike, token) = setup_database(&db);
+        let other_token = insert_into(tokens::table).default_values().get_result::<Token>(&*db).unwrap();
+
+        let result = rent_series::insert_recurring_booking(&db, &recurring_booking(&token, &bike)).unwrap();
+        let rent_id = result.series.rents[1].id;
+
+        assert!(rent_series::revoke_occurrence(&db, &other_token.uuid, rent_id).is_err());
+        assert!(rent_series::revoke_occurrence(&db, &token.uuid, rent_id).is_ok());
+        assert!(rent_series::revoke_occurrence(&db, &token.uuid, rent_id).is_err());
+
+        let revoked = rent_series::get_series_by_token(&db, &token.uuid).unwrap().rents.iter()
+            .filter(|rent| rent.revocation_timestamp.is_some())
+            .map(|rent| rent.id)
+            .collect::<Vec<_>>();
+        assert_eq!(revoked, vec![rent_id]);
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_insert_recurring_booking_takes_hold() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let (bike, token) = setup_database(&db);
+        let other_token = insert_into(tokens::table).default_values().get_result::<Token>(&*db).unwrap();
+
+        let held = hold::create_hold(&db, &HoldRequest {
+            token: token.uuid,
+            bike_id: bike.id,
+            start_timestamp: timestamp(1, 14),
+            end_timestamp: timestamp(1, 18),
+            accessories: vec![],
+        }).unwrap();
+
+        let result = rent_series::insert_recurring_booking(&db, &recurring_booking(&other_token, &bike)).unwrap();
+        assert_eq!(result.conflicts.iter().map(|o| o.start_timestamp).collect::<Vec<_>>(), vec![timestamp(1, 14)]);
+
+        diesel::update(rents::table)
+            .set(rents::revocation_timestamp.eq(Utc::now().naive_utc()))
+            .execute(&*db)
+            .unwrap();
+
+        let mut booking = recurring_booking(&token, &bike);
+        booking.hold_id = Some(held.uuid);
+
+        let result = rent_series::insert_recurring_booking(&db, &booking).unwrap();
+        assert_eq!(result.series.rents.len(), 4);
+        assert!(result.conflicts.is_empty());
+
+        Ok(())
+    });
+}
@@ -0,0 +1,75 @@
+use chrono::{Duration,Utc};
+
+use diesel::Connection;
+
+use crate::database::daos::supporter;
+use crate::database::models::{InsertSupporter,InsertSupporterTranslatable,InsertSupporterType};
+use crate::locale::LocalePreferences;
+
+fn insert_supporter(db: &crate::database::DbConn, supporter_type_id: i32, sort_priority: i32, title: &str, start_date: Option<chrono::NaiveDate>, end_date: Option<chrono::NaiveDate>) {
+    let inserted = supporter::insert_supporter(db, &InsertSupporter {
+        supporter_type_id: Some(supporter_type_id),
+        sort_priority,
+        start_date,
+        end_date,
+    }).unwrap();
+
+    supporter::upsert_supporter_translation(db, &InsertSupporterTranslatable {
+        supporter_id: inserted.id,
+        locale: "de-DE".to_string(),
+        title: title.to_string(),
+        description: None,
+        url: None,
+        logo_url: None,
+        logo_width: None,
+        logo_height: None,
+        logo_alt_text: None,
+    }).unwrap();
+}
+
+#[test]
+pub fn test_get_supporters_orders_and_filters_by_period() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let today = Utc::today().naive_utc();
+        let hardware = supporter::insert_supporter_type(&db, &InsertSupporterType { title: "Hardware".to_string(), sort_priority: 10 }).unwrap();
+        let software = supporter::insert_supporter_type(&db, &InsertSupporterType { title: "Software".to_string(), sort_priority: 20 }).unwrap();
+
+        insert_supporter(&db, hardware.id, 1, "Hardware low", None, None);
+        insert_supporter(&db, hardware.id, 5, "Hardware high", Some(today), Some(today));
+        insert_supporter(&db, software.id, 0, "Software", None, Some(today + Duration::days(7)));
+        insert_supporter(&db, software.id, 9, "Expired", None, Some(today - Duration::days(1)));
+        insert_supporter(&db, software.id, 9, "Upcoming", Some(today + Duration::days(1)), None);
+
+        let preferences = LocalePreferences::new(vec![]);
+        let titles = |supporter_type| supporter::get_supporters(&db, &preferences, supporter_type).unwrap()
+            .into_iter()
+            .map(|s| s.title)
+            .collect::<Vec<_>>();
+
+        assert_eq!(titles(None), vec!["Software", "Hardware high", "Hardware low"]);
+        assert_eq!(titles(Some("Hardware")), vec!["Hardware high", "Hardware low"]);
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_update_supporter_logo() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let hardware = supporter::insert_supporter_type(&db, &InsertSupporterType { title: "Hardware".to_string(), sort_priority: 0 }).unwrap();
+        insert_supporter(&db, hardware.id, 0, "Sponsor", None, None);
+        let inserted = supporter::get_all_supporters(&db).unwrap().pop().unwrap();
+
+        let updated = supporter::update_supporter_logo(&db, inserted.id, "de-DE", "/uploads/logos/a-web.png", 400, 100).unwrap();
+
+        assert_eq!(updated.logo_url, Some("/uploads/logos/a-web.png".to_string()));
+        assert_eq!((updated.logo_width, updated.logo_height), (Some(400), Some(100)));
+        assert!(supporter::update_supporter_logo(&db, inserted.id, "en-GB", "/uploads/logos/a-web.png", 400, 100).is_err());
+
+        Ok(())
+    });
+}
@@ -0,0 +1,69 @@
+use chrono::prelude::*;
+
+use diesel::{RunQueryDsl,QueryDsl,ExpressionMethods,Connection};
+use diesel::{insert_into,update};
+
+use crate::database::models::{Bike,InsertRent,Token};
+use crate::schema::bikes::dsl::*;
+use crate::schema::rents::dsl::*;
+use crate::schema::tokens::dsl::*;
+
+#[test]
+pub fn test_insert_token() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let token = crate::database::daos::token::insert_token(&db).unwrap();
+
+        let expected: i64 = 1;
+        let actual: i64 = tokens.filter(crate::schema::tokens::dsl::id.eq(token.id))
+            .count()
+            .get_result::<i64>(&*db)
+            .unwrap();
+
+        assert_eq!(actual, expected);
+
+        Ok(())
+    });
+}
+
+
+#[test]
+pub fn test_delete_stale_tokens_keeps_used_and_fresh_tokens() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let epoch = DateTime::parse_from_rfc3339(&"1970-01-01T00:00:00.000Z".to_string()).unwrap().naive_utc();
+
+        let bike1 = insert_into(bikes).default_values().get_result::<Bike>(&*db).unwrap();
+        let used_token = insert_into(tokens).default_values().get_result::<Token>(&*db).unwrap();
+        let stale_token = insert_into(tokens).default_values().get_result::<Token>(&*db).unwrap();
+        let fresh_token = insert_into(tokens).default_values().get_result::<Token>(&*db).unwrap();
+
+        update(tokens.filter(crate::schema::tokens::dsl::id.eq_any(vec![used_token.id, stale_token.id])))
+            .set(crate::schema::tokens::dsl::created_at.eq(epoch))
+            .execute(&*db)
+            .unwrap();
+
+        insert_into(rents).values(InsertRent {
+            token_id: used_token.id,
+            bike_id: bike1.id,
+            start_timestamp: epoch,
+            end_timestamp: epoch,
+        }).execute(&*db).unwrap();
+
+        let actual = crate::database::daos::token::delete_stale_tokens(&db).unwrap();
+
+        assert_eq!(actual, 1);
+
+        let remaining = tokens.select(crate::schema::tokens::dsl::id)
+            .filter(crate::schema::tokens::dsl::id.eq_any(vec![used_token.id, stale_token.id, fresh_token.id]))
+            .order_by(crate::schema::tokens::dsl::id)
+            .load::<i32>(&*db)
+            .unwrap();
+
+        assert_eq!(remaining, vec![used_token.id, fresh_token.id]);
+
+        Ok(())
+    });
+}
\ No newline at end of file
@@ -0,0 +1,75 @@
+use std::env;
+
+use diesel::{RunQueryDsl,Connection};
+use diesel::{insert_into};
+
+use crate::database::daos::translatable;
+use crate::database::daos::translatable::BikeTranslations;
+use crate::database::models::{Bike,InsertBikeTranslatable,MissingTranslations};
+use crate::locale::LocalePreferences;
+use crate::schema::bikes::dsl::*;
+
+#[test]
+pub fn test_upsert_translation_replaces_existing_locale() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike1 = insert_into(bikes).default_values().get_result::<Bike>(&*db).unwrap();
+        let mut translation = InsertBikeTranslatable {
+            bike_id: bike1.id,
+            locale: "de-DE".to_string(),
+            title: "Lastenrad".to_string(),
+            description: Some("Alt".to_string()),
+            url: None,
+        };
+
+        let inserted = translatable::upsert_translation::<BikeTranslations>(&db, &translation).unwrap();
+
+        translation.title = "Lastenrad 2".to_string();
+        translation.description = None;
+
+        let updated = translatable::upsert_translation::<BikeTranslations>(&db, &translation).unwrap();
+
+        assert_eq!(updated.id, inserted.id);
+        assert_eq!(updated.title, "Lastenrad 2");
+        assert_eq!(updated.description, None);
+
+        let actual = translatable::load_one_by_locale::<BikeTranslations>(&db, bike1.id, &LocalePreferences::new(vec![])).unwrap();
+
+        assert_eq!(actual.map(|t| t.title), Some("Lastenrad 2".to_string()));
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_missing_locales() {
+    env::set_var("SUPPORTED_LOCALES", "de-DE,en-GB");
+
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike1 = insert_into(bikes).default_values().get_result::<Bike>(&*db).unwrap();
+        let bike2 = insert_into(bikes).default_values().get_result::<Bike>(&*db).unwrap();
+        let bike3 = insert_into(bikes).default_values().get_result::<Bike>(&*db).unwrap();
+
+        for (bike, locale) in vec![(&bike1, "de-DE"), (&bike1, "en-GB"), (&bike2, "de-DE")] {
+            translatable::upsert_translation::<BikeTranslations>(&db, &InsertBikeTranslatable {
+                bike_id: bike.id,
+                locale: locale.to_string(),
+                title: "Bike".to_string(),
+                description: None,
+                url: None,
+            }).unwrap();
+        }
+
+        let actual = translatable::missing_locales::<BikeTranslations>(&db).unwrap();
+
+        assert_eq!(actual, vec![
+            MissingTranslations { parent_id: bike2.id, locales: vec!["en-GB".to_string()] },
+            MissingTranslations { parent_id: bike3.id, locales: vec!["de-DE".to_string(), "en-GB".to_string()] },
+        ]);
+
+        Ok(())
+    });
+}
@@ -0,0 +1,111 @@
+use chrono::{Duration,Utc};
+
+use diesel::Connection;
+
+use mocktopus::mocking::Mockable;
+use mocktopus::mocking::MockResult;
+
+use crate::config;
+use crate::database::daos::{rent,verification};
+use crate::database::models::Booking;
+use crate::routes::errors::RentError;
+use crate::tests::database::{booking,setup_database,tomorrow};
+
+#[test]
+pub fn test_verified_booking_is_kept() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let (bike, token, _) = setup_database(&db);
+
+        let pending = rent::insert_booking(&db, &Booking {
+            short_token: "ABC123".to_string(),
+            ..booking(&token, &bike, tomorrow(9), tomorrow(11), Some("someone@somewhere.near"))
+        }).unwrap().verification.unwrap();
+        assert_eq!(pending.status, "pending");
+        assert_eq!(pending.short_token, Some("ABC123".to_string()));
+        assert!(!pending.encrypted_email.contains("someone"));
+        assert_eq!(verification::get_verified_email(&db, token.id).unwrap(), None);
+
+        let (verified, rents) = verification::verify_email(&db, &pending.uuid).unwrap();
+        assert_eq!(verified.status, "verified");
+        assert!(verified.verified_at.is_some());
+        assert_eq!(rents.len(), 1);
+        assert_eq!(verification::get_verified_email(&db, token.id).unwrap(), Some("someone@somewhere.near".to_string()));
+
+        assert!(verification::verify_email(&db, &pending.uuid).is_err());
+        assert_eq!(verification::lapse_unverified_bookings(&db, tomorrow(10)).unwrap(), 0);
+        assert!(rent::get_rent(&db, rents[0].id).unwrap().revocation_timestamp.is_none());
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_unverified_booking_lapses_and_frees_slot() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let (bike, token, other_token) = setup_database(&db);
+
+        let pending = rent::insert_booking(&db, &booking(&token, &bike, tomorrow(9), tomorrow(11), Some("someone@somewhere.near"))).unwrap().verification.unwrap();
+        assert!(rent::insert_booking(&db, &booking(&other_token, &bike, tomorrow(9), tomorrow(11), None)).is_err());
+
+        assert_eq!(verification::lapse_unverified_bookings(&db, pending.expires_at - Duration::minutes(1)).unwrap(), 0);
+        assert_eq!(verification::lapse_unverified_bookings(&db, pending.expires_at).unwrap(), 1);
+        assert!(verification::verify_email(&db, &pending.uuid).is_err());
+
+        let receipt = rent::insert_booking(&db, &booking(&other_token, &bike, tomorrow(9), tomorrow(11), None)).unwrap();
+        assert!(receipt.verification.is_none());
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_imminent_booking_gets_grace_period() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let (bike, token, _) = setup_database(&db);
+        config::verification_grace_minutes.mock_safe(|| MockResult::Return(15));
+
+        let mut imminent = booking(&token, &bike, tomorrow(9), tomorrow(11), Some("someone@somewhere.near"));
+        imminent.start_timestamp = Utc::now().naive_utc() - Duration::minutes(5);
+
+        let pending = rent::insert_booking(&db, &imminent).unwrap().verification.unwrap();
+        assert!(pending.expires_at > Utc::now().naive_utc() + Duration::minutes(14));
+        assert_eq!(verification::lapse_unverified_bookings(&db, Utc::now().naive_utc()).unwrap(), 0);
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_address_is_needed_to_be_sealed() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let (bike, token, _) = setup_database(&db);
+        config::rider_hash_secret.mock_safe(|| MockResult::Return(None));
+
+        assert!(rent::insert_booking(&db, &booking(&token, &bike, tomorrow(9), tomorrow(11), Some("someone@somewhere.near"))).is_err());
+        assert!(rent::insert_booking(&db, &booking(&token, &bike, tomorrow(9), tomorrow(11), None)).is_ok());
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_invalid_address_is_rejected() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let (bike, token, _) = setup_database(&db);
+
+        assert!(matches!(rent::insert_booking(&db, &booking(&token, &bike, tomorrow(9), tomorrow(11), Some("nobody"))), Err(RentError::Validation(_))));
+        assert!(rent::insert_booking(&db, &booking(&token, &bike, tomorrow(9), tomorrow(11), Some("someone@somewhere.near"))).is_ok());
+
+        Ok(())
+    });
+}
@@ -0,0 +1,160 @@
+use chrono::Utc;
+
+use diesel::{RunQueryDsl,Connection,ExpressionMethods};
+use diesel::insert_into;
+
+use mocktopus::mocking::Mockable;
+use mocktopus::mocking::MockResult;
+
+use crate::config;
+use crate::database::DbConn;
+use crate::database::daos::{availability,rent,verification,waitlist};
+use crate::database::models::{Bike,Booking,InsertRent,WaitlistRequest};
+use crate::schema::rents;
+use crate::tests::database::{booking,insert_bike,insert_token,tomorrow};
+
+fn setup_database(db: &DbConn, confirmed: bool) -> Bike {
+    config::rider_hash_secret.mock_safe(|| MockResult::Return(Some("test secret".to_string())));
+
+    let bike = insert_bike(db);
+    let token = insert_token(db);
+
+    insert_into(rents::table)
+        .values(&InsertRent {
+            token_id: token.id,
+            bike_id: bike.id,
+            start_timestamp: tomorrow(8),
+            end_timestamp: tomorrow(18),
+        })
+        .execute(&**db)
+        .unwrap();
+
+    for (start, end, email) in &[(8, 12, "first@somewhere.near"), (10, 14, "second@somewhere.near")] {
+        let token = insert_token(db);
+        let (_, pending) = waitlist::insert_waitlist_entry(db, &WaitlistRequest {
+            token: token.uuid,
+            bike_id: bike.id,
+            start_timestamp: tomorrow(*start),
+            end_timestamp: tomorrow(*end),
+            email: email.to_string(),
+        }).unwrap();
+
+        if confirmed {
+            verification::verify_email(db, &pending.uuid).unwrap();
+        }
+    }
+
+    bike
+}
+
+#[test]
+pub fn test_offer_free_slots_in_order() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike = setup_database(&db, true);
+
+        assert_eq!(waitlist::offer_free_slots(&db, Utc::now().naive_utc()).unwrap().len(), 0);
+
+        diesel::update(rents::table)
+            .set(rents::revocation_timestamp.eq(Utc::now().naive_utc()))
+            .execute(&*db)
+            .unwrap();
+
+        let offers = waitlist::offer_free_slots(&db, Utc::now().naive_utc()).unwrap();
+        assert_eq!(offers.len(), 1);
+        assert_eq!(verification::get_verified_email(&db, offers[0].0.token_id).unwrap(), Some("first@somewhere.near".to_string()));
+        assert_eq!(offers[0].1.waitlist_entry_id, Some(offers[0].0.id));
+
+        assert!(!availability::is_bike_available(&db, bike.id, tomorrow(10), tomorrow(14)).unwrap());
+        assert_eq!(waitlist::offer_free_slots(&db, Utc::now().naive_utc()).unwrap().len(), 0);
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_book_held_slot() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike = setup_database(&db, true);
+
+        diesel::update(rents::table)
+            .set(rents::revocation_timestamp.eq(Utc::now().naive_utc()))
+            .execute(&*db)
+            .unwrap();
+
+        let offers = waitlist::offer_free_slots(&db, Utc::now().naive_utc()).unwrap();
+        let token = insert_token(&db);
+
+        assert!(rent::insert_booking(&db, &booking(&token, &bike, tomorrow(8), tomorrow(12), None)).is_err());
+        assert!(rent::insert_booking(&db, &Booking { hold_id: Some(token.uuid), ..booking(&token, &bike, tomorrow(8), tomorrow(12), None) }).is_err());
+        assert!(rent::insert_booking(&db, &Booking { hold_id: Some(offers[0].1.uuid), ..booking(&token, &bike, tomorrow(8), tomorrow(12), None) }).is_ok());
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_unconfirmed_entries_are_not_offered() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        setup_database(&db, false);
+
+        diesel::update(rents::table)
+            .set(rents::revocation_timestamp.eq(Utc::now().naive_utc()))
+            .execute(&*db)
+            .unwrap();
+
+        assert_eq!(waitlist::offer_free_slots(&db, Utc::now().naive_utc()).unwrap().len(), 0);
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_waitlist_spends_the_token() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike = setup_database(&db, false);
+        let token = insert_token(&db);
+        let request = WaitlistRequest {
+            token: token.uuid,
+            bike_id: bike.id,
+            start_timestamp: tomorrow(8),
+            end_timestamp: tomorrow(12),
+            email: "someone@somewhere.near".to_string(),
+        };
+
+        assert!(waitlist::insert_waitlist_entry(&db, &request).is_ok());
+        assert!(waitlist::insert_waitlist_entry(&db, &request).is_err());
+        assert!(rent::insert_booking(&db, &booking(&token, &bike, tomorrow(8), tomorrow(12), None)).is_err());
+
+        Ok(())
+    });
+}
+
+#[test]
+pub fn test_waitlist_rejects_invalid_address() {
+    let db = crate::tests::get_database_connection();
+
+    db.test_transaction::<_, diesel::result::Error, _>(|| {
+        let bike = setup_database(&db, false);
+        let token = insert_token(&db);
+        let request = WaitlistRequest {
+            token: token.uuid,
+            bike_id: bike.id,
+            start_timestamp: tomorrow(8),
+            end_timestamp: tomorrow(12),
+            email: "nobody".to_string(),
+        };
+
+        assert!(waitlist::insert_waitlist_entry(&db, &request).is_err());
+        assert!(rent::insert_booking(&db, &booking(&token, &bike, tomorrow(19), tomorrow(20), None)).is_ok());
+
+        Ok(())
+    });
+}
@@ -0,0 +1,131 @@
+use chrono::{DateTime,Duration,LocalResult,NaiveDate,NaiveDateTime,SecondsFormat,TimeZone,Utc};
+use chrono_tz::Tz;
+
+use crate::config;
+
+/// The stored UTC timestamp as wall-clock time of the deployment timezone.
+pub fn to_local(timestamp: NaiveDateTime) -> DateTime<Tz> {
+    config::timezone().from_utc_datetime(&timestamp)
+}
+
+/// The UTC timestamp of a wall-clock time of the deployment timezone. A time
+/// repeated when the clocks go back is taken at its first occurrence, a time
+/// skipped when they go forward is moved past the gap.
+pub fn from_local(local: NaiveDateTime) -> NaiveDateTime {
+    match config::timezone().from_local_datetime(&local) {
+        LocalResult::Single(timestamp) | LocalResult::Ambiguous(timestamp, _) => timestamp.naive_utc(),
+        LocalResult::None => from_local(local + Duration::hours(1)),
+    }
+}
+
+/// The current date in the deployment timezone.
+pub fn today() -> NaiveDate {
+    to_local(Utc::now().naive_utc()).naive_local().date()
+}
+
+/// Accepts RFC 3339 timestamps as well as timestamps without an offset, which
+/// are wall-clock times of the deployment timezone.
+pub fn parse_timestamp(value: &str) -> Result<NaiveDateTime, String> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|timestamp| timestamp.naive_utc())
+        .or_else(|_| value.parse::<NaiveDateTime>().map(from_local))
+        .map_err(|_| format!("'{}' is not a valid timestamp.", value))
+}
+
+/// RFC 3339 in the deployment timezone, e.g. `2021-05-01T10:00:00+02:00`.
+pub fn format_timestamp(timestamp: NaiveDateTime) -> String {
+    to_local(timestamp).to_rfc3339_opts(SecondsFormat::AutoSi, false)
+}
+
+/// Serde format of stored timestamps, used with `#[serde(with = "crate::timezone::timestamp")]`.
+pub mod timestamp {
+    use chrono::NaiveDateTime;
+    use serde::{de,Deserialize,Deserializer,Serializer};
+
+    pub fn serialize<S: Serializer>(timestamp: &NaiveDateTime, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::format_timestamp(*timestamp))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDateTime, D::Error> {
+        super::parse_timestamp(&String::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+/// Like `timestamp`, for optional timestamps.
+pub mod optional_timestamp {
+    use chrono::NaiveDateTime;
+    use serde::{de,Deserialize,Deserializer,Serializer};
+
+    pub fn serialize<S: Serializer>(timestamp: &Option<NaiveDateTime>, serializer: S) -> Result<S::Ok, S::Error> {
+        match timestamp {
+            Some(timestamp) => serializer.serialize_some(&super::format_timestamp(*timestamp)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<NaiveDateTime>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|value| super::parse_timestamp(&value).map_err(de::Error::custom))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use mocktopus::mocking::Mockable;
+    use mocktopus::mocking::MockResult;
+
+    use chrono::NaiveDate;
+    use chrono_tz::Tz;
+
+    use crate::config;
+    use crate::database::models::BookingGranularity;
+
+    fn setup() {
+        config::timezone.mock_safe(|| MockResult::Return(Tz::Europe__Berlin));
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        setup();
+
+        let expected = NaiveDate::from_ymd(2021, 5, 1).and_hms(8, 0, 0);
+
+        assert_eq!(super::parse_timestamp("2021-05-01T10:00:00"), Ok(expected));
+        assert_eq!(super::parse_timestamp("2021-05-01T10:00:00+02:00"), Ok(expected));
+        assert_eq!(super::parse_timestamp("2021-05-01T08:00:00Z"), Ok(expected));
+        assert!(super::parse_timestamp("2021-05-01").is_err());
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        setup();
+
+        assert_eq!(super::format_timestamp(NaiveDate::from_ymd(2021, 5, 1).and_hms(8, 0, 0)), "2021-05-01T10:00:00+02:00");
+        assert_eq!(super::format_timestamp(NaiveDate::from_ymd(2021, 1, 1).and_hms(8, 0, 0)), "2021-01-01T09:00:00+01:00");
+    }
+
+    #[test]
+    fn test_from_local_across_daylight_saving_changes() {
+        setup();
+
+        assert_eq!(super::from_local(NaiveDate::from_ymd(2021, 3, 28).and_hms(2, 30, 0)), NaiveDate::from_ymd(2021, 3, 28).and_hms(1, 30, 0));
+        assert_eq!(super::from_local(NaiveDate::from_ymd(2021, 10, 31).and_hms(2, 30, 0)), NaiveDate::from_ymd(2021, 10, 31).and_hms(0, 30, 0));
+    }
+
+    #[test]
+    fn test_slots_follow_the_local_calendar() {
+        setup();
+
+        let day = BookingGranularity::Day.slots(NaiveDate::from_ymd(2021, 5, 1), 12);
+        assert_eq!(day.len(), 1);
+        assert_eq!(day[0].start_timestamp, NaiveDate::from_ymd(2021, 4, 30).and_hms(22, 0, 0));
+        assert_eq!(day[0].end_timestamp, NaiveDate::from_ymd(2021, 5, 1).and_hms(22, 0, 0));
+
+        assert_eq!(BookingGranularity::Hourly.slots(NaiveDate::from_ymd(2021, 3, 28), 12).len(), 23);
+        assert_eq!(BookingGranularity::Hourly.slots(NaiveDate::from_ymd(2021, 5, 1), 12).len(), 24);
+
+        let snapped = BookingGranularity::Day.snap(NaiveDate::from_ymd(2021, 4, 30).and_hms(23, 0, 0), NaiveDate::from_ymd(2021, 5, 1).and_hms(10, 0, 0), 12);
+        assert_eq!(snapped, (day[0].start_timestamp, day[0].end_timestamp));
+    }
+}